@@ -0,0 +1,223 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{CountSink, GrepEngine, GrepFlags, GrepOptions, MatchSink};
+
+const NEEDLE: &[u8] = b"simd-grep";
+
+/// `len` random lowercase bytes; never contains `NEEDLE` since it has a '-'.
+fn random_blob(len: usize, seed: u64) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len).map(|_| rng.gen_range(b'a'..=b'z')).collect()
+}
+
+/// A random blob with `NEEDLE` planted every `every` bytes.
+fn planted(len: usize, every: usize) -> Vec<u8> {
+    let mut buf = random_blob(len, 7);
+    let mut at = every / 2;
+    while at + NEEDLE.len() <= buf.len() {
+        buf[at..at + NEEDLE.len()].copy_from_slice(NEEDLE);
+        at += every;
+    }
+    buf
+}
+
+fn bench_find(c: &mut Criterion) {
+    let miss = random_blob(16 << 20, 1);
+    let mut hit = miss.clone();
+    let at = hit.len() - 1024;
+    hit[at..at + NEEDLE.len()].copy_from_slice(NEEDLE);
+
+    let mut group = c.benchmark_group("find_16MiB");
+    group.throughput(Throughput::Bytes(miss.len() as u64));
+    group.bench_function("miss", |b| {
+        b.iter(|| simd_grep::find(black_box(&miss), NEEDLE))
+    });
+    group.bench_function("hit_near_end", |b| {
+        b.iter(|| simd_grep::find(black_box(&hit), NEEDLE))
+    });
+    group.finish();
+}
+
+fn bench_single_byte(c: &mut Criterion) {
+    let mut data = random_blob(16 << 20, 3);
+    let at = data.len() - 1024;
+    data[at] = b'#';
+
+    let mut group = c.benchmark_group("single_byte_16MiB");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("find", |b| {
+        b.iter(|| simd_grep::find(black_box(&data), b"#"))
+    });
+    group.bench_function("contains", |b| {
+        b.iter(|| simd_grep::contains(black_box(&data), b"#"))
+    });
+    group.bench_function("memmem", |b| {
+        b.iter(|| memchr::memmem::find(black_box(&data), b"#"))
+    });
+    group.finish();
+}
+
+fn bench_short_needle(c: &mut Criterion) {
+    // Sparse: the first byte of the needle is rare. Dense: every byte is the
+    // needle's first byte, where jumping between occurrences of it with
+    // `memchr` (once used for 2 and 3 byte needles) was ~200x slower than memmem.
+    let mut sparse = random_blob(16 << 20, 5);
+    let mut dense = vec![b'a'; 16 << 20];
+    let at = sparse.len() - 1024;
+    sparse[at..at + 2].copy_from_slice(b"#!");
+    dense[at..at + 2].copy_from_slice(b"a!");
+
+    let mut group = c.benchmark_group("short_needle_16MiB");
+    group.throughput(Throughput::Bytes(sparse.len() as u64));
+    for (name, data, needle) in [("sparse", &sparse, b"#!"), ("dense", &dense, b"a!")] {
+        group.bench_with_input(BenchmarkId::new("find", name), data, |b, data| {
+            b.iter(|| simd_grep::find(black_box(data), needle))
+        });
+        group.bench_with_input(BenchmarkId::new("memmem", name), data, |b, data| {
+            b.iter(|| memchr::memmem::find(black_box(data), needle))
+        });
+        let engine = GrepEngine::new_literal(needle, GrepOptions::default());
+        group.bench_with_input(BenchmarkId::new("engine", name), data, |b, data| {
+            b.iter(|| {
+                let mut sink = CountSink::default();
+                engine.search_slice(black_box(data), &mut sink).unwrap();
+                sink.total
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_search(c: &mut Criterion) {
+    let corpora = [
+        ("sparse", planted(32 << 20, 1 << 20)),
+        ("dense", planted(32 << 20, 64)),
+    ];
+    let mut group = c.benchmark_group("search_32MiB");
+    group.sample_size(10);
+    for (name, data) in &corpora {
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for chunk_bytes in [64 << 10, 1 << 20, 8 << 20] {
+            let opts = GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            };
+            let engine = GrepEngine::new_literal(NEEDLE, opts);
+            let id = BenchmarkId::new(*name, format!("{}KiB", chunk_bytes >> 10));
+            group.bench_with_input(id, data, |b, data| {
+                b.iter(|| {
+                    let mut sink = CountSink::default();
+                    engine.search(&mut &data[..], &mut sink).unwrap();
+                    sink.total
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_prefilter(c: &mut Criterion) {
+    let corpora = [
+        ("sparse", planted(32 << 20, 1 << 20)),
+        ("dense", planted(32 << 20, 64)),
+    ];
+    let mut group = c.benchmark_group("prefilter_32MiB");
+    group.sample_size(10);
+    for (name, data) in &corpora {
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for (mode, flags) in [("on", GrepFlags::empty()), ("off", GrepFlags::NO_PREFILTER)] {
+            let opts = GrepOptions {
+                flags,
+                ..Default::default()
+            };
+            let engine = GrepEngine::new_literal(NEEDLE, opts);
+            group.bench_with_input(BenchmarkId::new(*name, mode), data, |b, data| {
+                b.iter(|| {
+                    let mut sink = CountSink::default();
+                    engine.search_slice(data, &mut sink).unwrap();
+                    sink.total
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_periodic(c: &mut Criterion) {
+    let data = vec![b'a'; 1 << 20];
+    let needle = b"aaaaaaaaaaaaaaaa";
+    let mut group = c.benchmark_group("periodic_run_1MiB");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    // Searching again one byte after every match, as before periodic needles
+    // were walked by their period.
+    group.bench_function("research", |b| {
+        b.iter(|| simd_grep::find_overlapping_iter(black_box(&data), needle).count())
+    });
+    // A bare counter, so the per-match cost of `CountSink`'s map doesn't hide
+    // the search.
+    struct Tally(u64);
+    impl MatchSink for Tally {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {
+            self.0 += 1;
+        }
+    }
+    let engine = GrepEngine::new_literal(needle, GrepOptions::default());
+    group.bench_function("engine", |b| {
+        b.iter(|| {
+            let mut sink = Tally(0);
+            engine.search_slice(black_box(&data), &mut sink).unwrap();
+            sink.0
+        })
+    });
+    group.finish();
+}
+
+fn bench_kmp(c: &mut Criterion) {
+    // A long needle in small chunks: the overlapping search scans the last
+    // 255 bytes of every 4 KiB chunk twice, about 6% more than the input.
+    // `search_kmp` scans each byte once.
+    let needle: Vec<u8> = random_blob(256, 11);
+    let mut data = random_blob(32 << 20, 5);
+    let at = data.len() / 2;
+    data[at..at + needle.len()].copy_from_slice(&needle);
+    let chunk_bytes = 4 << 10;
+
+    let opts = GrepOptions {
+        chunk_bytes,
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(&needle, opts);
+    let mut group = c.benchmark_group("no_rescan_32MiB");
+    group.sample_size(10);
+    // Both rates are per input byte, so they compare directly. The overlapping
+    // search scans 8192 chunks * 255 bytes = 2 MiB more than that.
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("overlap", |b| {
+        b.iter(|| {
+            let mut sink = CountSink::default();
+            engine.search(&mut &data[..], &mut sink).unwrap();
+            sink.total
+        })
+    });
+    group.bench_function("kmp", |b| {
+        b.iter(|| {
+            let mut sink = CountSink::default();
+            engine.search_kmp(&mut &data[..], &mut sink).unwrap();
+            sink.total
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_find,
+    bench_single_byte,
+    bench_short_needle,
+    bench_search,
+    bench_prefilter,
+    bench_periodic,
+    bench_kmp
+);
+criterion_main!(benches);
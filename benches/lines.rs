@@ -0,0 +1,34 @@
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use simd_grep::lines::count_newlines;
+
+/// 64 MiB of text with lines of varying length (32..=127 bytes).
+fn text_corpus() -> Vec<u8> {
+    let len = 64 << 20;
+    let mut buf = Vec::with_capacity(len);
+    let mut line = 0usize;
+    while buf.len() < len {
+        let width = 32 + (line * 37) % 96;
+        buf.extend((0..width).map(|i| b'a' + (i % 26) as u8));
+        buf.push(b'\n');
+        line += 1;
+    }
+    buf.truncate(len);
+    buf
+}
+
+fn naive_count(haystack: &[u8]) -> usize {
+    haystack.iter().filter(|&&b| b == b'\n').count()
+}
+
+fn bench_newlines(c: &mut Criterion) {
+    let corpus = text_corpus();
+    let mut group = c.benchmark_group("count_newlines_64MiB");
+    group.throughput(Throughput::Bytes(corpus.len() as u64));
+    group.sample_size(10);
+    group.bench_function("memchr", |b| b.iter(|| count_newlines(black_box(&corpus))));
+    group.bench_function("scalar", |b| b.iter(|| naive_count(black_box(&corpus))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_newlines);
+criterion_main!(benches);
@@ -1,20 +1,184 @@
-use std::io;
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::mem;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
-use memchr::memmem;
-use crate::io::chunker::Chunker;
+use crate::error::GrepError;
+use crate::io::chunker::{BufChunker, ChunkSource, Chunker, UTF8_BOM};
+use crate::io::output::ColorChoice;
+use crate::kmp::KmpSearcher;
+use crate::lines::{LineIndex, LineTracker, Separator};
+use crate::matcher::{LiteralMatcher, Matcher, MultiMatcher, RegexMatcher, RegexSetMatcher};
+use crate::skip::SkipRegions;
+use memchr::{memchr_iter, memrchr};
 
 bitflags::bitflags! {
     /// Flags to control grep engine behavior.
     ///
     /// These flags modify how the grep engine processes matches and outputs results.
-    /// Currently kept minimal at this stage.
-    #[derive(Clone, Debug)]
+    ///
+    /// `GrepEngine::new_literal` accepts any combination and resolves overlaps by
+    /// precedence: `REPORT_LINE_OFFSET`, `COUNT_LINES` and `UNIQUE_LINES` are
+    /// ignored without `LINE_NUMBER`, and `COUNT_ONLY` never suppresses reports
+    /// (counting is up to the sink). Use
+    /// `GrepEngine::try_new_literal` to reject such combinations instead.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub struct GrepFlags: u32 {
         /// Only count matches without reporting positions.
         const COUNT_ONLY = 1 << 0;
-        /// Include line numbers in match reports.
-        const LINE_NUMBER = 1 << 1; // TODO: placeholder for now
+        /// Include line numbers and columns in match reports (line-aware mode).
+        ///
+        /// A match containing `\n` is assigned the line and column of its start,
+        /// and line sinks are handed that start line only. Such matches are found
+        /// across chunk boundaries like any other, unless `NO_CROSS_LINE` is set.
+        const LINE_NUMBER = 1 << 1;
+        /// In line-aware mode, report the offset of the line containing each match as
+        /// `Match::off` (and the `off` argument of `MatchSink::on_match`) instead of the
+        /// match start. The match start stays available as `Match::match_off`.
+        const REPORT_LINE_OFFSET = 1 << 2;
+        /// Disable the `memmem` rare-byte prefilter. It speeds up sparse matches but
+        /// can slow down inputs dense in the needle's bytes. Applies to engines built
+        /// with `new_literal`; a `PreparedPattern` keeps the finder it was built with.
+        const NO_PREFILTER = 1 << 3;
+        /// In line-aware mode, report only the first match of each line, so a
+        /// counting sink counts matching lines as `grep -c` does. Unlike `COUNT_ONLY`,
+        /// this changes what is reported; the count passed to `on_finish` is the
+        /// number of matching lines.
+        const COUNT_LINES = 1 << 4;
+        /// In line-aware mode, report each matching line once, at its first match,
+        /// with the line passed to `MatchSink::on_match_line`. Reports the same
+        /// matches as `COUNT_LINES`, for sinks that display lines.
+        const UNIQUE_LINES = 1 << 5;
+        /// Drop matches that contain `\n`, so every report lies within one line.
+        /// A literal needle containing `\n` then never matches.
+        const NO_CROSS_LINE = 1 << 6;
+    }
+}
+
+impl GrepFlags {
+    /// Flags with only `COUNT_ONLY` set.
+    pub const fn count_only() -> Self {
+        Self::COUNT_ONLY
+    }
+
+    /// Flags with only `LINE_NUMBER` set.
+    pub const fn line_numbers() -> Self {
+        Self::LINE_NUMBER
+    }
+
+    /// Flags with `LINE_NUMBER` and `REPORT_LINE_OFFSET` set.
+    pub const fn line_offsets() -> Self {
+        Self::LINE_NUMBER.union(Self::REPORT_LINE_OFFSET)
+    }
+
+    /// Returns these flags with `other` added.
+    pub const fn with(self, other: Self) -> Self {
+        self.union(other)
+    }
+
+    /// Returns these flags with `other` removed.
+    pub const fn without(self, other: Self) -> Self {
+        self.difference(other)
+    }
+
+    /// Describes the first contradictory combination in these flags, if any.
+    pub fn conflict(&self) -> Option<&'static str> {
+        if self.contains(Self::REPORT_LINE_OFFSET) && !self.contains(Self::LINE_NUMBER) {
+            return Some("REPORT_LINE_OFFSET requires LINE_NUMBER");
+        }
+        if self.contains(Self::COUNT_LINES) && !self.contains(Self::LINE_NUMBER) {
+            return Some("COUNT_LINES requires LINE_NUMBER");
+        }
+        if self.contains(Self::UNIQUE_LINES) && !self.contains(Self::LINE_NUMBER) {
+            return Some("UNIQUE_LINES requires LINE_NUMBER");
+        }
+        if self.contains(Self::COUNT_ONLY) && self.contains(Self::REPORT_LINE_OFFSET) {
+            return Some("COUNT_ONLY reports no offsets, so REPORT_LINE_OFFSET has no effect");
+        }
+        None
+    }
+}
+
+/// Counts the characters of `bytes` decoded lossily, one per invalid sequence.
+fn char_count(bytes: &[u8]) -> usize {
+    bytes
+        .utf8_chunks()
+        .map(|chunk| chunk.valid().chars().count() + usize::from(!chunk.invalid().is_empty()))
+        .sum()
+}
+
+/// Copies the first `max` bytes of `line` and the truncation marker into `buf`.
+/// The cut backs off over at most three UTF-8 continuation bytes so that a
+/// character is not split.
+fn truncate_line<'b>(line: &[u8], max: usize, buf: &'b mut Vec<u8>) -> &'b [u8] {
+    let mut cut = max;
+    while cut > 0 && max - cut < 3 && (line[cut] & 0xC0) == 0x80 {
+        cut -= 1;
+    }
+    if (line[cut] & 0xC0) == 0x80 {
+        cut = max;
     }
+    buf.clear();
+    buf.extend_from_slice(&line[..cut]);
+    buf.extend_from_slice(TRUNCATION_MARKER);
+    buf
+}
+
+/// Builds the literal matcher for `needle`, honoring `GrepFlags::NO_PREFILTER`.
+fn literal_matcher<'n>(needle: &'n [u8], flags: &GrepFlags) -> LiteralMatcher<'n> {
+    LiteralMatcher::new(needle, !flags.contains(GrepFlags::NO_PREFILTER))
+}
+
+/// Unit used to count the column of a match within its line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Count bytes from the start of the line.
+    #[default]
+    Byte,
+    /// Count Unicode scalar values from the start of the line, as editors index
+    /// text. Invalid UTF-8 counts as the `U+FFFD`s `String::from_utf8_lossy`
+    /// would replace it with.
+    Char,
+}
+
+/// What path-based searches do when the input shrinks while being read.
+///
+/// A live log file can be truncated or rotated mid-search; the next read then
+/// returns end-of-file early. The policy decides how that is treated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TruncationPolicy {
+    /// End the search at the truncation point; matches found so far stand.
+    #[default]
+    Stop,
+    /// Fail with an `io::ErrorKind::UnexpectedEof` error.
+    Error,
+    /// Treat the input as rewritten from its start, as after a log rotation
+    /// that copies and truncates the file: seek back to offset 0 and keep
+    /// reading what is there now. Reported offsets keep counting on from the
+    /// bytes read before, so past that point they are no longer file offsets.
+    Continue,
+}
+
+/// A window of the input to search, instead of the whole stream.
+///
+/// Reported offsets stay absolute (relative to the start of the stream). Only
+/// matches lying entirely inside the window are reported: a match that starts
+/// inside but runs past the end is dropped, since those bytes are never read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    /// The first `n` bytes.
+    Head(u64),
+    /// The last `n` bytes. Requires a seekable source.
+    Tail(u64),
+    /// Bytes `[start, end)`. Requires a seekable source.
+    Span(u64, u64),
 }
 
 /// Configuration options for the grep engine.
@@ -26,6 +190,249 @@ pub struct GrepOptions {
     pub chunk_bytes: usize,
     pub flags: GrepFlags,
     pub file_id: u32,
+    /// Unit for reported columns in line-aware mode.
+    pub column_unit: ColumnUnit,
+    /// Stop the search after this many reported matches.
+    pub max_matches: Option<u64>,
+    /// In line-aware mode, report at most this many matches per line and then
+    /// skip to the next line. `max_matches` still applies on top.
+    pub max_matches_per_line: Option<u32>,
+    /// How path-based searches react to the file shrinking mid-read.
+    pub on_truncation: TruncationPolicy,
+    /// Bytes carried between chunks, overriding the default of `needle.len() - 1`.
+    ///
+    /// A larger value only costs rescanning; for a literal search a smaller one
+    /// would miss matches split across chunks, so searches fail with
+    /// `InvalidInput` instead. In line-aware mode it also sets how far back from
+    /// a chunk's end the line cut is placed.
+    pub overlap: Option<usize>,
+    /// Restrict the search to part of the input. `Tail` and `Span` need
+    /// [`GrepEngine::search_seekable`]. Line numbers count from the window start.
+    pub byte_range: Option<ByteRange>,
+    /// Start every streamed chunk on this boundary (a power of two, e.g. 64).
+    ///
+    /// The chunker then allocates its own aligned buffer per search, so the
+    /// buffer passed to [`GrepEngine::search_with_buffer`] is not used.
+    pub buffer_align: Option<usize>,
+    /// Classify every match as found in the carried or fresh part of its chunk
+    /// and report it through `MatchSink::on_match_region`.
+    pub debug_stitch: bool,
+    /// Give up once this instant has passed. Checked before reading each chunk,
+    /// so a streaming search fails with [`GrepError::Timeout`] (wrapped in an
+    /// `io::Error` of kind `TimedOut`) at most one chunk late. `search_slice`
+    /// and [`PushSearcher`] never time out.
+    pub deadline: Option<Instant>,
+    /// Report a match only when its stream offset is a multiple of this, e.g.
+    /// the record size of a binary format. Unaligned candidates are skipped
+    /// before any line bookkeeping.
+    pub match_stride: Option<u64>,
+    /// In line-aware mode, hand sinks at most this many bytes of each matching
+    /// line, followed by [`TRUNCATION_MARKER`]. The cut moves back rather than
+    /// split a UTF-8 character. Offsets and columns still describe the match.
+    pub max_columns: Option<usize>,
+    /// In line-aware mode, only newline-terminated lines count: bytes after the
+    /// last `\n` of the input are not a line and matches there are dropped. By
+    /// default they form the final line, numbered like any other.
+    pub require_final_newline: bool,
+    /// In line-aware mode, report only matches on lines `first..=last`
+    /// (1-based). Earlier lines are still scanned to count them; the search
+    /// stops once past `last`.
+    pub line_range: Option<(u32, u32)>,
+    /// Skip a UTF-8 byte order mark at the start of the input, so `^` anchors
+    /// match on the first line. Offsets then count from the first byte after
+    /// the BOM. Only a BOM at stream offset 0 is skipped; off by default since
+    /// the engine searches raw bytes.
+    pub strip_bom: bool,
+    /// Report a match only if it starts at least this many bytes after the
+    /// start of the last reported match, thinning out dense runs of hits.
+    /// Suppressed candidates are skipped like `match_stride` ones.
+    pub min_gap: Option<u64>,
+    /// In line-aware mode, what ends a line instead of `\n`: another byte, such
+    /// as `\0` for NUL-terminated records, or a byte sequence such as `\n---\n`
+    /// between YAML documents. Line numbers, columns, the line handed to sinks
+    /// and the options working per line then apply to these records; a
+    /// multi-byte separator straddling chunks is still found. The separator
+    /// ends the record before it, as `\n` does. Occurrences of a separator that
+    /// can overlap itself are taken left to right. Must not be empty.
+    pub record_separator: Option<Vec<u8>>,
+    /// Whether output built alongside these options is colored, to pass on to
+    /// e.g. [`TtySink::new`](crate::io::output::TtySink::new). The engine
+    /// itself ignores it.
+    pub color: ColorChoice,
+}
+
+/// Parses a positive byte count with an optional binary `K`/`M`/`G` suffix.
+fn parse_size(value: &str) -> Option<usize> {
+    let value = value.trim();
+    let (digits, shift) = match value.as_bytes().last()?.to_ascii_uppercase() {
+        b'K' => (&value[..value.len() - 1], 10),
+        b'M' => (&value[..value.len() - 1], 20),
+        b'G' => (&value[..value.len() - 1], 30),
+        _ => (value, 0),
+    };
+    let n = digits.trim_end().parse::<usize>().ok()?;
+    n.checked_mul(1 << shift).filter(|&n| n > 0)
+}
+
+/// Parses `always`, `never` or `auto`, ignoring case.
+fn parse_color(value: &str) -> Option<ColorChoice> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "always" => Some(ColorChoice::Always),
+        "never" => Some(ColorChoice::Never),
+        "auto" => Some(ColorChoice::Auto),
+        _ => None,
+    }
+}
+
+/// Appended to lines shortened by `GrepOptions::max_columns`.
+pub const TRUNCATION_MARKER: &[u8] = b"[... truncated]";
+
+impl GrepOptions {
+    /// Returns these options with `file_id`, e.g. an id from a
+    /// [`FileRegistry`](crate::io::FileRegistry).
+    pub fn with_file_id(mut self, file_id: u32) -> Self {
+        self.file_id = file_id;
+        self
+    }
+
+    /// Returns these options with `flags` replacing the current flags.
+    pub fn with_flags(mut self, flags: GrepFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns the default options updated from the environment, as
+    /// [`GrepOptions::with_env`] does.
+    pub fn from_env() -> Self {
+        Self::default().with_env()
+    }
+
+    /// Returns these options updated from environment variables, for command
+    /// line tools built on the engine:
+    ///
+    /// * `SIMD_GREP_CHUNK_BYTES` - `chunk_bytes`, a positive byte count with an
+    ///   optional `K`, `M` or `G` suffix (powers of 1024), e.g. `64K`
+    /// * `SIMD_GREP_COLOR` - `color`, one of `always`, `never` or `auto`
+    /// * `NO_COLOR` - when set and not empty, `color` becomes `Never`, following
+    ///   <https://no-color.org>. An explicit `SIMD_GREP_COLOR=always` still wins.
+    ///
+    /// Unset variables leave their option as it is, and so do values that don't
+    /// parse or aren't UTF-8 (case and surrounding whitespace are ignored), so a
+    /// bad setting in the environment can't make a tool fail.
+    pub fn with_env(mut self) -> Self {
+        if let Some(n) = env::var("SIMD_GREP_CHUNK_BYTES")
+            .ok()
+            .and_then(|v| parse_size(&v))
+        {
+            self.chunk_bytes = n;
+        }
+        let color = env::var("SIMD_GREP_COLOR")
+            .ok()
+            .and_then(|v| parse_color(&v));
+        let no_color = env::var("NO_COLOR").ok().is_some_and(|v| !v.is_empty());
+        match color {
+            Some(ColorChoice::Always) => self.color = ColorChoice::Always,
+            _ if no_color => self.color = ColorChoice::Never,
+            Some(color) => self.color = color,
+            None => {}
+        }
+        self
+    }
+
+    /// Checks these options for a pattern of `needle_len` bytes, rejecting
+    /// configurations that are contradictory or can never report anything.
+    ///
+    /// Searches run the same checks before reading anything, except for the
+    /// configurations they give a meaning to: conflicting flags, and
+    /// `max_matches_per_line` without `LINE_NUMBER`, follow the precedence
+    /// described on [`GrepFlags`], an `overlap` above `chunk_bytes` widens the
+    /// chunks, and `max_matches` of `Some(0)` reports nothing.
+    /// [`GrepEngine::try_new_literal`] runs the full check.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - When the options are consistent
+    /// * `Err(GrepError::Options(_))` - When `chunk_bytes` is 0, `overlap` exceeds
+    ///   `chunk_bytes` or is shorter than `needle_len - 1`, the flags conflict (see
+    ///   [`GrepFlags::conflict`]), `max_matches_per_line` is set without
+    ///   `LINE_NUMBER`, `max_matches` or `match_stride` is `Some(0)`, `line_range`
+    ///   is empty, or `buffer_align` is not a power of two
+    pub fn validate(&self, needle_len: usize) -> Result<(), GrepError> {
+        self.check_values(needle_len)?;
+        if let Some(n) = self.overlap
+            && n > self.chunk_bytes
+        {
+            return Err(GrepError::Options(format!(
+                "overlap {} exceeds chunk_bytes {}",
+                n, self.chunk_bytes
+            )));
+        }
+        if self.max_matches == Some(0) {
+            return Err(GrepError::Options(
+                "max_matches of 0 would never report a match".to_owned(),
+            ));
+        }
+        if let Some(msg) = self.flags.conflict() {
+            return Err(GrepError::Options(msg.to_owned()));
+        }
+        if self.max_matches_per_line.is_some() && !self.flags.contains(GrepFlags::LINE_NUMBER) {
+            return Err(GrepError::Options(
+                "max_matches_per_line requires LINE_NUMBER".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The part of [`GrepOptions::validate`] searches run: values no search can
+    /// make sense of.
+    fn check_values(&self, needle_len: usize) -> Result<(), GrepError> {
+        if self.chunk_bytes == 0 {
+            return Err(GrepError::Options(
+                "chunk_bytes must be at least 1".to_owned(),
+            ));
+        }
+        if let Some(n) = self.overlap {
+            let min = needle_len.saturating_sub(1);
+            if n < min {
+                return Err(GrepError::Options(format!(
+                    "overlap {} is shorter than the {} bytes the pattern needs",
+                    n, min
+                )));
+            }
+        }
+        if let Some((first, last)) = self.line_range
+            && (first == 0 || first > last)
+        {
+            return Err(GrepError::Options(format!(
+                "line_range {}..={} is empty; lines count from 1",
+                first, last
+            )));
+        }
+        if self.record_separator.as_ref().is_some_and(Vec::is_empty) {
+            return Err(GrepError::Options(
+                "record_separator must not be empty".to_owned(),
+            ));
+        }
+        if self.match_stride == Some(0) {
+            return Err(GrepError::Options(
+                "match_stride must be at least 1".to_owned(),
+            ));
+        }
+        if let Some(align) = self.buffer_align
+            && !align.is_power_of_two()
+        {
+            return Err(GrepError::Options(format!(
+                "buffer alignment {} is not a power of two",
+                align
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns what ends a line, `record_separator` or `\n`.
+    fn separator(&self) -> Separator {
+        Separator::new(self.record_separator.as_deref().unwrap_or(b"\n"))
+    }
 }
 
 impl Default for GrepOptions {
@@ -34,10 +441,225 @@ impl Default for GrepOptions {
             chunk_bytes: 8 * 1024 * 1024,
             flags: GrepFlags::empty(),
             file_id: 0,
+            column_unit: ColumnUnit::Byte,
+            max_matches: None,
+            max_matches_per_line: None,
+            on_truncation: TruncationPolicy::Stop,
+            overlap: None,
+            byte_range: None,
+            buffer_align: None,
+            debug_stitch: false,
+            deadline: None,
+            match_stride: None,
+            max_columns: None,
+            require_final_newline: false,
+            line_range: None,
+            strip_bom: false,
+            min_gap: None,
+            record_separator: None,
+            color: ColorChoice::Auto,
+        }
+    }
+}
+
+/// A literal pattern compiled once and shareable across engines and threads.
+///
+/// Building a `memmem::Finder` has a non-trivial setup cost (rare-byte selection,
+/// prefilter state). `PreparedPattern` owns the needle and its compiled finder
+/// behind an `Arc`, so cloning is a reference-count bump and the same compiled
+/// pattern can back any number of `GrepEngine`s via [`GrepEngine::from_prepared`].
+#[derive(Clone, Debug)]
+pub struct PreparedPattern {
+    matcher: Arc<LiteralMatcher<'static>>,
+}
+
+impl PreparedPattern {
+    /// Compiles `needle` into a reusable pattern. The needle bytes are copied.
+    pub fn new(needle: &[u8]) -> Self {
+        Self {
+            matcher: Arc::new(LiteralMatcher::new(needle, true).into_owned()),
+        }
+    }
+
+    /// Returns the needle this pattern was compiled from.
+    pub fn needle(&self) -> &[u8] {
+        self.matcher.needle()
+    }
+}
+
+/// A single match with all the metadata the engine knows about it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Match {
+    /// Global byte offset within the entire file/stream. With
+    /// `GrepFlags::REPORT_LINE_OFFSET` in line-aware mode, the offset of the line start.
+    pub off: u64,
+    /// Global byte offset of the match start, regardless of `REPORT_LINE_OFFSET`.
+    pub match_off: u64,
+    /// Match length (needle length).
+    pub len: u32,
+    /// 1-based line number (0 unless `GrepFlags::LINE_NUMBER` is set).
+    pub line_no: u32,
+    /// 1-based column of the match start within its line, in `GrepOptions::column_unit`
+    /// units (0 unless `GrepFlags::LINE_NUMBER` is set).
+    pub col: u32,
+    /// Caller-provided file identifier.
+    pub file_id: u32,
+    /// Index of the needle that matched, for engines built with
+    /// `GrepEngine::new_multi` or `GrepEngine::new_regex_set`; 0 otherwise.
+    pub pattern: u32,
+    /// How far the search had read when the match was reported: the stream
+    /// offset just past the last byte taken from the source. Buffering means
+    /// this usually runs ahead of `match_off + len`; it never decreases
+    /// within a search.
+    pub bytes_read: u64,
+}
+
+/// Renders a match as `file 0, line 3, offset 42, len 6`, for debugging. The
+/// offset is [`Match::off`].
+impl fmt::Display for Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "file {}, line {}, offset {}, len {}",
+            self.file_id, self.line_no, self.off, self.len
+        )
+    }
+}
+
+/// A checkpoint in a search, from [`GrepEngine::search_until`], to carry on
+/// with [`GrepEngine::resume_from`] later, possibly in another process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchState {
+    /// Stream offset where the search continues. Every match starting before
+    /// it was reported.
+    pub global_off: u64,
+    /// 1-based number of the line containing `global_off`.
+    pub line_no: u32,
+    /// Stream offset where that line starts. A resumed search reads from there
+    /// again, so matches after `global_off` see the rest of their line.
+    pub line_start: u64,
+    /// Stream offset where the last match reported before `global_off` ends,
+    /// or 0. A non-overlapping pattern, such as a regex, is looked for again
+    /// from there, as a single pass would, so a match straddling `global_off`
+    /// isn't reported a second time from its middle.
+    pub match_end: u64,
+}
+
+impl SearchState {
+    /// The length of [`SearchState::to_bytes`].
+    pub const ENCODED_LEN: usize = 28;
+
+    /// Encodes the state as `global_off`, `line_no`, `line_start` then
+    /// `match_end`, little-endian.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut out = [0; Self::ENCODED_LEN];
+        out[..8].copy_from_slice(&self.global_off.to_le_bytes());
+        out[8..12].copy_from_slice(&self.line_no.to_le_bytes());
+        out[12..20].copy_from_slice(&self.line_start.to_le_bytes());
+        out[20..].copy_from_slice(&self.match_end.to_le_bytes());
+        out
+    }
+
+    /// Decodes a state written by [`SearchState::to_bytes`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(state)` - The decoded state
+    /// * `Err(e)` - `InvalidData` when `bytes` is not [`SearchState::ENCODED_LEN`]
+    ///   long, holds line number 0 or a line start past `global_off`
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let bytes: &[u8; Self::ENCODED_LEN] = bytes.try_into().map_err(|_| {
+            invalid(format!(
+                "search state is {} bytes, expected {}",
+                bytes.len(),
+                Self::ENCODED_LEN
+            ))
+        })?;
+        let state = Self {
+            global_off: u64::from_le_bytes(bytes[..8].try_into().unwrap()),
+            line_no: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            line_start: u64::from_le_bytes(bytes[12..20].try_into().unwrap()),
+            match_end: u64::from_le_bytes(bytes[20..].try_into().unwrap()),
+        };
+        if state.line_no == 0 {
+            return Err(invalid("search state has line number 0".to_owned()));
+        }
+        if state.line_start > state.global_off {
+            return Err(invalid(format!(
+                "search state line start {} is past its offset {}",
+                state.line_start, state.global_off
+            )));
+        }
+        Ok(state)
+    }
+}
+
+impl SearchState {
+    /// Seeks `reader` to where a resumed search starts reading and returns the
+    /// state with `line_start` moved there. At a line start, that is the
+    /// separator before it, so `\A` can't match there again.
+    fn context<R: Seek>(self, reader: &mut R) -> io::Result<Self> {
+        let from = if self.line_start == self.global_off && self.global_off > 0 {
+            Self {
+                line_no: self.line_no.saturating_sub(1).max(1),
+                line_start: self.global_off - 1,
+                ..self
+            }
+        } else {
+            self
+        };
+        reader.seek(SeekFrom::Start(from.line_start))?;
+        Ok(from)
+    }
+}
+
+impl Default for SearchState {
+    /// The start of the stream, on line 1.
+    fn default() -> Self {
+        Self {
+            global_off: 0,
+            line_no: 1,
+            line_start: 0,
+            match_end: 0,
+        }
+    }
+}
+
+/// A reader counting the line separators it passes before stream offset `end`.
+struct LineCounter<R> {
+    inner: R,
+    sep: u8,
+    pos: u64,
+    end: u64,
+    newlines: u64,
+    /// Offset just past the last separator counted.
+    line_start: u64,
+}
+
+impl<R: Read> Read for LineCounter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let counted = self.end.saturating_sub(self.pos).min(n as u64) as usize;
+        self.newlines += memchr_iter(self.sep, &buf[..counted]).count() as u64;
+        if let Some(i) = memrchr(self.sep, &buf[..counted]) {
+            self.line_start = self.pos + i as u64 + 1;
         }
+        self.pos += n as u64;
+        Ok(n)
     }
 }
 
+/// Where in its chunk a match was found, for debugging the overlap logic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchRegion {
+    /// In bytes carried over from the previous chunk, e.g. straddling the
+    /// boundary between the two.
+    Stitch,
+    /// In bytes the chunk read fresh.
+    Fresh,
+}
+
 /// A trait for receiving match notifications from the grep engine.
 ///
 /// Implementations of this trait handle matches found during searches,
@@ -49,9 +671,138 @@ pub trait MatchSink {
     ///
     /// * `off` - Global byte offset within the entire file/stream
     /// * `len` - Match length (needle length)
-    /// * `line_no` - 1-based line number (0 for "unknown" until line indexer is implemented)
+    /// * `line_no` - 1-based line number (0 unless `GrepFlags::LINE_NUMBER` is set)
     /// * `file_id` - Caller-provided file identifier
     fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32);
+
+    /// Reports a match with its full metadata, including the column.
+    ///
+    /// This is what the engine calls. The default implementation forwards to
+    /// [`MatchSink::on_match`], so sinks that don't care about columns only
+    /// need to implement that.
+    fn on_match_info(&mut self, m: &Match) {
+        self.on_match(m.off, m.len, m.line_no, m.file_id);
+    }
+
+    /// Reports a match together with the line containing it.
+    ///
+    /// Called instead of [`MatchSink::on_match_info`] in line-aware mode, where the
+    /// whole line is in the buffer. `line` excludes the trailing newline. The default
+    /// implementation drops the line and forwards to `on_match_info`.
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        let _ = line;
+        self.on_match_info(m);
+    }
+
+    /// Called once before the engine reads the input of `file_id`.
+    fn on_start(&mut self, file_id: u32) {
+        let _ = file_id;
+    }
+
+    /// With `GrepOptions::debug_stitch`, called just before each match is
+    /// reported with where in its chunk it was found.
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        let _ = (m, region);
+    }
+
+    /// For engines built with [`GrepEngine::new_regex_captures`], called right
+    /// after each match once per capture group that took part in it, in group
+    /// order. `group` is 1-based (group 0 is the match itself) and `off` is a
+    /// global offset like `Match::off`.
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        let _ = (group, off, len);
+    }
+
+    /// Called after each chunk of input is scanned: every match starting
+    /// before stream offset `global_off` has been reported. Offsets never
+    /// decrease within a search; there is no call for a chunk the search
+    /// stopped in.
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        let _ = global_off;
+    }
+
+    /// Called once after the search of `file_id` completed, with the number of
+    /// matches reported for it. Not called when the search fails.
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        let _ = (file_id, total_matches);
+    }
+}
+
+/// What the engine should do after reporting a match to a [`ControlSink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Keep searching.
+    Continue,
+    /// Stop the search; no further input is read.
+    Stop,
+}
+
+/// A sink that can ask the engine to stop early.
+///
+/// Use this for "first match then quit" or cancellation from the sink side.
+/// Every [`MatchSink`] is also a `ControlSink` that always continues, so either
+/// kind can be passed to [`GrepEngine::search_control`].
+pub trait ControlSink {
+    /// Reports a match and returns whether the search should go on.
+    ///
+    /// `line` is the matching line (without its newline) in line-aware mode.
+    fn on_match_control(&mut self, m: &Match, line: Option<&[u8]>) -> Control;
+
+    /// See [`MatchSink::on_start`].
+    fn on_start(&mut self, file_id: u32) {
+        let _ = file_id;
+    }
+
+    /// See [`MatchSink::on_finish`]. Also called when the sink stopped the search.
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        let _ = (file_id, total_matches);
+    }
+
+    /// See [`MatchSink::on_match_region`].
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        let _ = (m, region);
+    }
+
+    /// See [`MatchSink::on_capture`]. Called even after the match returned
+    /// `Control::Stop`.
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        let _ = (group, off, len);
+    }
+
+    /// See [`MatchSink::on_chunk_boundary`].
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        let _ = global_off;
+    }
+}
+
+impl<T: MatchSink + ?Sized> ControlSink for T {
+    fn on_match_control(&mut self, m: &Match, line: Option<&[u8]>) -> Control {
+        match line {
+            Some(line) => self.on_match_line(m, line),
+            None => self.on_match_info(m),
+        }
+        Control::Continue
+    }
+
+    fn on_start(&mut self, file_id: u32) {
+        MatchSink::on_start(self, file_id);
+    }
+
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        MatchSink::on_finish(self, file_id, total_matches);
+    }
+
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        MatchSink::on_match_region(self, m, region);
+    }
+
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        MatchSink::on_capture(self, group, off, len);
+    }
+
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        MatchSink::on_chunk_boundary(self, global_off);
+    }
 }
 
 /// An input source with `io::Read` semantic.
@@ -64,11 +815,31 @@ impl<T: io::Read> Source for T {}
 ///
 /// This struct holds the compiled pattern and search options,
 /// providing methods to search through various input sources.
+///
+/// The engine is `Send + Sync`, but it borrows its needle for `'p`. To share one
+/// across threads without tying it to a borrow, use [`OwnedGrepEngine`].
 pub struct GrepEngine<'p> {
-    needle: &'p [u8],
+    matcher: MatcherRef<'p>,
     opts: GrepOptions,
 }
 
+/// The matcher of a [`GrepEngine`], owned or borrowed from a longer-lived value.
+enum MatcherRef<'p> {
+    Owned(Box<dyn Matcher + 'p>),
+    Borrowed(&'p dyn Matcher),
+}
+
+impl<'p> std::ops::Deref for MatcherRef<'p> {
+    type Target = dyn Matcher + 'p;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MatcherRef::Owned(m) => m.as_ref(),
+            MatcherRef::Borrowed(m) => *m,
+        }
+    }
+}
+
 impl<'p> GrepEngine<'p> {
     /// Creates a new engine for searching a single literal pattern.
     ///
@@ -77,7 +848,150 @@ impl<'p> GrepEngine<'p> {
     /// * `needle` - The literal byte pattern to search for
     /// * `opts` - Configuration options for the search
     pub fn new_literal(needle: &'p [u8], opts: GrepOptions) -> Self {
-        Self { needle, opts }
+        Self {
+            matcher: MatcherRef::Owned(Box::new(literal_matcher(needle, &opts.flags))),
+            opts,
+        }
+    }
+
+    /// Creates an engine matching `needle` byte for byte, for callers choosing
+    /// between fixed-string and regex search at run time.
+    ///
+    /// Same as [`GrepEngine::new_literal`]: regex metacharacters in `needle` are
+    /// never interpreted, so `a.c` matches only `a.c`. To feed a literal to
+    /// [`GrepEngine::new_regex`] instead, escape it with [`escape_literal`].
+    pub fn new_fixed_string(needle: &'p [u8], opts: GrepOptions) -> Self {
+        Self::new_literal(needle, opts)
+    }
+
+    /// Creates an engine for a regular expression over bytes.
+    ///
+    /// Matches may be up to 4 KiB long in streaming searches; set
+    /// `GrepOptions::overlap` for longer ones. A `chunk_bytes` below 4 KiB is
+    /// widened to 8 KiB to leave room for them. With `LINE_NUMBER`, only matches
+    /// within whole lines of the chunk are reported, as for literals. Unlike
+    /// literal matches, regex matches never overlap, and a streamed search
+    /// resumes where the last match ended, as a single pass would. The few bytes
+    /// before each chunk are kept as context, so `^`, `\b` and `\A` see what
+    /// precedes it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(engine)` - When `pattern` compiles
+    /// * `Err(GrepError::Regex(_))` - When it does not
+    pub fn new_regex(pattern: &str, opts: GrepOptions) -> Result<Self, GrepError> {
+        Ok(Self {
+            matcher: MatcherRef::Owned(Box::new(RegexMatcher::new(pattern)?)),
+            opts,
+        })
+    }
+
+    /// Like [`GrepEngine::new_regex`], also reporting capture groups.
+    ///
+    /// After each match, [`MatchSink::on_capture`] receives the span of every
+    /// capture group that took part in it, e.g. the digits of `id=(\d+)`.
+    /// Locating the groups costs a second, slower regex run per match.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(engine)` - When `pattern` compiles
+    /// * `Err(GrepError::Regex(_))` - When it does not
+    pub fn new_regex_captures(pattern: &str, opts: GrepOptions) -> Result<Self, GrepError> {
+        Ok(Self {
+            matcher: MatcherRef::Owned(Box::new(RegexMatcher::new(pattern)?.with_captures())),
+            opts,
+        })
+    }
+
+    /// Creates an engine matching several regular expressions, line by line.
+    ///
+    /// Lines are checked against all of them at once with a `RegexSet`, and only
+    /// the regexes matching a line are run to locate their matches. Every such
+    /// regex reports its own non-overlapping matches, with its index as
+    /// `Match::pattern`, so one line may report several patterns, even at the
+    /// same offset. Reports ascend by offset, then by index. Matches never span
+    /// a newline. Streamed chunks are cut at line ends, so `^` never matches at
+    /// a chunk start inside a line; lines longer than `chunk_bytes` grow the
+    /// buffer to hold them whole.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(engine)` - When every pattern compiles
+    /// * `Err(GrepError::Regex(_))` - When one does not
+    pub fn new_regex_set<S: AsRef<str>>(
+        patterns: &[S],
+        opts: GrepOptions,
+    ) -> Result<Self, GrepError> {
+        Ok(Self {
+            matcher: MatcherRef::Owned(Box::new(RegexSetMatcher::new(patterns)?)),
+            opts,
+        })
+    }
+
+    /// Creates an engine matching any of several literal needles.
+    ///
+    /// At each offset only one needle is reported: the first listed among those
+    /// matching there, its index given as `Match::pattern`. Each search still
+    /// resumes one byte after a match start.
+    pub fn new_multi<P: AsRef<[u8]>>(needles: &[P], opts: GrepOptions) -> Self {
+        Self {
+            matcher: MatcherRef::Owned(Box::new(MultiMatcher::new(needles))),
+            opts,
+        }
+    }
+
+    /// Like [`GrepEngine::new_multi`], but reports every needle matching at an
+    /// offset.
+    ///
+    /// Matches at the same offset are reported in listed order (ascending
+    /// `Match::pattern`), whatever their lengths, so `["foobar", "foo"]` on
+    /// `foobar` reports pattern 0 then pattern 1 at offset 0. Offsets still
+    /// ascend across reports. Match limits count each report.
+    pub fn new_multi_all<P: AsRef<[u8]>>(needles: &[P], opts: GrepOptions) -> Self {
+        Self {
+            matcher: MatcherRef::Owned(Box::new(MultiMatcher::new_all(needles))),
+            opts,
+        }
+    }
+
+    /// Like [`GrepEngine::new_literal`], but rejects contradictory options.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(engine)` - When the options are consistent
+    /// * `Err(GrepError::Options(_))` - When [`GrepOptions::validate`] rejects `opts`
+    pub fn try_new_literal(needle: &'p [u8], opts: GrepOptions) -> Result<Self, GrepError> {
+        opts.validate(needle.len())?;
+        Ok(Self::new_literal(needle, opts))
+    }
+
+    /// Creates a new engine backed by an already compiled pattern.
+    ///
+    /// Unlike [`GrepEngine::new_literal`], this does not rebuild the finder, so it is
+    /// cheap enough to call once per request.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The compiled pattern to search for
+    /// * `opts` - Configuration options for the search
+    pub fn from_prepared(pattern: &'p PreparedPattern, opts: GrepOptions) -> Self {
+        Self {
+            matcher: MatcherRef::Borrowed(&*pattern.matcher),
+            opts,
+        }
+    }
+
+    /// Returns the options this engine was built with.
+    pub fn options(&self) -> &GrepOptions {
+        &self.opts
+    }
+
+    /// Copies the needle into an [`OwnedGrepEngine`] with the same options.
+    pub fn into_owned_engine(self) -> OwnedGrepEngine {
+        OwnedGrepEngine {
+            matcher: Arc::from(self.matcher.to_static()),
+            opts: self.opts,
+        }
     }
 
     /// Runs the search pipeline on a `Source`, reporting all matches to the provided sink.
@@ -94,54 +1008,1597 @@ impl<'p> GrepEngine<'p> {
     ///
     /// # Notes
     ///
-    /// - Currently uses baseline `memmem::find` repeatedly inside each chunk
+    /// - Currently uses the compiled `memmem::Finder` repeatedly inside each chunk
     /// - Overlap is handled in `Chunker`, so cross-boundary matches are found exactly once
-    /// - Line numbers are reported as 0 (placeholder)
+    /// - Line numbers and columns are reported only with `GrepFlags::LINE_NUMBER`; otherwise 0
     pub fn search<R: Source>(&self, reader: &mut R, sink: &mut dyn MatchSink) -> io::Result<()> {
-        // For overlap we need "needle.len() - 1" bytes from the previous chunk.
-        let overlap = self.needle.len().saturating_sub(1);
-        let mut chunker = Chunker::new(reader, self.opts.chunk_bytes, overlap);
-
-        let mut total_count: u64 = 0;
-        let nlen = self.needle.len() as u32;
-
-        while let Some((global_base, chunk)) = chunker.next_chunk()? {
-            if self.needle.is_empty() {
-                // Empty needle convention: match at every position is nonsensical for grep.
-                // We follow our S0 API rules and report a single hit at the start of the stream.
-                if global_base == 0 {
-                    sink.on_match(0, 0, 0, self.opts.file_id);
-                    total_count += 1;
-                }
-                break;
-            }
+        self.run(reader, sink, &mut Vec::new())
+    }
 
-            // Repeatedly find all matches within the current chunk.
-            // Important: Chunker ensures that every *new* byte range (excluding the previous
-            // overlap except at the leading edge) is unique, so reporting here is safe.
-            let mut search_off = 0usize;
-            while let Some(rel) = memmem::find(&chunk[search_off..], self.needle) {
-                let pos = search_off + rel;
-                let global_off = (global_base + pos as u64) as u64;
-                sink.on_match(global_off, nlen, 0, self.opts.file_id);
-                total_count += 1;
+    /// Like [`GrepEngine::search`], for a reader chosen at run time, such as the
+    /// `Box<dyn Read>` from [`decompress::open`](crate::io::decompress::open).
+    pub fn search_dyn(
+        &self,
+        mut reader: &mut dyn Read,
+        sink: &mut dyn MatchSink,
+    ) -> io::Result<()> {
+        self.run(&mut reader, sink, &mut Vec::new())
+    }
 
-                // Move past this match to find subsequent occurrences (including overlaps).
-                search_off = pos + 1;
-                if search_off >= chunk.len() {
-                    break;
-                }
-            }
-        }
+    /// Runs the search pipeline, letting the sink stop it early.
+    ///
+    /// When `sink` returns [`Control::Stop`], the search returns `Ok(())` right away
+    /// without reading further input.
+    pub fn search_control<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn ControlSink,
+    ) -> io::Result<()> {
+        self.run(reader, sink, &mut Vec::new())
+    }
 
-        if self.opts.flags.contains(GrepFlags::COUNT_ONLY) {
-            // A "count only" sink could be specialized; for now we expect the sink
-            // implementation to handle "counting" if desired.
-            let _ = total_count;
-        }
+    /// Searches an in-memory buffer in one pass, without chunking or copying.
+    ///
+    /// Reports exactly what [`GrepEngine::search`] would report for the same bytes,
+    /// with offsets relative to the start of `data`. Every [`ByteRange`] is supported
+    /// since the whole input is at hand; `GrepOptions::overlap` is unused.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - On successful completion
+    /// * `Err(e)` - `InvalidInput` for the option values [`GrepEngine::search`]
+    ///   rejects, e.g. an empty `record_separator` or `chunk_bytes: 0`
+    pub fn search_slice(&self, data: &[u8], sink: &mut dyn MatchSink) -> io::Result<()> {
+        let (start, window) = self.slice_window(data);
+        let mut state = ScanState::new(start, &self.opts);
+        self.scan_window(window, sink, &mut state)
+    }
 
+    /// Searches `window`, the part of an in-memory buffer `state` starts at, as
+    /// a single chunk.
+    fn scan_window(
+        &self,
+        window: &[u8],
+        sink: &mut dyn MatchSink,
+        state: &mut ScanState<'_>,
+    ) -> io::Result<()> {
+        self.check_options()?;
+        sink.on_start(self.opts.file_id);
+        if !state.limit_hit(&self.opts) && !window.is_empty() {
+            let cut = self.final_cut(state, window, 0);
+            if self.scan_chunk(state, window, 0, cut, sink) == Control::Continue {
+                sink.on_chunk_boundary(state.origin + cut as u64);
+            }
+        }
+        sink.on_finish(self.opts.file_id, state.total_count);
         Ok(())
     }
+
+    /// Searches an in-memory buffer like [`GrepEngine::search_slice`], returning
+    /// the matches together with a [`LineIndex`] of the searched bytes.
+    ///
+    /// The index lets an editor map any offset to its line and column later,
+    /// e.g. for a cursor between matches, without scanning `data` again. Its
+    /// line starts are those the search's line tracker passes, so with
+    /// `GrepFlags::LINE_NUMBER` it agrees with the matches' line numbers and
+    /// byte columns under `record_separator`, `strip_bom` and `byte_range` too;
+    /// under `ColumnUnit::Char` its columns still count bytes. The tracker goes
+    /// on to the end of the window after the search (all of it without
+    /// `LINE_NUMBER`), and the index takes 8 bytes per line.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((matches, index))` - The matches in order and the line index
+    /// * `Err(e)` - As for [`GrepEngine::search_slice`]
+    pub fn search_all_in_memory(&self, data: &[u8]) -> io::Result<(Vec<Match>, LineIndex)> {
+        struct Collect(Vec<Match>);
+        impl MatchSink for Collect {
+            fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+                self.on_match_info(&Match {
+                    off,
+                    match_off: off,
+                    len,
+                    line_no,
+                    file_id,
+                    ..Match::default()
+                });
+            }
+
+            fn on_match_info(&mut self, m: &Match) {
+                self.0.push(*m);
+            }
+        }
+
+        let (start, window) = self.slice_window(data);
+        let mut state = ScanState::new(start, &self.opts);
+        state.lines = state.lines.recording();
+        let mut matches = Collect(Vec::new());
+        self.scan_window(window, &mut matches, &mut state)?;
+        // The search stops tracking lines at its last match or line.
+        state.lines.advance(window, 0, window.len() as u64);
+        let index = LineIndex::from_tracker(&mut state.lines, start, window.len());
+        Ok((matches.0, index))
+    }
+
+    /// Returns the part of `data` an in-memory search covers, after applying
+    /// `byte_range` and `strip_bom`, and the offset its matches count from.
+    fn slice_window<'d>(&self, data: &'d [u8]) -> (u64, &'d [u8]) {
+        let len = data.len() as u64;
+        let (start, end) = match self.opts.byte_range {
+            None => (0, len),
+            Some(ByteRange::Head(n)) => (0, n.min(len)),
+            Some(ByteRange::Tail(n)) => (len.saturating_sub(n), len),
+            Some(ByteRange::Span(start, end)) => (start.min(len), end.min(len).max(start.min(len))),
+        };
+        let mut window = &data[start as usize..end as usize];
+        if self.opts.strip_bom && start == 0 {
+            window = window.strip_prefix(UTF8_BOM).unwrap_or(window);
+        }
+        (start, window)
+    }
+
+    /// Returns whether `reader` contains the needle, reading no further than the
+    /// chunk holding the first match.
+    ///
+    /// This is the engine behind a `-q` style quiet mode: nothing is reported and
+    /// no sink is involved. A command-line tool maps `Ok(true)` to exit status 0,
+    /// `Ok(false)` to 1 and `Err(_)` to 2, as grep does.
+    pub fn any_match<R: Source>(&self, reader: &mut R) -> io::Result<bool> {
+        struct First(bool);
+        impl ControlSink for First {
+            fn on_match_control(&mut self, _: &Match, _: Option<&[u8]>) -> Control {
+                self.0 = true;
+                Control::Stop
+            }
+        }
+
+        let mut first = First(false);
+        self.run(reader, &mut first, &mut Vec::new())?;
+        Ok(first.0)
+    }
+
+    /// Estimates how common the pattern is from the first `sample_bytes` of
+    /// `reader`, as reported matches per byte read.
+    ///
+    /// The sample is searched like any input, with the same matcher and
+    /// options, except that `byte_range` is ignored. Returns 0 for an empty
+    /// sample.
+    pub fn estimate_density<R: Read>(
+        &self,
+        reader: &mut R,
+        sample_bytes: usize,
+    ) -> io::Result<f64> {
+        struct Count(u64);
+        impl ControlSink for Count {
+            fn on_match_control(&mut self, _: &Match, _: Option<&[u8]>) -> Control {
+                self.0 += 1;
+                Control::Continue
+            }
+        }
+
+        let mut sample = reader.take(sample_bytes as u64);
+        let mut count = Count(0);
+        self.scan_reader(
+            &mut sample,
+            &mut count,
+            &mut Vec::new(),
+            ScanState::new(0, &self.opts),
+        )?;
+        let read = sample_bytes as u64 - sample.limit();
+        Ok(if read == 0 {
+            0.0
+        } else {
+            count.0 as f64 / read as f64
+        })
+    }
+
+    /// Same as [`GrepEngine::any_match`].
+    pub fn contains_in<R: Source>(&self, reader: &mut R) -> io::Result<bool> {
+        self.any_match(reader)
+    }
+
+    /// Runs the search pipeline using `buf` as the chunker's working buffer.
+    ///
+    /// The buffer is handed back (possibly grown) when the search ends, so a caller
+    /// searching many inputs can keep one allocation across all of them.
+    pub fn search_with_buffer<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        self.run(reader, sink, buf)
+    }
+
+    /// Runs the search pipeline in `buf`, without allocating a chunk buffer.
+    ///
+    /// The rest of the search still allocates as usual, e.g. for the sink's
+    /// lines or a regex's state, and needs the `std` feature.
+    ///
+    /// `buf` must hold `chunk_bytes` plus the overlap; `buffer_align` does not
+    /// apply. In line-aware mode the unfinished line at the end of each chunk
+    /// is carried into the next, so a line longer than `buf.len() - chunk_bytes`
+    /// fails the search with `OutOfMemory`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - When the input was searched to the end or the sink stopped
+    /// * `Err(e)` - `InvalidInput` when `buf` is too small or a `byte_range` other
+    ///   than `Head` is set, `OutOfMemory` as above, or an I/O error of `reader`
+    pub fn search_with_slice<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        buf: &mut [u8],
+    ) -> io::Result<()> {
+        match self.opts.byte_range {
+            None => self.scan_slice(reader, sink, buf),
+            Some(ByteRange::Head(n)) => self.scan_slice(&mut reader.by_ref().take(n), sink, buf),
+            Some(range) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} needs a seekable source; use search_seekable", range),
+            )),
+        }
+    }
+
+    fn scan_slice<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        buf: &mut [u8],
+    ) -> io::Result<()> {
+        let (chunk_bytes, overlap) = self.chunk_layout()?;
+        let mut chunker = Chunker::try_with_slice(reader, chunk_bytes, overlap, buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .skip_bom(self.opts.strip_bom);
+        sink.on_start(self.opts.file_id);
+        let res = self.scan(&mut chunker, sink, ScanState::new(0, &self.opts), overlap);
+        sink.on_finish(self.opts.file_id, res?);
+        Ok(())
+    }
+
+    /// Searches `reader` from its start, reporting only matches that start
+    /// before stream offset `end`, and returns the checkpoint to resume from.
+    ///
+    /// The search reads somewhat past `end` to complete matches straddling it.
+    /// The returned state is at `end`, or at the end of the input if that
+    /// comes first, even when the sink or `max_matches` stopped the search
+    /// early; the rest of the input up to `end` is then read to count its
+    /// lines.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(state)` - The checkpoint, for [`GrepEngine::resume_from`]
+    /// * `Err(e)` - `InvalidInput` when a `byte_range` or `strip_bom` is set, since
+    ///   checkpoints count raw stream offsets, or any error [`GrepEngine::search`]
+    ///   returns
+    pub fn search_until<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        end: u64,
+    ) -> io::Result<SearchState> {
+        self.scan_from(reader, sink, SearchState::default(), Some(end))
+    }
+
+    /// Continues a search from a checkpoint taken by
+    /// [`GrepEngine::search_until`], reporting the matches starting at or after
+    /// `state.global_off` (and, for a regex, not before `state.match_end`) with
+    /// line numbers counted on from `state.line_no`.
+    ///
+    /// `reader` is sought back to `state.line_start` first (or the byte before a
+    /// checkpoint at a line start), and the bytes up to `state.global_off` are
+    /// read again as context only: anchors, word boundaries, columns and line
+    /// offsets come out as in a single pass. `on_finish` receives the number of
+    /// matches of this part only.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - When the input was searched to the end or the sink stopped
+    /// * `Err(e)` - As for [`GrepEngine::search_until`]
+    pub fn resume_from<R: Source + Seek>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        state: SearchState,
+    ) -> io::Result<()> {
+        let from = state.context(reader)?;
+        self.scan_from(reader, sink, from, None).map(|_| ())
+    }
+
+    /// Like [`GrepEngine::resume_from`], stopping at `end` as
+    /// [`GrepEngine::search_until`] does, to take the next checkpoint.
+    pub fn resume_until<R: Source + Seek>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        state: SearchState,
+        end: u64,
+    ) -> io::Result<SearchState> {
+        let from = state.context(reader)?;
+        self.scan_from(reader, sink, from, Some(end))
+    }
+
+    /// Scans `reader`, positioned at `from.line_start`, reporting the matches
+    /// from `from.global_off` on up to those starting at `end` if given, and
+    /// returns the state at `end`.
+    fn scan_from<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        from: SearchState,
+        end: Option<u64>,
+    ) -> io::Result<SearchState> {
+        if self.opts.byte_range.is_some() || self.opts.strip_bom {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "checkpointed searches support neither byte_range nor strip_bom",
+            ));
+        }
+        // A checkpoint could fall inside a multi-byte separator, which the
+        // resumed search would then miss.
+        let Separator::Byte(sep) = self.opts.separator() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "checkpointed searches support only single-byte record separators",
+            ));
+        };
+        let mut counter = LineCounter {
+            inner: reader,
+            sep,
+            pos: from.line_start,
+            end: end.unwrap_or(u64::MAX),
+            newlines: 0,
+            line_start: from.line_start,
+        };
+        let match_end = Cell::new(from.match_end);
+        let mut state = ScanState::new(from.line_start, &self.opts);
+        state.lines = LineTracker::starting_at(from.line_no).separated_by(self.opts.separator());
+        state.done = from.global_off - from.line_start;
+        if !self.matcher.overlapping() {
+            state.done = state
+                .done
+                .max(from.match_end.saturating_sub(from.line_start));
+        }
+        state.stop_at = end;
+        state.match_end = Some(&match_end);
+        self.scan_reader(&mut counter, sink, &mut Vec::new(), state)?;
+        if let Some(end) = end
+            && counter.pos < end
+        {
+            let rest = end - counter.pos;
+            io::copy(&mut counter.by_ref().take(rest), &mut io::sink())?;
+        }
+        let newlines = u32::try_from(counter.newlines).unwrap_or(u32::MAX);
+        Ok(SearchState {
+            global_off: counter.pos.min(counter.end),
+            line_no: from.line_no.saturating_add(newlines),
+            line_start: counter.line_start,
+            match_end: match_end.get(),
+        })
+    }
+
+    /// Runs the search pipeline, reporting only the matches `skip` keeps.
+    ///
+    /// `skip` sees every candidate match in order (see [`SkipRegions`]); with
+    /// [`QuotedStrings`](crate::skip::QuotedStrings) matches inside `"..."` are
+    /// left out. Vetoed matches don't count toward `max_matches` or the per-line cap.
+    pub fn search_skipping<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        skip: &mut dyn SkipRegions,
+    ) -> io::Result<()> {
+        let mut state = ScanState::new(0, &self.opts);
+        state.skip = Some(skip);
+        self.run_with(reader, sink, &mut Vec::new(), state)
+    }
+
+    /// Runs the search pipeline until `cancel` is set, e.g. from a UI thread.
+    ///
+    /// The flag is read (relaxed) once per chunk, before the chunk is read, so
+    /// a cancelled search stops at most one chunk late. It then ends as if the
+    /// sink had stopped it: `on_finish` is called with the matches reported so
+    /// far and the result is `Ok(())`.
+    pub fn search_cancellable<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        cancel: &AtomicBool,
+    ) -> io::Result<()> {
+        let mut state = ScanState::new(0, &self.opts);
+        state.cancel = Some(cancel);
+        self.run_with(reader, sink, &mut Vec::new(), state)
+    }
+
+    /// Runs the search pipeline on a buffered source, scanning its buffer in place.
+    ///
+    /// Reports the same matches as [`GrepEngine::search`], but instead of copying
+    /// the input into a chunk buffer of `chunk_bytes`, each chunk is the reader's
+    /// own buffer (see [`BufChunker`]), so the reader's capacity sets the chunk
+    /// size. Only the bytes around buffer boundaries are copied.
+    pub fn search_buffered<R: BufRead>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+    ) -> io::Result<()> {
+        match self.opts.byte_range {
+            None => self.scan_buffered(reader, sink),
+            Some(ByteRange::Head(n)) => self.scan_buffered(&mut reader.by_ref().take(n), sink),
+            Some(range) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} needs a seekable source; use search_seekable", range),
+            )),
+        }
+    }
+
+    fn scan_buffered<R: BufRead>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+    ) -> io::Result<()> {
+        self.check_options()?;
+        let overlap = self.overlap()?;
+        // A BOM split across the reader's first two buffers is not seen; that
+        // takes a buffer of under three bytes.
+        if self.opts.strip_bom && reader.fill_buf()?.starts_with(UTF8_BOM) {
+            reader.consume(UTF8_BOM.len());
+        }
+        let mut chunker = BufChunker::new(reader, overlap);
+        sink.on_start(self.opts.file_id);
+        let total = self.scan(&mut chunker, sink, ScanState::new(0, &self.opts), overlap)?;
+        sink.on_finish(self.opts.file_id, total);
+        Ok(())
+    }
+
+    /// Runs a literal search that reads every byte once, with no chunk overlap.
+    ///
+    /// Instead of searching the tail of each chunk again, a [`KmpSearcher`]
+    /// carries the partial match across chunk boundaries, so matches are the
+    /// same as [`GrepEngine::search`] reports without line numbers. Past each
+    /// candidate the automaton steps one byte at a time, so fewer bytes are
+    /// scanned but the search is not generally faster than `search`. `chunk_bytes`,
+    /// `buffer_align`, `max_matches`, `match_stride`, `min_gap`, `strip_bom` and
+    /// `deadline` apply.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - On successful completion
+    /// * `Err(e)` - On I/O errors; `InvalidInput` for a non-literal pattern or
+    ///   with `GrepFlags::LINE_NUMBER` or a `byte_range`, which need the
+    ///   overlapping chunks
+    pub fn search_kmp<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+    ) -> io::Result<()> {
+        let unsupported = |what: &str| {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("search_kmp does not support {}", what),
+            ))
+        };
+        let Some(needle) = self.matcher.literal() else {
+            return unsupported("non-literal patterns");
+        };
+        if self.opts.flags.contains(GrepFlags::LINE_NUMBER) {
+            return unsupported("LINE_NUMBER");
+        }
+        if let Some(range) = self.opts.byte_range {
+            return unsupported(&format!("{:?}", range));
+        }
+        if needle.is_empty() {
+            return self.search(reader, sink);
+        }
+        self.check_options()?;
+        let mut chunker = match self.buffer_align()? {
+            Some(align) => Chunker::with_alignment(reader, self.opts.chunk_bytes, 0, align),
+            None => Chunker::new(reader, self.opts.chunk_bytes, 0),
+        }
+        .skip_bom(self.opts.strip_bom);
+
+        // Every match of the needle crosses a line if any does.
+        let crosses_line = self.opts.flags.contains(GrepFlags::NO_CROSS_LINE)
+            && self.opts.separator().find(needle).is_some();
+        let mut kmp = KmpSearcher::new(needle);
+        let mut total = 0;
+        let mut last: Option<u64> = None;
+        sink.on_start(self.opts.file_id);
+        let mut stopped = self.opts.max_matches.is_some_and(|max| max == 0);
+        while !stopped {
+            if self.opts.deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(GrepError::timeout(total));
+            }
+            let Some((base, chunk)) = chunker.next_chunk()? else {
+                break;
+            };
+            let bytes_read = base + chunk.len() as u64;
+            let control = kmp.feed(chunk, |off| {
+                let misaligned = self
+                    .opts
+                    .match_stride
+                    .is_some_and(|s| !off.is_multiple_of(s));
+                let too_close = self
+                    .opts
+                    .min_gap
+                    .zip(last)
+                    .is_some_and(|(gap, last)| off < last.saturating_add(gap));
+                if misaligned || too_close || crosses_line {
+                    return Control::Continue;
+                }
+                last = Some(off);
+                total += 1;
+                sink.on_match_info(&Match {
+                    off,
+                    match_off: off,
+                    len: needle.len() as u32,
+                    file_id: self.opts.file_id,
+                    bytes_read,
+                    ..Match::default()
+                });
+                if self.opts.max_matches.is_some_and(|max| total >= max) {
+                    Control::Stop
+                } else {
+                    Control::Continue
+                }
+            });
+            stopped = control == Control::Stop;
+            if !stopped {
+                // A partial match at the end of the chunk may still complete.
+                sink.on_chunk_boundary(bytes_read - kmp.pending() as u64);
+            }
+        }
+        if !stopped && kmp.pending() > 0 {
+            sink.on_chunk_boundary(kmp.position());
+        }
+        sink.on_finish(self.opts.file_id, total);
+        Ok(())
+    }
+
+    /// Starts a search that is fed input through [`PushSearcher::push`] instead of
+    /// pulling it from a reader.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(searcher)` - A searcher at offset 0
+    /// * `Err(e)` - `InvalidInput` for the option values [`GrepEngine::search`]
+    ///   rejects, such as an overlap too short for the pattern, or when a
+    ///   `byte_range` is set, which pushed input does not support
+    pub fn push_searcher(&self) -> io::Result<PushSearcher<'_, 'p>> {
+        if let Some(range) = self.opts.byte_range {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not supported for pushed input", range),
+            ));
+        }
+        self.check_options()?;
+        let overlap = self.overlap()?;
+        let state = ScanState::new(0, &self.opts);
+        Ok(PushSearcher {
+            engine: self,
+            finished: state.limit_hit(&self.opts),
+            state,
+            buf: Vec::new(),
+            base: 0,
+            overlap,
+            started: false,
+            bom_pending: self.opts.strip_bom,
+        })
+    }
+
+    /// Searches `reader` lazily, yielding matches instead of reporting them to a
+    /// sink.
+    ///
+    /// Each chunk is read only when the matches of the previous one are used
+    /// up. Errors, including invalid options and an unsupported `byte_range`,
+    /// are yielded as the last item.
+    pub fn matches<'r, R: Source>(&self, reader: &'r mut R) -> Matches<'_, 'p, 'r, R> {
+        let state = ScanState::new(0, &self.opts);
+        let mut iter = Matches {
+            engine: self,
+            chunker: None,
+            state,
+            pending: Pending(VecDeque::new()),
+            overlap: 0,
+            error: None,
+        };
+        if let Some(range) = self.opts.byte_range {
+            iter.error = Some(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{:?} is not supported by matches; use search_seekable",
+                    range
+                ),
+            ));
+            return iter;
+        }
+        match self.chunker(reader, Vec::new()) {
+            Ok((chunker, overlap)) if !iter.state.limit_hit(&self.opts) => {
+                iter.chunker = Some(chunker.skip_bom(self.opts.strip_bom));
+                iter.overlap = overlap;
+            }
+            Ok(_) => {}
+            Err(e) => iter.error = Some(e),
+        }
+        iter
+    }
+
+    /// Runs the search pipeline on a seekable source, honoring every [`ByteRange`].
+    ///
+    /// The source is positioned at the window start before reading; offsets are
+    /// reported relative to the start of the stream.
+    pub fn search_seekable<R: Source + Seek>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+    ) -> io::Result<()> {
+        self.seekable_with_buffer(reader, sink, &mut Vec::new())
+    }
+
+    pub(crate) fn seekable_with_buffer<R: Source + Seek>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let (start, end) = match self.opts.byte_range {
+            None | Some(ByteRange::Head(_)) => return self.run(reader, sink, buf),
+            Some(ByteRange::Tail(n)) => {
+                let len = reader.seek(SeekFrom::End(0))?;
+                (len.saturating_sub(n), len)
+            }
+            Some(ByteRange::Span(start, end)) => (start, end.max(start)),
+        };
+        reader.seek(SeekFrom::Start(start))?;
+        let mut window = reader.by_ref().take(end - start);
+        self.scan_reader(&mut window, sink, buf, ScanState::new(start, &self.opts))
+    }
+
+    fn run<R: Source, S: ControlSink + ?Sized>(
+        &self,
+        reader: &mut R,
+        sink: &mut S,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        self.run_with(reader, sink, buf, ScanState::new(0, &self.opts))
+    }
+
+    fn run_with<R: Source, S: ControlSink + ?Sized>(
+        &self,
+        reader: &mut R,
+        sink: &mut S,
+        buf: &mut Vec<u8>,
+        state: ScanState<'_>,
+    ) -> io::Result<()> {
+        match self.opts.byte_range {
+            None => self.scan_reader(reader, sink, buf, state),
+            Some(ByteRange::Head(n)) => {
+                self.scan_reader(&mut reader.by_ref().take(n), sink, buf, state)
+            }
+            Some(range) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} needs a seekable source; use search_seekable", range),
+            )),
+        }
+    }
+
+    /// Chunks `reader` through `buf` and scans it from `state`, whose origin is
+    /// the absolute offset of the reader's first byte.
+    fn scan_reader<R: Source, S: ControlSink + ?Sized>(
+        &self,
+        reader: &mut R,
+        sink: &mut S,
+        buf: &mut Vec<u8>,
+        state: ScanState<'_>,
+    ) -> io::Result<()> {
+        let (chunker, overlap) = self.chunker(reader, mem::take(buf))?;
+        let mut chunker = chunker.skip_bom(self.opts.strip_bom && state.origin == 0);
+        sink.on_start(self.opts.file_id);
+        let res = self.scan(&mut chunker, sink, state, overlap);
+        if self.opts.buffer_align.is_none() {
+            *buf = chunker.into_buffer();
+        }
+        sink.on_finish(self.opts.file_id, res?);
+        Ok(())
+    }
+
+    /// Sets up a chunker over `reader` for these options, reusing `buf` unless
+    /// chunks are aligned. Also returns the overlap.
+    fn chunker<'r, R: Source>(
+        &self,
+        reader: &'r mut R,
+        buf: Vec<u8>,
+    ) -> io::Result<(Chunker<'r, R>, usize)> {
+        let (chunk_bytes, overlap) = self.chunk_layout()?;
+        let chunker = match self.buffer_align()? {
+            Some(align) => Chunker::with_alignment(reader, chunk_bytes, overlap, align),
+            None => Chunker::with_buffer(reader, chunk_bytes, overlap, buf),
+        };
+        Ok((chunker, overlap))
+    }
+
+    /// Returns the chunk size and overlap the chunker uses for these options.
+    fn chunk_layout(&self) -> io::Result<(usize, usize)> {
+        self.check_options()?;
+        let overlap = self.overlap()?;
+        // A needle longer than a chunk gets a window twice its size, so each chunk
+        // can hold a whole match plus fresh bytes. The chunker also needs fresh bytes
+        // past a custom overlap.
+        let needle_len = self.matcher.max_len_hint();
+        let chunk_bytes = if needle_len > self.opts.chunk_bytes {
+            needle_len * 2
+        } else {
+            self.opts.chunk_bytes
+        };
+        Ok((chunk_bytes.max(overlap + 1), overlap))
+    }
+
+    /// Drives `chunker` to the end, reporting matches to `sink`. Returns the
+    /// number of reported matches.
+    fn scan<C: ChunkSource, S: ControlSink + ?Sized>(
+        &self,
+        chunker: &mut C,
+        sink: &mut S,
+        mut state: ScanState<'_>,
+        overlap: usize,
+    ) -> io::Result<u64> {
+        if state.limit_hit(&self.opts) {
+            return Ok(0);
+        }
+
+        // Checked once per chunk, before reading it.
+        let expired = || self.opts.deadline.is_some_and(|d| Instant::now() >= d);
+
+        loop {
+            if expired() {
+                return Err(GrepError::timeout(state.total_count));
+            }
+            if state.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return Ok(state.total_count);
+            }
+            let Some((global_base, chunk, eof)) = chunker.next_chunk_with_eof()? else {
+                break;
+            };
+            let (control, keep) = self.step(&mut state, chunk, global_base, eof, overlap, sink);
+            if control == Control::Stop {
+                return Ok(state.total_count);
+            }
+            // Hand the tail out again even if no fresh bytes follow it, since
+            // matches starting there were not reported yet.
+            if !eof {
+                chunker.retain(keep);
+            }
+        }
+
+        if self.opts.flags.contains(GrepFlags::COUNT_ONLY) {
+            // A "count only" sink could be specialized; for now we expect the sink
+            // implementation to handle "counting" if desired.
+            let _ = state.total_count;
+        }
+
+        Ok(state.total_count)
+    }
+
+    /// Scans one chunk at `global_base`. Returns how the search goes on and how
+    /// many bytes at the end of the chunk must be handed out again.
+    fn step<S: ControlSink + ?Sized>(
+        &self,
+        state: &mut ScanState<'_>,
+        chunk: &[u8],
+        global_base: u64,
+        eof: bool,
+        overlap: usize,
+        sink: &mut S,
+    ) -> (Control, usize) {
+        // Only matches starting before `cut` are reported from this chunk; the rest
+        // of the chunk is carried into the next one. Matches starting in the last
+        // `overlap` bytes may not fit yet, so they wait for the next chunk (for a
+        // literal they can't be in this one anyway). In line-aware mode we cut at a
+        // line start so every reported line is complete and the next chunk begins
+        // on a fresh line.
+        let limit = chunk.len() - overlap.min(chunk.len());
+        let cut = if eof {
+            self.final_cut(state, chunk, global_base)
+        } else if self.opts.flags.contains(GrepFlags::LINE_NUMBER) {
+            state.lines.last_line_start(chunk, global_base, limit)
+        } else if self.matcher.whole_lines() {
+            memrchr(b'\n', &chunk[..limit]).map_or(0, |i| i + 1)
+        } else {
+            limit
+        };
+
+        // Bytes up to the end of the previous chunk were carried over.
+        state.carried = (state.prev_end.saturating_sub(global_base) as usize).min(chunk.len());
+        state.prev_end = global_base + chunk.len() as u64;
+        let control = self.scan_chunk(state, chunk, global_base, cut, sink);
+        if control == Control::Continue {
+            sink.on_chunk_boundary(state.origin + global_base + cut as u64);
+        }
+        // Bytes before the cut the matcher needs as context are handed out again;
+        // `state.done` keeps them from being searched twice.
+        (
+            control,
+            chunk.len() - cut + self.matcher.look_behind().min(cut),
+        )
+    }
+
+    /// Returns where matches stop being reported in the last chunk of the input:
+    /// its end, or with `GrepOptions::require_final_newline` the end of its last
+    /// terminated line.
+    fn final_cut(&self, state: &ScanState<'_>, chunk: &[u8], global_base: u64) -> usize {
+        if self.opts.require_final_newline && self.opts.flags.contains(GrepFlags::LINE_NUMBER) {
+            state.lines.last_line_start(chunk, global_base, chunk.len())
+        } else {
+            chunk.len()
+        }
+    }
+
+    /// Reports the matches of one chunk starting before `cut`, then moves `state`
+    /// up to `cut`. Returns `Control::Stop` once the search is over.
+    fn scan_chunk<S: ControlSink + ?Sized>(
+        &self,
+        state: &mut ScanState<'_>,
+        chunk: &[u8],
+        global_base: u64,
+        cut: usize,
+        sink: &mut S,
+    ) -> Control {
+        let line_aware = self.opts.flags.contains(GrepFlags::LINE_NUMBER);
+        let per_line = if self
+            .opts
+            .flags
+            .intersects(GrepFlags::COUNT_LINES | GrepFlags::UNIQUE_LINES)
+        {
+            Some(1)
+        } else {
+            self.opts.max_matches_per_line
+        }
+        .filter(|_| line_aware);
+        let origin = state.origin;
+        let bytes_read = origin + global_base + chunk.len() as u64;
+
+        if self.matcher.is_empty_literal() {
+            // Empty needle convention: match at every position is nonsensical for grep.
+            // We follow our S0 API rules and report a single hit at the start of the stream.
+            if global_base == 0 && state.done == 0 {
+                let (line_no, col) = if line_aware { (1, 1) } else { (0, 0) };
+                let m = Match {
+                    off: origin,
+                    match_off: origin,
+                    len: 0,
+                    line_no,
+                    col,
+                    file_id: self.opts.file_id,
+                    pattern: 0,
+                    bytes_read,
+                };
+                state.total_count += 1;
+                sink.on_match_control(&m, None);
+            }
+            return Control::Stop;
+        }
+
+        // Repeatedly find all matches within the current chunk.
+        // Important: Chunker ensures that every *new* byte range (excluding the previous
+        // overlap except at the leading edge) is unique, so reporting here is safe.
+        let mut search_off = (state.done.saturating_sub(global_base) as usize).min(chunk.len());
+        // Start of the previous match when the search resumes right after it.
+        let mut prev = None;
+        loop {
+            let found = match prev.take() {
+                Some((p, pattern)) => self.matcher.find_after(chunk, p, pattern),
+                None => self.matcher.find_in(chunk, search_off),
+            };
+            let Some((pos, len, pattern)) = found else {
+                break;
+            };
+            if pos >= cut {
+                break;
+            }
+            let abs = origin + global_base + pos as u64;
+            if state.stop_at.is_some_and(|end| abs >= end) {
+                return Control::Stop;
+            }
+            let misaligned = self
+                .opts
+                .match_stride
+                .is_some_and(|s| !abs.is_multiple_of(s));
+            let too_close = self
+                .opts
+                .min_gap
+                .zip(state.last_reported)
+                .is_some_and(|(gap, last)| abs < last.saturating_add(gap));
+            let crosses_line = self.opts.flags.contains(GrepFlags::NO_CROSS_LINE)
+                && state
+                    .lines
+                    .separator()
+                    .find(&chunk[pos..pos + len])
+                    .is_some();
+            if misaligned
+                || too_close
+                || crosses_line
+                || state
+                    .skip
+                    .as_mut()
+                    .is_some_and(|skip| !skip.keep(chunk, global_base, pos))
+            {
+                if self.matcher.overlapping() {
+                    prev = Some((pos, pattern));
+                    search_off = pos + 1;
+                } else {
+                    search_off = pos + len.max(1);
+                }
+                if prev.is_none() && search_off >= chunk.len() {
+                    break;
+                }
+                continue;
+            }
+            let global_off = global_base + pos as u64;
+            let mut m = Match {
+                off: origin + global_off,
+                match_off: origin + global_off,
+                len: len as u32,
+                file_id: self.opts.file_id,
+                pattern,
+                bytes_read,
+                ..Match::default()
+            };
+            if line_aware {
+                state.lines.advance(chunk, global_base, global_off);
+                m.line_no = state.lines.line_no();
+                if let Some((first, last)) = self.opts.line_range {
+                    if m.line_no > last {
+                        return Control::Stop;
+                    }
+                    if m.line_no < first {
+                        // Resume on the next line.
+                        match state.lines.separator().find(&chunk[pos..]) {
+                            Some(i) => search_off = pos + i + state.lines.separator().len(),
+                            None => break,
+                        }
+                        continue;
+                    }
+                }
+                let line_start = (state.lines.line_start() - global_base) as usize;
+                m.col = self.column(&chunk[line_start..pos]);
+                if self.opts.flags.contains(GrepFlags::REPORT_LINE_OFFSET) {
+                    m.off = origin + state.lines.line_start();
+                }
+            }
+
+            let mut over_cap = false;
+            let mut line_full = false;
+            if let Some(cap) = per_line {
+                if state.line_hits.0 != m.line_no {
+                    state.line_hits = (m.line_no, 0);
+                }
+                over_cap = state.line_hits.1 >= cap;
+                if !over_cap {
+                    state.line_hits.1 += 1;
+                }
+                line_full = state.line_hits.1 >= cap;
+            }
+
+            if !over_cap {
+                state.total_count += 1;
+                state.last_reported = Some(abs);
+                if let Some(end) = state.match_end {
+                    end.set(abs + len as u64);
+                }
+                if self.opts.debug_stitch {
+                    let region = if pos < state.carried {
+                        MatchRegion::Stitch
+                    } else {
+                        MatchRegion::Fresh
+                    };
+                    sink.on_match_region(&m, region);
+                }
+                let line = line_aware.then(|| {
+                    let start = (state.lines.line_start() - global_base) as usize;
+                    let end = state
+                        .lines
+                        .separator()
+                        .find(&chunk[pos..])
+                        .map_or(chunk.len(), |i| pos + i);
+                    let line = &chunk[start..end];
+                    match self.opts.max_columns {
+                        Some(max) if line.len() > max => {
+                            truncate_line(line, max, &mut state.truncated)
+                        }
+                        _ => line,
+                    }
+                });
+                let control = sink.on_match_control(&m, line);
+                let base = origin + global_base;
+                self.matcher.captures(chunk, pos, &mut |group, start, len| {
+                    sink.on_capture(group, base + start as u64, len as u32)
+                });
+                if control == Control::Stop || state.limit_hit(&self.opts) {
+                    return Control::Stop;
+                }
+            }
+
+            if line_full {
+                // The rest of this line is skipped; resume right after its newline.
+                match state.lines.separator().find(&chunk[pos..]) {
+                    Some(i) => search_off = pos + i + state.lines.separator().len(),
+                    None => break,
+                }
+                continue;
+            }
+
+            // Move past this match to find subsequent occurrences (including overlaps
+            // for literals).
+            if self.matcher.overlapping() {
+                prev = Some((pos, pattern));
+                search_off = pos + 1;
+            } else {
+                search_off = pos + len.max(1);
+            }
+            if prev.is_none() && search_off >= chunk.len() {
+                break;
+            }
+        }
+
+        // Every match starting before `cut` was seen. A non-overlapping search
+        // resumes after the last match, which may end past `cut`.
+        state.done = state.done.max(global_base + cut.max(search_off) as u64);
+        if let Some(skip) = state.skip.as_mut() {
+            skip.chunk_done(chunk, global_base, cut);
+        }
+        if line_aware {
+            state
+                .lines
+                .advance(chunk, global_base, global_base + cut as u64);
+            // Every line in the range is done; don't read further.
+            if let Some((_, last)) = self.opts.line_range
+                && state.lines.line_no() > last
+            {
+                return Control::Stop;
+            }
+        }
+        Control::Continue
+    }
+
+    /// Returns the chunk overlap, checking an explicit one against the pattern.
+    fn overlap(&self) -> io::Result<usize> {
+        // A match of length `n` needs "n - 1" bytes from the previous chunk. An
+        // unbounded pattern (a regex) only has a default, which callers may lower.
+        let min = self.matcher.max_len_hint().saturating_sub(1);
+        match self.opts.overlap {
+            None => Ok(min),
+            Some(n) if n >= min || !self.matcher.bounded() => Ok(n),
+            Some(n) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "overlap {} is shorter than the {} bytes the pattern needs",
+                    n, min
+                ),
+            )),
+        }
+    }
+
+    /// Rejects the option values [`GrepOptions::validate`] would for this
+    /// engine's pattern.
+    fn check_options(&self) -> io::Result<()> {
+        // An unbounded pattern (a regex) takes any overlap.
+        let needle_len = if self.matcher.bounded() {
+            self.matcher.max_len_hint()
+        } else {
+            0
+        };
+        self.opts
+            .check_values(needle_len)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// Returns `GrepOptions::buffer_align`, checking it is a power of two.
+    fn buffer_align(&self) -> io::Result<Option<usize>> {
+        match self.opts.buffer_align {
+            Some(align) if !align.is_power_of_two() => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("buffer alignment {} is not a power of two", align),
+            )),
+            align => Ok(align),
+        }
+    }
+
+    /// Computes the 1-based column of a match given the bytes of its line before it.
+    fn column(&self, before: &[u8]) -> u32 {
+        let n = match self.opts.column_unit {
+            ColumnUnit::Byte => before.len(),
+            ColumnUnit::Char => char_count(before),
+        };
+        n as u32 + 1
+    }
+}
+
+/// Progress of one search, carried from chunk to chunk.
+struct ScanState<'s> {
+    /// Absolute offset of the first scanned byte.
+    origin: u64,
+    lines: LineTracker,
+    total_count: u64,
+    /// (line number, matches reported on it) for the per-line cap.
+    line_hits: (u32, u32),
+    /// Matches starting before this offset were already handled by an earlier
+    /// chunk. Only a custom overlap carries enough bytes to see them again.
+    done: u64,
+    /// Hook vetoing candidate matches.
+    skip: Option<&'s mut dyn SkipRegions>,
+    /// Number of carried bytes at the front of the current chunk.
+    carried: usize,
+    /// Offset where the previous chunk ended.
+    prev_end: u64,
+    /// Scratch space for a line shortened by `GrepOptions::max_columns`.
+    truncated: Vec<u8>,
+    /// Offset of the last reported match, for `GrepOptions::min_gap`.
+    last_reported: Option<u64>,
+    /// Absolute offset at which the search stops, for
+    /// [`GrepEngine::search_until`]: matches starting there or later are left
+    /// to a resumed search.
+    stop_at: Option<u64>,
+    /// Set to end the search, for [`GrepEngine::search_cancellable`].
+    cancel: Option<&'s AtomicBool>,
+    /// Absolute offset where the last reported match ends, for
+    /// [`SearchState::match_end`].
+    match_end: Option<&'s Cell<u64>>,
+}
+
+impl ScanState<'_> {
+    fn new(origin: u64, opts: &GrepOptions) -> Self {
+        Self {
+            origin,
+            lines: LineTracker::new().separated_by(opts.separator()),
+            total_count: 0,
+            line_hits: (0, 0),
+            done: 0,
+            skip: None,
+            carried: 0,
+            prev_end: 0,
+            truncated: Vec::new(),
+            last_reported: None,
+            stop_at: None,
+            cancel: None,
+            match_end: None,
+        }
+    }
+
+    fn limit_hit(&self, opts: &GrepOptions) -> bool {
+        opts.max_matches.is_some_and(|max| self.total_count >= max)
+    }
+}
+
+/// A search fed by pushing byte slices, e.g. network packets as they arrive.
+///
+/// Like the chunker, it carries the last bytes of each push into the next, so
+/// matches spanning pushes are found once. Offsets count from the first pushed
+/// byte. `GrepOptions::deadline` is not checked. Created by
+/// [`GrepEngine::push_searcher`].
+pub struct PushSearcher<'e, 'p> {
+    engine: &'e GrepEngine<'p>,
+    state: ScanState<'static>,
+    /// Carried bytes followed by pushed bytes not scanned yet.
+    buf: Vec<u8>,
+    /// Offset of `buf[0]`.
+    base: u64,
+    overlap: usize,
+    started: bool,
+    /// `strip_bom` is set and the input may still start with a BOM.
+    bom_pending: bool,
+    /// Set once `finish` ran or the sink stopped the search.
+    finished: bool,
+}
+
+impl PushSearcher<'_, '_> {
+    /// Appends `bytes` to the input, reporting the matches that can no longer
+    /// grow into later pushes.
+    pub fn push(&mut self, bytes: &[u8], sink: &mut dyn MatchSink) {
+        self.start(sink);
+        if self.finished {
+            return;
+        }
+        self.buf.extend_from_slice(bytes);
+        if self.bom_pending {
+            if self.buf.len() < UTF8_BOM.len() && UTF8_BOM.starts_with(&self.buf) {
+                return;
+            }
+            self.bom_pending = false;
+            if self.buf.starts_with(UTF8_BOM) {
+                self.buf.drain(..UTF8_BOM.len());
+            }
+        }
+        if self.buf.len() <= self.overlap {
+            return;
+        }
+        let (control, keep) = self.engine.step(
+            &mut self.state,
+            &self.buf,
+            self.base,
+            false,
+            self.overlap,
+            sink,
+        );
+        if control == Control::Stop {
+            self.finished = true;
+            self.buf.clear();
+            return;
+        }
+        let scanned = self.buf.len() - keep;
+        self.buf.drain(..scanned);
+        self.base += scanned as u64;
+    }
+
+    /// Ends the input, reporting the remaining matches and calling
+    /// `MatchSink::on_finish`. Later pushes are ignored.
+    pub fn finish(&mut self, sink: &mut dyn MatchSink) {
+        self.start(sink);
+        if !self.finished && !self.buf.is_empty() {
+            self.engine.step(
+                &mut self.state,
+                &self.buf,
+                self.base,
+                true,
+                self.overlap,
+                sink,
+            );
+        }
+        self.finished = true;
+        self.buf = Vec::new();
+        sink.on_finish(self.engine.opts.file_id, self.state.total_count);
+    }
+
+    fn start(&mut self, sink: &mut dyn MatchSink) {
+        if !self.started {
+            self.started = true;
+            sink.on_start(self.engine.opts.file_id);
+        }
+    }
+}
+
+/// Matches of a search, read lazily chunk by chunk. Created by
+/// [`GrepEngine::matches`].
+pub struct Matches<'e, 'p, 'r, R: Read> {
+    engine: &'e GrepEngine<'p>,
+    /// `None` once the input is exhausted or the search is over.
+    chunker: Option<Chunker<'r, R>>,
+    state: ScanState<'static>,
+    /// Matches of the last scanned chunk not yielded yet.
+    pending: Pending,
+    overlap: usize,
+    error: Option<io::Error>,
+}
+
+impl<R: Read> Iterator for Matches<'_, '_, '_, R> {
+    type Item = io::Result<Match>;
+
+    fn next(&mut self) -> Option<io::Result<Match>> {
+        loop {
+            if let Some(m) = self.pending.0.pop_front() {
+                return Some(Ok(m));
+            }
+            if let Some(e) = self.error.take() {
+                return Some(Err(e));
+            }
+            let chunker = self.chunker.as_mut()?;
+            if self
+                .engine
+                .opts
+                .deadline
+                .is_some_and(|d| Instant::now() >= d)
+            {
+                self.chunker = None;
+                return Some(Err(GrepError::timeout(self.state.total_count)));
+            }
+            let (global_base, chunk, eof) = match chunker.next_chunk_with_eof() {
+                Ok(Some(next)) => next,
+                Ok(None) => {
+                    self.chunker = None;
+                    continue;
+                }
+                Err(e) => {
+                    self.chunker = None;
+                    return Some(Err(e));
+                }
+            };
+            let (control, keep) = self.engine.step(
+                &mut self.state,
+                chunk,
+                global_base,
+                eof,
+                self.overlap,
+                &mut self.pending,
+            );
+            if control == Control::Stop || eof {
+                self.chunker = None;
+            } else {
+                chunker.retain(keep);
+            }
+        }
+    }
+}
+
+/// Collects the matches of one chunk for [`Matches`].
+struct Pending(VecDeque<Match>);
+
+impl ControlSink for Pending {
+    fn on_match_control(&mut self, m: &Match, _line: Option<&[u8]>) -> Control {
+        self.0.push_back(*m);
+        Control::Continue
+    }
+}
+
+/// A [`GrepEngine`] that owns its needle.
+///
+/// It is `Send + Sync` with no lifetime, so it can sit in an `Arc` shared by
+/// worker threads. Each search borrows it as a [`GrepEngine`] without rebuilding
+/// the finder.
+#[derive(Clone, Debug)]
+pub struct OwnedGrepEngine {
+    matcher: Arc<dyn Matcher>,
+    opts: GrepOptions,
+}
+
+impl OwnedGrepEngine {
+    /// Creates an engine for `needle`, copying it.
+    pub fn new_literal(needle: &[u8], opts: GrepOptions) -> Self {
+        Self {
+            matcher: Arc::new(literal_matcher(needle, &opts.flags).into_owned()),
+            opts,
+        }
+    }
+
+    /// Borrows this engine as a [`GrepEngine`].
+    pub fn engine(&self) -> GrepEngine<'_> {
+        GrepEngine {
+            matcher: MatcherRef::Borrowed(&*self.matcher),
+            opts: self.opts.clone(),
+        }
+    }
+
+    /// Returns the options this engine was built with.
+    pub fn options(&self) -> &GrepOptions {
+        &self.opts
+    }
+
+    /// See [`GrepEngine::search`].
+    pub fn search<R: Source>(&self, reader: &mut R, sink: &mut dyn MatchSink) -> io::Result<()> {
+        self.engine().search(reader, sink)
+    }
+
+    /// See [`GrepEngine::search_dyn`].
+    pub fn search_dyn(&self, reader: &mut dyn Read, sink: &mut dyn MatchSink) -> io::Result<()> {
+        self.engine().search_dyn(reader, sink)
+    }
+
+    /// See [`GrepEngine::search_control`].
+    pub fn search_control<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn ControlSink,
+    ) -> io::Result<()> {
+        self.engine().search_control(reader, sink)
+    }
+
+    /// See [`GrepEngine::search_slice`].
+    pub fn search_slice(&self, data: &[u8], sink: &mut dyn MatchSink) -> io::Result<()> {
+        self.engine().search_slice(data, sink)
+    }
+
+    /// See [`GrepEngine::any_match`].
+    pub fn any_match<R: Source>(&self, reader: &mut R) -> io::Result<bool> {
+        self.engine().any_match(reader)
+    }
+
+    /// See [`GrepEngine::contains_in`].
+    pub fn contains_in<R: Source>(&self, reader: &mut R) -> io::Result<bool> {
+        self.engine().contains_in(reader)
+    }
+
+    /// See [`GrepEngine::search_with_buffer`].
+    pub fn search_with_buffer<R: Source>(
+        &self,
+        reader: &mut R,
+        sink: &mut dyn MatchSink,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        self.engine().search_with_buffer(reader, sink, buf)
+    }
+}
+
+/// Reads needles from the file at `path`, one per line.
+///
+/// Only the `\n` terminator is removed; every other byte (including a `\r`
+/// before it) is part of the needle. The last needle may lack a terminator.
+/// Empty lines are skipped, since an empty needle would match everywhere.
+pub fn load_needles_from_file(path: &Path) -> io::Result<Vec<Vec<u8>>> {
+    load_needles_delimited(path, b'\n')
+}
+
+/// Like [`load_needles_from_file`], with needles ended by `terminator`
+/// (e.g. `b'\0'` for NUL-delimited lists).
+pub fn load_needles_delimited(path: &Path, terminator: u8) -> io::Result<Vec<Vec<u8>>> {
+    let data = fs::read(path)?;
+    Ok(data
+        .split(|&b| b == terminator)
+        .filter(|needle| !needle.is_empty())
+        .map(<[u8]>::to_vec)
+        .collect())
+}
+
+/// Escapes `s` into a regex matching exactly its bytes.
+///
+/// Regex metacharacters get a backslash, and bytes outside ASCII become
+/// `(?-u:\xNN)` so they match the raw byte rather than a Unicode character.
+/// The result is always ASCII, so it converts to the `&str` that
+/// [`GrepEngine::new_regex`] takes without loss.
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::engine::escape_literal;
+/// assert_eq!(escape_literal(b"a.c"), b"a\\.c");
+/// assert_eq!(escape_literal(b"\xff"), b"(?-u:\\xFF)");
+/// ```
+pub fn escape_literal(s: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len());
+    for &b in s {
+        if !b.is_ascii() {
+            out.extend_from_slice(format!("(?-u:\\x{:02X})", b).as_bytes());
+        } else if regex_syntax_meta(b) {
+            out.push(b'\\');
+            out.push(b);
+        } else {
+            out.push(b);
+        }
+    }
+    out
+}
+
+/// Whether `b` has a meaning in regex syntax (see `regex::escape`).
+fn regex_syntax_meta(b: u8) -> bool {
+    matches!(
+        b,
+        b'\\'
+            | b'.'
+            | b'+'
+            | b'*'
+            | b'?'
+            | b'('
+            | b')'
+            | b'|'
+            | b'['
+            | b']'
+            | b'{'
+            | b'}'
+            | b'^'
+            | b'$'
+            | b'#'
+            | b'&'
+            | b'-'
+            | b'~'
+    )
+}
+
+/// Width of an integer searched for with [`int_pattern`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntWidth {
+    /// Returns the width in bytes.
+    pub const fn bytes(self) -> usize {
+        match self {
+            IntWidth::U8 => 1,
+            IntWidth::U16 => 2,
+            IntWidth::U32 => 4,
+            IntWidth::U64 => 8,
+        }
+    }
+}
+
+/// Byte order of an integer searched for with [`int_pattern`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// Encodes `value` as a `width`-byte integer in `endian` order, the needle to
+/// search binary data for it with [`GrepEngine::new_literal`].
+///
+/// Negative values cast with `as u64` encode as two's complement, so
+/// `int_pattern(-2i16 as u64, IntWidth::U16, Endian::Little)` is
+/// `Some([0xFE, 0xFF])`.
+///
+/// # Returns
+///
+/// * `Some(needle)` - The encoded bytes
+/// * `None` - When `value` fits in `width` neither as an unsigned nor as a
+///   sign-extended signed integer
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::engine::{Endian, IntWidth, int_pattern};
+/// assert_eq!(int_pattern(0x0102, IntWidth::U16, Endian::Big), Some(vec![0x01, 0x02]));
+/// assert_eq!(int_pattern(0x0102, IntWidth::U16, Endian::Little), Some(vec![0x02, 0x01]));
+/// assert_eq!(int_pattern(0x1_0000, IntWidth::U16, Endian::Big), None);
+/// ```
+pub fn int_pattern(value: u64, width: IntWidth, endian: Endian) -> Option<Vec<u8>> {
+    let n = width.bytes();
+    let bits = 8 * n as u32;
+    let high = value.checked_shr(bits).unwrap_or(0);
+    let sign_extended = (value as i64).checked_shr(bits - 1).unwrap_or(0) == -1;
+    if high != 0 && !sign_extended {
+        return None;
+    }
+    Some(match endian {
+        Endian::Big => value.to_be_bytes()[8 - n..].to_vec(),
+        Endian::Little => value.to_le_bytes()[..n].to_vec(),
+    })
+}
+
+/// A match found by [`grep_bytes`], together with the line it is on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineMatch {
+    /// 1-based line number.
+    pub line_no: u32,
+    /// Offset of the match itself, as [`Match::match_off`] counts it: from the
+    /// start of the haystack, after a BOM dropped by `strip_bom`.
+    pub byte_offset: u64,
+    /// Contents of the matching line, without its trailing newline, shortened
+    /// as `GrepOptions::max_columns` asks.
+    pub line: Vec<u8>,
+}
+
+/// Searches an in-memory buffer and returns every match with its line.
+///
+/// Runs [`GrepEngine::search_slice`] in line-aware mode (`GrepFlags::LINE_NUMBER`
+/// is forced on) over `haystack`, so every `ByteRange` works. A line containing
+/// several matches yields one entry per match.
+///
+/// # Returns
+///
+/// * `Ok(matches)` - The matches in order
+/// * `Err(e)` - `InvalidInput` when `opts` holds a value a search rejects, e.g.
+///   `chunk_bytes: 0`
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::engine::{grep_bytes, GrepOptions};
+/// let hits = grep_bytes(b"one\ntwo needle\n", b"needle", &GrepOptions::default()).unwrap();
+/// assert_eq!(hits[0].line_no, 2);
+/// assert_eq!(hits[0].line, b"two needle");
+/// ```
+pub fn grep_bytes(
+    haystack: &[u8],
+    needle: &[u8],
+    opts: &GrepOptions,
+) -> io::Result<Vec<LineMatch>> {
+    struct Lines(Vec<LineMatch>);
+    impl MatchSink for Lines {
+        fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+        fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+            self.0.push(LineMatch {
+                line_no: m.line_no,
+                byte_offset: m.match_off,
+                line: line.to_vec(),
+            });
+        }
+    }
+
+    let mut opts = opts.clone();
+    opts.flags |= GrepFlags::LINE_NUMBER;
+    let mut lines = Lines(Vec::new());
+    GrepEngine::new_literal(needle, opts).search_slice(haystack, &mut lines)?;
+    Ok(lines.0)
 }
 
 /// A simple sink implementation that collects match data into vectors.
@@ -152,12 +2609,111 @@ impl<'p> GrepEngine<'p> {
 pub struct VecSink {
     pub offs: Vec<u64>,
     pub lens: Vec<u32>,
+    pub line_nos: Vec<u32>,
+    pub cols: Vec<u32>,
     pub file_ids: Vec<u32>,
 }
 impl MatchSink for VecSink {
-    fn on_match(&mut self, off: u64, len: u32, _line_no: u32, file_id: u32) {
-        self.offs.push(off);
-        self.lens.push(len);
-        self.file_ids.push(file_id);
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            col: 0,
+            file_id,
+            pattern: 0,
+            bytes_read: off + u64::from(len),
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.offs.push(m.off);
+        self.lens.push(m.len);
+        self.line_nos.push(m.line_no);
+        self.cols.push(m.col);
+        self.file_ids.push(m.file_id);
+    }
+}
+
+/// A sink that only counts matches, overall and per `file_id`.
+#[derive(Default, Debug)]
+pub struct CountSink {
+    pub total: u64,
+    pub per_file: HashMap<u32, u64>,
+}
+impl CountSink {
+    /// Returns the number of matches reported for `file_id`.
+    pub fn count_for(&self, file_id: u32) -> u64 {
+        self.per_file.get(&file_id).copied().unwrap_or(0)
+    }
+}
+impl MatchSink for CountSink {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, file_id: u32) {
+        self.total += 1;
+        *self.per_file.entry(file_id).or_insert(0) += 1;
+    }
+}
+
+/// A sink counting matches per pattern index (`Match::pattern`).
+///
+/// Use with [`GrepEngine::new_multi`]; `counts[i]` is the number of reported
+/// matches of the `i`-th needle. Single-pattern engines count everything at 0.
+#[derive(Default, Debug)]
+pub struct PerPatternCountSink {
+    pub counts: Vec<u64>,
+}
+impl PerPatternCountSink {
+    /// Creates a sink with a zero count for each of `patterns` needles.
+    pub fn new(patterns: usize) -> Self {
+        Self {
+            counts: vec![0; patterns],
+        }
+    }
+}
+impl MatchSink for PerPatternCountSink {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {
+        self.on_match_info(&Match::default());
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        let i = m.pattern as usize;
+        if i >= self.counts.len() {
+            self.counts.resize(i + 1, 0);
+        }
+        self.counts[i] += 1;
+    }
+}
+
+/// A sink keeping the matched bytes of an in-memory search as slices of the
+/// haystack.
+///
+/// Use it with [`GrepEngine::search_slice`] on the same `haystack`: reported
+/// offsets are then positions in it. Streaming searches reuse their chunk
+/// buffer, so their matches can't be borrowed; reporting offsets from another
+/// input gives wrong slices, or panics once they run past `haystack`.
+#[derive(Debug)]
+pub struct SliceSink<'h> {
+    haystack: &'h [u8],
+    pub matches: Vec<&'h [u8]>,
+}
+impl<'h> SliceSink<'h> {
+    /// Creates a sink slicing matches out of `haystack`.
+    pub fn new(haystack: &'h [u8]) -> Self {
+        Self {
+            haystack,
+            matches: Vec::new(),
+        }
+    }
+}
+impl MatchSink for SliceSink<'_> {
+    fn on_match(&mut self, off: u64, len: u32, _line_no: u32, _file_id: u32) {
+        let start = off as usize;
+        self.matches
+            .push(&self.haystack[start..start + len as usize]);
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.on_match(m.match_off, m.len, m.line_no, m.file_id);
     }
 }
@@ -0,0 +1,116 @@
+//! Error type for path-aware and higher-level entry points.
+//!
+//! The low-level building blocks (`Chunker::next_chunk`, `GrepEngine::search`)
+//! keep returning `io::Result` because they never know which file they read
+//! from. Helpers that open files wrap failures in [`GrepError`] so callers can
+//! tell which path failed; `From<io::Error>` keeps `?` working across the two.
+//!
+//! A search past `GrepOptions::deadline` fails with an `io::ErrorKind::TimedOut`
+//! error carrying [`GrepError::Timeout`]; both conversions unwrap it again.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors produced by the path-based search helpers.
+#[derive(Debug)]
+pub enum GrepError {
+    /// An I/O error, with the path being opened or read when it is known.
+    Io(io::Error, Option<PathBuf>),
+    /// An output format template could not be parsed.
+    Format(String),
+    /// `GrepOptions` combine settings that contradict each other.
+    Options(String),
+    /// A regular expression failed to compile.
+    Regex(regex::Error),
+    /// The search passed `GrepOptions::deadline`. Matches reported before that
+    /// stay with the sink; `matches` counts them.
+    Timeout { matches: u64, path: Option<PathBuf> },
+}
+
+impl GrepError {
+    /// Wraps an I/O error that happened while accessing `path`.
+    pub fn io_at(err: io::Error, path: impl Into<PathBuf>) -> Self {
+        match GrepError::from(err) {
+            GrepError::Io(err, _) => GrepError::Io(err, Some(path.into())),
+            GrepError::Timeout { matches, .. } => GrepError::Timeout {
+                matches,
+                path: Some(path.into()),
+            },
+            other => other,
+        }
+    }
+
+    /// The I/O error a search returns when it passes its deadline.
+    pub(crate) fn timeout(matches: u64) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::TimedOut,
+            GrepError::Timeout {
+                matches,
+                path: None,
+            },
+        )
+    }
+
+    /// Returns the path associated with this error, if any.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            GrepError::Io(_, path) | GrepError::Timeout { path, .. } => path.as_deref(),
+            GrepError::Format(_) | GrepError::Options(_) | GrepError::Regex(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for GrepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrepError::Io(err, Some(path)) => write!(f, "{}: {}", path.display(), err),
+            GrepError::Io(err, None) => write!(f, "{}", err),
+            GrepError::Format(msg) => write!(f, "invalid format template: {}", msg),
+            GrepError::Options(msg) => write!(f, "invalid options: {}", msg),
+            GrepError::Regex(err) => write!(f, "invalid regex: {}", err),
+            GrepError::Timeout {
+                matches,
+                path: Some(path),
+            } => write!(
+                f,
+                "{}: deadline passed after {} matches",
+                path.display(),
+                matches
+            ),
+            GrepError::Timeout {
+                matches,
+                path: None,
+            } => write!(f, "deadline passed after {} matches", matches),
+        }
+    }
+}
+
+impl Error for GrepError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GrepError::Io(err, _) => Some(err),
+            GrepError::Regex(err) => Some(err),
+            GrepError::Format(_) | GrepError::Options(_) | GrepError::Timeout { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for GrepError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::TimedOut
+            && err.get_ref().is_some_and(|inner| inner.is::<GrepError>())
+        {
+            let inner = err.into_inner().expect("checked above");
+            return *inner.downcast::<GrepError>().expect("checked above");
+        }
+        GrepError::Io(err, None)
+    }
+}
+
+impl From<regex::Error> for GrepError {
+    fn from(err: regex::Error) -> Self {
+        GrepError::Regex(err)
+    }
+}
@@ -0,0 +1,118 @@
+//! Byte buffers with a chosen start alignment.
+//!
+//! `Vec<u8>` only guarantees 1-byte alignment, so the first chunk of a search
+//! may start mid cache line and every SIMD load straddles two. [`AlignedBuf`]
+//! allocates with an explicit [`Layout`] instead and frees with the same one;
+//! a `Vec` must never take ownership of such memory, since it would free it
+//! with the wrong layout.
+
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+
+/// A zero-initialized, growable byte buffer whose start is aligned to `align`.
+pub struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    align: usize,
+}
+
+// SAFETY: the buffer uniquely owns its allocation, like `Vec<u8>`.
+unsafe impl Send for AlignedBuf {}
+// SAFETY: shared access only hands out `&[u8]`.
+unsafe impl Sync for AlignedBuf {}
+
+impl AlignedBuf {
+    /// Allocates `len` zeroed bytes starting on an `align`-byte boundary.
+    ///
+    /// # Panics
+    ///
+    /// If `align` is not a power of two or the size overflows `isize`.
+    pub fn new(len: usize, align: usize) -> Self {
+        let layout = Self::layout(len, align);
+        // SAFETY: `layout` has a non-zero size.
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Self { ptr, len, align }
+    }
+
+    /// Returns the alignment of the buffer start.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Grows the buffer to `new_len` bytes, zeroing the new ones. Existing bytes
+    /// are kept; a smaller `new_len` does nothing.
+    pub fn grow(&mut self, new_len: usize) {
+        if new_len <= self.len {
+            return;
+        }
+        let old = Self::layout(self.len, self.align);
+        let new = Self::layout(new_len, self.align);
+        // SAFETY: `ptr` was allocated with `old`, and `new.size()` is non-zero and
+        // valid for `align`. `realloc` keeps the alignment of `old`.
+        let ptr = unsafe { alloc::realloc(self.ptr.as_ptr(), old, new.size()) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(new));
+        // SAFETY: bytes `len..new_len` are inside the new allocation.
+        unsafe {
+            ptr.as_ptr()
+                .add(self.len)
+                .write_bytes(0, new_len - self.len)
+        };
+        self.ptr = ptr;
+        self.len = new_len;
+    }
+
+    /// Shrinks the buffer to `new_len` bytes, releasing the rest. A larger
+    /// `new_len` does nothing.
+    pub fn shrink(&mut self, new_len: usize) {
+        if new_len >= self.len {
+            return;
+        }
+        let old = Self::layout(self.len, self.align);
+        let new = Self::layout(new_len, self.align);
+        // SAFETY: as in `grow`; the first `new_len` bytes are kept.
+        let ptr = unsafe { alloc::realloc(self.ptr.as_ptr(), old, new.size()) };
+        self.ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(new));
+        self.len = new_len;
+    }
+
+    /// The layout of a `len`-byte buffer. Zero-length buffers still allocate one
+    /// byte, since the global allocator rejects zero-sized layouts.
+    fn layout(len: usize, align: usize) -> Layout {
+        Layout::from_size_align(len.max(1), align).expect("alignment must be a power of two")
+    }
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` initialized bytes owned by `self`.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as in `deref`, and `&mut self` makes the access exclusive.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` was allocated with exactly this layout.
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), Self::layout(self.len, self.align)) }
+    }
+}
+
+impl std::fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuf")
+            .field("len", &self.len)
+            .field("align", &self.align)
+            .finish()
+    }
+}
@@ -11,12 +11,157 @@
 //!
 //! Invariants:
 //! - Overlap = max(needle.len() - 1, 0).
+//! - `overlap < chunk_size`, so every chunk holds at least one fresh byte past
+//!   the carried tail. Constructors reject other combinations with
+//!   [`ChunkerError`].
 //! - Returned slices never exceed the valid data range in the internal buffer.
 //! - `global_offset` always points to the beginning of the returned slice in
 //!   the global stream.
+//!
+//! Callers that need more than `overlap` bytes of context (e.g. the line-aware
+//! engine, which must keep a partial line) can call [`Chunker::retain`] before
+//! the next read to carry a longer tail. Retained bytes are treated as not yet
+//! scanned, so they are returned once more even at EOF.
 
 use std::cmp;
-use std::io::{self, Read};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::atomic;
+
+use crate::io::aligned::AlignedBuf;
+
+/// The UTF-8 byte order mark.
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Invalid `Chunker` configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkerError {
+    /// The overlap does not leave room for fresh bytes in each chunk.
+    OverlapTooLarge { overlap: usize, chunk_size: usize },
+    /// A fixed buffer can't hold a chunk plus the overlap.
+    BufferTooSmall { len: usize, needed: usize },
+}
+
+impl fmt::Display for ChunkerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkerError::OverlapTooLarge {
+                overlap,
+                chunk_size,
+            } => write!(
+                f,
+                "chunker overlap ({}) must be smaller than chunk_size ({})",
+                overlap, chunk_size
+            ),
+            ChunkerError::BufferTooSmall { len, needed } => write!(
+                f,
+                "chunker buffer of {} bytes is smaller than chunk_size + overlap ({})",
+                len, needed
+            ),
+        }
+    }
+}
+
+impl Error for ChunkerError {}
+
+/// The chunker's working memory: a caller's `Vec`, an aligned allocation or a
+/// caller's fixed slice.
+enum Storage<'a> {
+    Vec(Vec<u8>),
+    Aligned(AlignedBuf),
+    Fixed(&'a mut [u8]),
+}
+
+impl<'a> Storage<'a> {
+    /// Grows to at least `len` bytes, zero-filling. Returns `false` if a fixed
+    /// buffer is shorter than `len`. With `zero_old`, an allocation that has to
+    /// move is zeroed before it is freed.
+    fn grow(&mut self, len: usize, zero_old: bool) -> bool {
+        match self {
+            Storage::Vec(v) if v.len() >= len => {}
+            Storage::Vec(v) if zero_old && v.capacity() < len => {
+                let mut new = Vec::with_capacity(len);
+                new.extend_from_slice(v);
+                new.resize(len, 0);
+                self.replace_zeroing(Storage::Vec(new));
+            }
+            Storage::Vec(v) => v.resize(len, 0),
+            Storage::Aligned(a) if zero_old && a.len() < len => {
+                let mut new = AlignedBuf::new(len, a.align());
+                new[..a.len()].copy_from_slice(a);
+                self.replace_zeroing(Storage::Aligned(new));
+            }
+            Storage::Aligned(a) => a.grow(len),
+            Storage::Fixed(s) => return s.len() >= len,
+        }
+        true
+    }
+
+    /// Shrinks to `len` bytes, releasing the memory past them. With `zero_old`,
+    /// the released allocation is zeroed first.
+    fn shrink(&mut self, len: usize, zero_old: bool) {
+        match self {
+            Storage::Vec(v) if zero_old => {
+                if v.capacity() > len {
+                    let new = v[..len.min(v.len())].to_vec();
+                    self.replace_zeroing(Storage::Vec(new));
+                }
+            }
+            Storage::Vec(v) => {
+                v.truncate(len);
+                v.shrink_to_fit();
+            }
+            Storage::Aligned(a) if zero_old && a.len() > len => {
+                let mut new = AlignedBuf::new(len, a.align());
+                new.copy_from_slice(&a[..len]);
+                self.replace_zeroing(Storage::Aligned(new));
+            }
+            Storage::Aligned(a) => a.shrink(len),
+            Storage::Fixed(_) => {}
+        }
+    }
+
+    /// Switches to `new`, zeroing the old storage before it is freed.
+    fn replace_zeroing(&mut self, new: Storage<'a>) {
+        mem::replace(self, new).zero();
+    }
+
+    /// Overwrites every byte with zero in a way the compiler can't elide, even
+    /// when the memory is freed right after.
+    fn zero(&mut self) {
+        for b in self.iter_mut() {
+            // SAFETY: `b` is a valid, exclusive reference to a byte.
+            unsafe { ptr::write_volatile(b, 0) };
+        }
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
+impl Deref for Storage<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Storage::Vec(v) => v,
+            Storage::Aligned(a) => a,
+            Storage::Fixed(s) => s,
+        }
+    }
+}
+
+impl DerefMut for Storage<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Storage::Vec(v) => v,
+            Storage::Aligned(a) => a,
+            Storage::Fixed(s) => s,
+        }
+    }
+}
 
 /// A chunked reader that processes data in fixed-size chunks with overlap.
 ///
@@ -26,7 +171,7 @@ use std::io::{self, Read};
 pub struct Chunker<'a, R: Read> {
     reader: &'a mut R,
     /// Working buffer (capacity >= chunk_size + overlap).
-    buf: Vec<u8>,
+    buf: Storage<'a>,
     /// Preferred chunk payload size (excluding overlap).
     chunk_size: usize,
     /// Number of bytes to carry from the previous tail.
@@ -37,6 +182,14 @@ pub struct Chunker<'a, R: Read> {
     eof: bool,
     /// Global offset for the next returned chunk.
     next_global_off: u64,
+    /// Caller-requested carry for the next chunk, overriding `overlap` once.
+    retain: Option<usize>,
+    /// Number of carried bytes at the front of the last returned chunk.
+    carried: usize,
+    /// Zero the buffer before it is freed or handed back.
+    zero_on_drop: bool,
+    /// Drop a leading UTF-8 BOM on the first read.
+    skip_bom: bool,
 }
 
 impl<'a, R: Read> Chunker<'a, R> {
@@ -52,20 +205,223 @@ impl<'a, R: Read> Chunker<'a, R> {
     ///
     /// The internal buffer capacity will be at least `chunk_size + overlap`,
     /// with a minimum of 4KB to ensure reasonable performance even with small chunk sizes.
+    ///
+    /// # Panics
+    ///
+    /// If `overlap >= chunk_size`; use [`Chunker::try_new`] to handle that case.
     pub fn new(reader: &'a mut R, chunk_size: usize, overlap: usize) -> Self {
-        // Ensure some minimum capacity so tiny chunk sizes still work.
-        let cap = cmp::max(4 * 1024, chunk_size.saturating_add(overlap));
-        Self {
+        Self::with_buffer(reader, chunk_size, overlap, Vec::new())
+    }
+
+    /// Like [`Chunker::new`], returning an error instead of panicking.
+    pub fn try_new(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+    ) -> Result<Self, ChunkerError> {
+        Self::try_with_buffer(reader, chunk_size, overlap, Vec::new())
+    }
+
+    /// Creates a new `Chunker` that reuses `buf` as its working buffer.
+    ///
+    /// The buffer is only grown (never shrunk) to the capacity `new` would use, so
+    /// passing back a buffer from [`Chunker::into_buffer`] avoids reallocating when
+    /// searching many inputs in a row. Its previous contents are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `overlap >= chunk_size`; use [`Chunker::try_with_buffer`] to handle that case.
+    pub fn with_buffer(reader: &'a mut R, chunk_size: usize, overlap: usize, buf: Vec<u8>) -> Self {
+        Self::try_with_buffer(reader, chunk_size, overlap, buf).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [`Chunker::with_buffer`], returning an error instead of panicking.
+    pub fn try_with_buffer(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        buf: Vec<u8>,
+    ) -> Result<Self, ChunkerError> {
+        Self::with_storage(reader, chunk_size, overlap, Storage::Vec(buf))
+    }
+
+    /// Like [`Chunker::new`], with every chunk starting on an `align`-byte
+    /// boundary (e.g. 64 for a cache line) so SIMD loads don't straddle lines.
+    ///
+    /// # Panics
+    ///
+    /// If `overlap >= chunk_size` or `align` is not a power of two.
+    pub fn with_alignment(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        align: usize,
+    ) -> Self {
+        Self::try_with_alignment(reader, chunk_size, overlap, align)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [`Chunker::with_alignment`], returning an error instead of panicking
+    /// on a too large overlap.
+    ///
+    /// # Panics
+    ///
+    /// If `align` is not a power of two.
+    pub fn try_with_alignment(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        align: usize,
+    ) -> Result<Self, ChunkerError> {
+        let buf = Storage::Aligned(AlignedBuf::new(0, align));
+        Self::with_storage(reader, chunk_size, overlap, buf)
+    }
+
+    /// Creates a `Chunker` working in `buf` only, never allocating a buffer of
+    /// its own.
+    ///
+    /// This bounds the chunker's memory, but like the rest of `io` it needs the
+    /// `std` feature, as it reads through `std::io::Read`; it does not make
+    /// searching possible without an allocator.
+    ///
+    /// `buf` must hold at least `chunk_size + overlap` bytes; there is no 4KB
+    /// minimum. Since the buffer can't grow, [`Chunker::retain`] can carry at
+    /// most `buf.len() - chunk_size` bytes: reading the next chunk after
+    /// retaining more fails with `io::ErrorKind::OutOfMemory`.
+    ///
+    /// # Panics
+    ///
+    /// If `overlap >= chunk_size` or `buf` is too small; use
+    /// [`Chunker::try_with_slice`] to handle those cases.
+    pub fn with_slice(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        buf: &'a mut [u8],
+    ) -> Self {
+        Self::try_with_slice(reader, chunk_size, overlap, buf).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like [`Chunker::with_slice`], returning an error instead of panicking.
+    pub fn try_with_slice(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        buf: &'a mut [u8],
+    ) -> Result<Self, ChunkerError> {
+        let needed = chunk_size.saturating_add(overlap);
+        if overlap < chunk_size && buf.len() < needed {
+            return Err(ChunkerError::BufferTooSmall {
+                len: buf.len(),
+                needed,
+            });
+        }
+        Self::with_storage(reader, chunk_size, overlap, Storage::Fixed(buf))
+    }
+
+    fn with_storage(
+        reader: &'a mut R,
+        chunk_size: usize,
+        overlap: usize,
+        mut buf: Storage<'a>,
+    ) -> Result<Self, ChunkerError> {
+        if overlap >= chunk_size {
+            return Err(ChunkerError::OverlapTooLarge {
+                overlap,
+                chunk_size,
+            });
+        }
+        // Ensure some minimum capacity so tiny chunk sizes still work. A fixed
+        // buffer was checked to hold `chunk_size + overlap` and stays as it is.
+        if !matches!(buf, Storage::Fixed(_)) {
+            buf.grow(
+                cmp::max(4 * 1024, chunk_size.saturating_add(overlap)),
+                false,
+            );
+        }
+        Ok(Self {
             reader,
-            buf: vec![0u8; cap],
+            buf,
             chunk_size,
             overlap,
             len: 0,
             eof: false,
             next_global_off: 0,
+            retain: None,
+            carried: 0,
+            zero_on_drop: false,
+            skip_bom: false,
+        })
+    }
+
+    /// Zeroes the working buffer when the chunker is dropped or its buffer is
+    /// returned by [`Chunker::into_buffer`], for input that may hold secrets.
+    /// Memory released when the buffer grows for retained bytes or shrinks in
+    /// [`Chunker::shrink_to`] is zeroed too, at the cost of a copy instead of a
+    /// reallocation. Off by default.
+    pub fn zero_on_drop(mut self, yes: bool) -> Self {
+        self.zero_on_drop = yes;
+        self
+    }
+
+    /// Drops a leading UTF-8 byte order mark ([`UTF8_BOM`]) from the stream, so
+    /// offset 0 is the first byte after it. Must be set before the first chunk
+    /// is read. Off by default.
+    pub fn skip_bom(mut self, yes: bool) -> Self {
+        self.skip_bom = yes;
+        self
+    }
+
+    /// Returns the size of the working buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Releases working memory past `cap` bytes, e.g. before returning a
+    /// long-lived chunker to a pool after a big search.
+    ///
+    /// Bytes still buffered for the next chunk are kept, so the buffer may stay
+    /// larger than `cap`. It grows again as needed on the next read.
+    pub fn shrink_to(&mut self, cap: usize) {
+        self.buf.shrink(cap.max(self.len), self.zero_on_drop);
+    }
+
+    /// Zeroes the working buffer and drops the bytes buffered in it.
+    ///
+    /// The next chunk starts with fresh input and carries nothing over; offsets
+    /// keep counting from where the dropped bytes ended.
+    pub fn clear(&mut self) {
+        self.next_global_off = self.next_global_off.saturating_add(self.len as u64);
+        self.len = 0;
+        self.carried = 0;
+        self.retain = None;
+        self.buf.zero();
+    }
+
+    /// Consumes the chunker and returns its working buffer for reuse.
+    ///
+    /// An aligned buffer can't become a `Vec` and is freed, and a fixed slice
+    /// stays with its owner; an empty `Vec` is returned instead.
+    pub fn into_buffer(mut self) -> Vec<u8> {
+        if self.zero_on_drop {
+            self.buf.zero();
+        }
+        match mem::replace(&mut self.buf, Storage::Vec(Vec::new())) {
+            Storage::Vec(v) => v,
+            Storage::Aligned(_) | Storage::Fixed(_) => Vec::new(),
         }
     }
 
+    /// Carries the last `n` bytes of the current chunk into the next one,
+    /// instead of the configured overlap.
+    ///
+    /// Applies to the next call only. The buffer grows if `n + chunk_size`
+    /// exceeds its capacity, so retaining an entire chunk still makes progress;
+    /// a fixed buffer from [`Chunker::with_slice`] can't grow.
+    pub fn retain(&mut self, n: usize) {
+        self.retain = Some(n);
+    }
+
     /// Reads the next chunk from the stream.
     ///
     /// # Returns
@@ -81,15 +437,51 @@ impl<'a, R: Read> Chunker<'a, R> {
     /// matches spanning chunk boundaries are not missed. It prevents infinite loops by
     /// not returning chunks that contain only previously-seen overlap bytes.
     pub fn next_chunk(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        Ok(self
+            .next_chunk_with_eof()?
+            .map(|(base, chunk, _)| (base, chunk)))
+    }
+
+    /// Reads the next chunk, separating the carried-over prefix from fresh bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((fresh_offset, overlap_len, chunk_slice)))` - `chunk_slice` is the whole
+    ///   chunk; its first `overlap_len` bytes were already returned by the previous call
+    ///   and the rest start at global offset `fresh_offset`
+    /// * `Ok(None)` - When the stream is exhausted
+    /// * `Err(e)` - On I/O errors
+    ///
+    /// Callers that stitch boundaries themselves can scan `chunk_slice[overlap_len..]`
+    /// only, looking into the prefix just for matches that cross into fresh bytes.
+    pub fn next_fresh_chunk(&mut self) -> io::Result<Option<(u64, usize, &[u8])>> {
+        if self.next_chunk_with_eof()?.is_none() {
+            return Ok(None);
+        }
+        let fresh = self.next_global_off + self.carried as u64;
+        Ok(Some((fresh, self.carried, &self.buf[..self.len])))
+    }
+
+    /// Reads the next chunk, also reporting whether it is the last one.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((global_offset, chunk_slice, eof)))` - As [`Chunker::next_chunk`], where
+    ///   `eof` is `true` when the reader hit end-of-stream while filling this chunk
+    /// * `Ok(None)` - When the stream is exhausted
+    /// * `Err(e)` - On I/O errors
+    pub fn next_chunk_with_eof(&mut self) -> io::Result<Option<(u64, &[u8], bool)>> {
         // If we already signaled EOF and have no buffered data, we are done.
         if self.eof && self.len == 0 {
             return Ok(None);
         }
 
         // Carry tail bytes from the previous chunk to the front.
-        let mut carry = 0usize;
-        if self.len > 0 && self.overlap > 0 {
-            carry = self.len.min(self.overlap);
+        // This also runs when the carry is zero (single-byte needles): the previous
+        // chunk must still be consumed and the global offset advanced past it.
+        let retained = self.retain.take();
+        if self.len > 0 {
+            let carry = self.len.min(retained.unwrap_or(self.overlap));
 
             // Advance global offset by the number of newly-consumed bytes
             // from the last returned chunk (len - carry).
@@ -103,15 +495,40 @@ impl<'a, R: Read> Chunker<'a, R> {
             }
             // Now the valid prefix is exactly the carried bytes.
             self.len = carry;
-        } else if self.len == 0 {
-            // First read; global offset starts at 0.
-            self.next_global_off = 0;
         }
+        self.carried = self.len;
 
-        // Read up to `chunk_size` fresh bytes after the carried prefix.
+        // Make sure a full chunk of fresh bytes fits after a long retained tail.
+        let want = self.len.saturating_add(self.chunk_size);
+        if !self.buf.grow(want, self.zero_on_drop) {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                format!(
+                    "fixed chunker buffer of {} bytes can't hold {} retained bytes plus a chunk",
+                    self.buf.len(),
+                    self.len
+                ),
+            ));
+        }
+
+        // Read up to `chunk_size` fresh bytes after the carried prefix. Once the
+        // reader reported end-of-stream it is not asked again.
         let mut filled = 0usize;
-        while filled < self.chunk_size {
-            let dst = &mut self.buf[self.len + filled..];
+        if mem::take(&mut self.skip_bom) {
+            // Read just far enough to tell whether the stream starts with a BOM.
+            while !self.eof && filled < UTF8_BOM.len() {
+                let n = self.reader.read(&mut self.buf[filled..UTF8_BOM.len()])?;
+                if n == 0 {
+                    self.eof = true;
+                }
+                filled += n;
+            }
+            if self.buf[..filled] == *UTF8_BOM {
+                filled = 0;
+            }
+        }
+        while !self.eof && filled < self.chunk_size {
+            let dst = &mut self.buf[self.len + filled..self.len + self.chunk_size];
             if dst.is_empty() {
                 break;
             }
@@ -130,10 +547,14 @@ impl<'a, R: Read> Chunker<'a, R> {
             return Ok(None);
         }
 
-        // When at EOF and the current buffer only contains the carried overlap
-        // (i.e., no new bytes were read), returning another chunk would repeat
-        // the same slice forever. Stop the iteration.
-        if self.eof && filled == 0 && self.len <= self.overlap && self.len > 0 {
+        // When at EOF and no new bytes were read, the buffer only holds the
+        // carried overlap. Those bytes were all part of the previous chunk, so a
+        // match ending in them, including one ending at the last byte of the
+        // stream, was already seen there; returning them again would repeat the
+        // same slice forever. Stop the iteration. A retained tail was not
+        // scanned yet, so it is still handed out one last time.
+        let nothing_fresh = filled == 0 && retained.is_none();
+        if self.eof && nothing_fresh {
             // Clear to make subsequent calls return None deterministically.
             self.len = 0;
             return Ok(None);
@@ -141,6 +562,228 @@ impl<'a, R: Read> Chunker<'a, R> {
 
         let base = self.next_global_off;
         let out = &self.buf[..self.len];
-        Ok(Some((base, out)))
+        Ok(Some((base, out, self.eof)))
+    }
+}
+
+impl<R: Read> Drop for Chunker<'_, R> {
+    fn drop(&mut self) {
+        if self.zero_on_drop {
+            self.buf.zero();
+        }
+    }
+}
+
+/// A source of overlapping chunks, as consumed by the engine.
+pub(crate) trait ChunkSource {
+    fn next_chunk_with_eof(&mut self) -> io::Result<Option<(u64, &[u8], bool)>>;
+    fn retain(&mut self, n: usize);
+}
+
+impl<R: Read> ChunkSource for Chunker<'_, R> {
+    fn next_chunk_with_eof(&mut self) -> io::Result<Option<(u64, &[u8], bool)>> {
+        Chunker::next_chunk_with_eof(self)
+    }
+
+    fn retain(&mut self, n: usize) {
+        Chunker::retain(self, n)
+    }
+}
+
+/// A chunker over a [`BufRead`] source that hands out the source's own buffer.
+///
+/// [`Chunker`] copies every byte into its working buffer, which doubles up with
+/// a `BufReader` the caller already has. `BufChunker` instead returns slices of
+/// `fill_buf` directly and only copies at buffer boundaries: the carried tail
+/// plus the first `max(overlap, tail)` bytes of the next buffer form a small
+/// stitch chunk, after which the rest of that buffer is returned in place.
+///
+/// Chunks are as [`Chunker::next_chunk_with_eof`] returns them: consecutive
+/// chunks share the carried bytes and [`BufChunker::retain`] works the same. A
+/// chunk that ends a buffer isn't known to be the last; the end of input is
+/// reported with the carried tail, and only when it was retained.
+pub struct BufChunker<'a, R: BufRead> {
+    reader: &'a mut R,
+    overlap: usize,
+    /// Bytes of the last stitch chunk, or the tail carried into the next one.
+    stitch: Vec<u8>,
+    last: Last,
+    /// Global offset of the last returned chunk.
+    base: u64,
+    retain: Option<usize>,
+    eof: bool,
+}
+
+/// Where the last returned chunk lives.
+#[derive(Clone, Copy)]
+enum Last {
+    None,
+    /// `fill_buf()[start..]`, not consumed yet.
+    Direct {
+        start: usize,
+    },
+    /// `stitch`, whose last `mirrored` bytes are `fill_buf()[..mirrored]`, not
+    /// consumed yet.
+    Stitch {
+        mirrored: usize,
+    },
+}
+
+impl<'a, R: BufRead> BufChunker<'a, R> {
+    /// Creates a chunker carrying `overlap` bytes between chunks.
+    pub fn new(reader: &'a mut R, overlap: usize) -> Self {
+        Self {
+            reader,
+            overlap,
+            stitch: Vec::new(),
+            last: Last::None,
+            base: 0,
+            retain: None,
+            eof: false,
+        }
+    }
+
+    /// See [`Chunker::retain`].
+    pub fn retain(&mut self, n: usize) {
+        self.retain = Some(n);
+    }
+
+    /// Reads the next chunk. See [`Chunker::next_chunk`].
+    pub fn next_chunk(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        Ok(self
+            .next_chunk_with_eof()?
+            .map(|(base, chunk, _)| (base, chunk)))
+    }
+
+    /// Reads the next chunk, also reporting whether it is the last one. See
+    /// [`Chunker::next_chunk_with_eof`].
+    pub fn next_chunk_with_eof(&mut self) -> io::Result<Option<(u64, &[u8], bool)>> {
+        if self.eof {
+            return Ok(None);
+        }
+
+        // Release the previous chunk, keeping its last `carry` bytes in `stitch`.
+        let retained = self.retain.take();
+        match self.last {
+            Last::None => {}
+            Last::Direct { start } => {
+                let buf = self.reader.fill_buf()?;
+                let end = buf.len();
+                let carry = retained.unwrap_or(self.overlap).min(end - start);
+                self.stitch.clear();
+                self.stitch.extend_from_slice(&buf[end - carry..]);
+                self.reader.consume(end);
+                self.base += (end - start - carry) as u64;
+            }
+            Last::Stitch { mirrored } => {
+                let len = self.stitch.len();
+                let carry = retained.unwrap_or(self.overlap).min(len);
+                self.base += (len - carry) as u64;
+                let avail = self.reader.fill_buf()?.len();
+                if carry <= mirrored && mirrored < avail {
+                    // The carried bytes are still in the source's buffer, followed
+                    // by fresh ones: hand the buffer out in place.
+                    let start = mirrored - carry;
+                    self.stitch.clear();
+                    self.last = Last::Direct { start };
+                    let buf = self.reader.fill_buf()?;
+                    return Ok(Some((self.base, &buf[start..], false)));
+                }
+                self.stitch.drain(..len - carry);
+                self.reader.consume(mirrored);
+            }
+        }
+
+        let carry = self.stitch.len();
+        let avail = self.reader.fill_buf()?.len();
+        if avail == 0 {
+            // As for `Chunker`, a carried tail is only new if it was retained.
+            self.eof = true;
+            if carry == 0 || retained.is_none() {
+                return Ok(None);
+            }
+            self.last = Last::Stitch { mirrored: 0 };
+            return Ok(Some((self.base, &self.stitch, true)));
+        }
+        if carry == 0 {
+            self.last = Last::Direct { start: 0 };
+            return Ok(Some((self.base, self.reader.fill_buf()?, false)));
+        }
+
+        // Stitch the tail to enough fresh bytes for any match starting in it.
+        let fresh = carry.max(self.overlap).min(avail);
+        let buf = self.reader.fill_buf()?;
+        self.stitch.extend_from_slice(&buf[..fresh]);
+        self.last = Last::Stitch { mirrored: fresh };
+        Ok(Some((self.base, &self.stitch, false)))
+    }
+}
+
+impl<R: BufRead> ChunkSource for BufChunker<'_, R> {
+    fn next_chunk_with_eof(&mut self) -> io::Result<Option<(u64, &[u8], bool)>> {
+        BufChunker::next_chunk_with_eof(self)
+    }
+
+    fn retain(&mut self, n: usize) {
+        BufChunker::retain(self, n)
+    }
+}
+
+/// A [`Chunker`] whose chunks never end inside a UTF-8 sequence.
+///
+/// When a chunk would end with the first bytes of a multi-byte character, those
+/// bytes are dropped from it and carried into the next chunk together with the
+/// usual overlap. Offsets stay byte offsets. The last chunk is returned as is, so
+/// a truncated sequence at the very end of the input is still handed out.
+/// Chunks may still *start* mid-character when the overlap cuts through one.
+pub struct Utf8Chunker<'a, R: Read> {
+    inner: Chunker<'a, R>,
+}
+
+impl<'a, R: Read> Utf8Chunker<'a, R> {
+    /// Wraps a new [`Chunker`]; see [`Chunker::new`].
+    pub fn new(reader: &'a mut R, chunk_size: usize, overlap: usize) -> Self {
+        Self {
+            inner: Chunker::new(reader, chunk_size, overlap),
+        }
+    }
+
+    /// Reads the next chunk, trimmed back to a character boundary.
+    ///
+    /// Same contract as [`Chunker::next_chunk`].
+    pub fn next_chunk(&mut self) -> io::Result<Option<(u64, &[u8])>> {
+        loop {
+            let (base, len, partial) = match self.inner.next_chunk_with_eof()? {
+                None => return Ok(None),
+                Some((base, chunk, true)) => (base, chunk.len(), 0),
+                Some((base, chunk, false)) => (base, chunk.len(), incomplete_utf8_tail(chunk)),
+            };
+            if partial > 0 {
+                let overlap = self.inner.overlap;
+                self.inner.retain(len.min(overlap + partial));
+            }
+            if partial < len {
+                return Ok(Some((base, &self.inner.buf[..len - partial])));
+            }
+            // The whole chunk is one unfinished character; read on.
+        }
+    }
+}
+
+/// Returns how many bytes at the end of `buf` form an unfinished UTF-8 sequence.
+fn incomplete_utf8_tail(buf: &[u8]) -> usize {
+    for back in 1..=buf.len().min(3) {
+        let b = buf[buf.len() - back];
+        if b & 0xC0 == 0x80 {
+            continue; // continuation byte, keep looking for the lead
+        }
+        let need = match b {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if need > back { back } else { 0 };
     }
+    0
 }
@@ -0,0 +1,49 @@
+//! Convenience collectors for quick tooling and tests.
+
+use std::io::{self, Read};
+
+use crate::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink};
+
+/// Collects each matching line once, in input order.
+struct LineCollector {
+    out: String,
+    last_line: u32,
+}
+
+impl MatchSink for LineCollector {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        if m.line_no == self.last_line {
+            return;
+        }
+        if self.last_line != 0 {
+            self.out.push('\n');
+        }
+        self.last_line = m.line_no;
+        self.out.push_str(&String::from_utf8_lossy(line));
+    }
+}
+
+/// Searches `reader` for `needle` and returns the matching lines joined by `\n`.
+///
+/// A line with several matches appears once. Invalid UTF-8 is replaced with
+/// `U+FFFD`. `GrepFlags::LINE_NUMBER` is always enabled.
+///
+/// # Returns
+///
+/// * `Ok(lines)` - The matching lines, without a trailing newline
+/// * `Err(e)` - On I/O errors
+pub fn collect_matching_lines<R: Read>(
+    mut reader: R,
+    needle: &[u8],
+    mut opts: GrepOptions,
+) -> io::Result<String> {
+    opts.flags |= GrepFlags::LINE_NUMBER;
+    let mut sink = LineCollector {
+        out: String::new(),
+        last_line: 0,
+    };
+    GrepEngine::new_literal(needle, opts).search(&mut reader, &mut sink)?;
+    Ok(sink.out)
+}
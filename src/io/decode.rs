@@ -0,0 +1,215 @@
+//! Streaming decoders that turn encoded input back into raw bytes.
+//!
+//! [`Base64Source`] and [`HexSource`] wrap any `Read` and are themselves
+//! `Read`, so they can be handed to the engine like any other source. Offsets
+//! reported by a search are then offsets into the *decoded* bytes.
+//!
+//! ASCII whitespace (including newlines) in the encoded stream is skipped, and
+//! groups split across reads of the inner reader are carried over. Malformed
+//! input fails the read with `io::ErrorKind::InvalidData`.
+
+use std::io::{self, Read};
+
+/// Size of the encoded-input scratch buffer.
+const RAW_BUF: usize = 8 * 1024;
+
+/// Incremental decoding state for one encoding.
+trait Decoder {
+    /// Feeds one non-whitespace input byte, appending decoded bytes to `out`.
+    fn push(&mut self, b: u8, out: &mut Vec<u8>) -> io::Result<()>;
+    /// Flushes any final group at end of input.
+    fn finish(&mut self, out: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// Shared buffering between the inner reader and a [`Decoder`].
+struct Decoding<R, D> {
+    inner: R,
+    decoder: D,
+    raw: Vec<u8>,
+    /// Decoded bytes not yet handed out, from `pos` on.
+    pending: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read, D: Decoder> Decoding<R, D> {
+    fn new(inner: R, decoder: D) -> Self {
+        Self {
+            inner,
+            decoder,
+            raw: vec![0; RAW_BUF],
+            pending: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.pending.len() {
+            if self.eof || buf.is_empty() {
+                return Ok(0);
+            }
+            self.pending.clear();
+            self.pos = 0;
+            let n = self.inner.read(&mut self.raw)?;
+            if n == 0 {
+                self.eof = true;
+                self.decoder.finish(&mut self.pending)?;
+                continue;
+            }
+            for &b in &self.raw[..n] {
+                if !b.is_ascii_whitespace() {
+                    self.decoder.push(b, &mut self.pending)?;
+                }
+            }
+        }
+        let n = buf.len().min(self.pending.len() - self.pos);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Base64 (standard alphabet) decoding state.
+struct Base64 {
+    quad: [u8; 4],
+    len: usize,
+    /// Set after `=` padding closed a group.
+    padded: bool,
+}
+
+impl Base64 {
+    fn value(b: u8) -> Option<u8> {
+        match b {
+            b'A'..=b'Z' => Some(b - b'A'),
+            b'a'..=b'z' => Some(b - b'a' + 26),
+            b'0'..=b'9' => Some(b - b'0' + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    /// Emits the bytes encoded by the first `self.len` sextets.
+    fn flush(&mut self, out: &mut Vec<u8>) -> io::Result<()> {
+        let q = self.quad;
+        match self.len {
+            0 => {}
+            1 => return Err(invalid("truncated base64 group".to_owned())),
+            n => {
+                let bytes = [
+                    q[0] << 2 | q[1] >> 4,
+                    q[1] << 4 | q[2] >> 2,
+                    q[2] << 6 | q[3],
+                ];
+                out.extend_from_slice(&bytes[..n - 1]);
+            }
+        }
+        self.quad = [0; 4];
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl Decoder for Base64 {
+    fn push(&mut self, b: u8, out: &mut Vec<u8>) -> io::Result<()> {
+        if b == b'=' {
+            if !self.padded {
+                self.flush(out)?;
+                self.padded = true;
+            }
+            return Ok(());
+        }
+        let v =
+            Self::value(b).ok_or_else(|| invalid(format!("invalid base64 byte 0x{:02x}", b)))?;
+        self.padded = false;
+        self.quad[self.len] = v;
+        self.len += 1;
+        if self.len == 4 {
+            self.flush(out)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> io::Result<()> {
+        self.flush(out)
+    }
+}
+
+/// Hex decoding state.
+struct Hex {
+    high: Option<u8>,
+}
+
+impl Decoder for Hex {
+    fn push(&mut self, b: u8, out: &mut Vec<u8>) -> io::Result<()> {
+        let v = (b as char)
+            .to_digit(16)
+            .ok_or_else(|| invalid(format!("invalid hex byte 0x{:02x}", b)))? as u8;
+        match self.high.take() {
+            Some(high) => out.push(high << 4 | v),
+            None => self.high = Some(v),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, _out: &mut Vec<u8>) -> io::Result<()> {
+        match self.high {
+            Some(_) => Err(invalid("odd number of hex digits".to_owned())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A reader that decodes standard base64 from `R`.
+///
+/// Padding is optional, and several padded blocks may follow each other.
+pub struct Base64Source<R> {
+    inner: Decoding<R, Base64>,
+}
+
+impl<R: Read> Base64Source<R> {
+    /// Wraps `inner`, which yields base64 text.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: Decoding::new(
+                inner,
+                Base64 {
+                    quad: [0; 4],
+                    len: 0,
+                    padded: false,
+                },
+            ),
+        }
+    }
+}
+
+impl<R: Read> Read for Base64Source<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// A reader that decodes hexadecimal digit pairs (either case) from `R`.
+pub struct HexSource<R> {
+    inner: Decoding<R, Hex>,
+}
+
+impl<R: Read> HexSource<R> {
+    /// Wraps `inner`, which yields hex text.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: Decoding::new(inner, Hex { high: None }),
+        }
+    }
+}
+
+impl<R: Read> Read for HexSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
@@ -0,0 +1,69 @@
+//! Opening compressed files by extension.
+//!
+//! [`open`] picks a decompressor from the file extension and returns a plain
+//! `Read` of the decompressed bytes, so compressed and raw files go through the
+//! same search path. Offsets reported by a search are then offsets into the
+//! *decompressed* stream.
+//!
+//! Each codec sits behind a cargo feature: `gzip` (`.gz`, via `flate2`), `zstd`
+//! (`.zst`) and `bzip2` (`.bz2`). A known extension whose feature is off fails
+//! with `io::ErrorKind::Unsupported` rather than searching compressed bytes.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// Compression formats recognized by extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Codec {
+    /// Returns the codec for `path`'s extension, or `None` for raw files.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Codec::Gzip),
+            "zst" => Some(Codec::Zstd),
+            "bz2" => Some(Codec::Bzip2),
+            _ => None,
+        }
+    }
+}
+
+/// Opens `path`, decompressing it if its extension names a known codec.
+///
+/// Files with any other extension (or none) are read as is.
+pub fn open(path: &Path) -> io::Result<Box<dyn Read>> {
+    let file = File::open(path)?;
+    match Codec::from_path(path) {
+        None => Ok(Box::new(file)),
+        Some(codec) => decoder(codec, file),
+    }
+}
+
+/// Wraps `file` in the decompressor for `codec`.
+fn decoder(codec: Codec, file: File) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::new(file);
+    match codec {
+        #[cfg(feature = "gzip")]
+        Codec::Gzip => Ok(Box::new(flate2::bufread::MultiGzDecoder::new(file))),
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => Ok(Box::new(zstd::stream::read::Decoder::with_buffer(file)?)),
+        #[cfg(feature = "bzip2")]
+        Codec::Bzip2 => Ok(Box::new(bzip2::bufread::MultiBzDecoder::new(file))),
+        #[allow(unreachable_patterns)]
+        _ => {
+            drop(file);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{:?} support is not enabled (see the cargo features)",
+                    codec
+                ),
+            ))
+        }
+    }
+}
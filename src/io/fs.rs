@@ -0,0 +1,160 @@
+//! Path-based search helpers.
+//!
+//! These open files on behalf of the caller and attach the offending path to
+//! any I/O failure, whether it happens on `open` or mid-stream. Reads go
+//! through a [`TruncationGuard`] so a file shrinking under us is handled per
+//! `GrepOptions::on_truncation` instead of silently looking like EOF.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::engine::{GrepEngine, GrepOptions, MatchSink, PreparedPattern, TruncationPolicy};
+use crate::error::GrepError;
+
+/// A reader that can report the current length of its underlying data.
+pub trait LenSource: Read {
+    /// Returns the current total length of the source in bytes.
+    fn current_len(&self) -> io::Result<u64>;
+}
+
+impl LenSource for File {
+    fn current_len(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+impl<T: AsRef<[u8]>> LenSource for io::Cursor<T> {
+    fn current_len(&self) -> io::Result<u64> {
+        Ok(self.get_ref().as_ref().len() as u64)
+    }
+}
+
+/// A reader adapter that detects truncation of a [`LenSource`].
+///
+/// When the inner reader reports end-of-file, the guard compares its position
+/// with the source's current length. If the source is now shorter, the input
+/// was truncated and `policy` applies; [`TruncationPolicy::Continue`] seeks the
+/// source back to its start.
+pub struct TruncationGuard<R> {
+    inner: R,
+    pos: u64,
+    policy: TruncationPolicy,
+}
+
+impl<R: LenSource> TruncationGuard<R> {
+    /// Wraps `inner`, handling truncation according to `policy`.
+    pub fn new(inner: R, policy: TruncationPolicy) -> Self {
+        Self {
+            inner,
+            pos: 0,
+            policy,
+        }
+    }
+}
+
+impl<R: LenSource + Seek> Read for TruncationGuard<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut n = self.inner.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            let len = self.inner.current_len()?;
+            if len < self.pos {
+                match self.policy {
+                    TruncationPolicy::Stop => {}
+                    TruncationPolicy::Error => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!(
+                                "input truncated to {} bytes after reading {}",
+                                len, self.pos
+                            ),
+                        ));
+                    }
+                    TruncationPolicy::Continue => {
+                        self.pos = self.inner.seek(SeekFrom::Start(0))?;
+                        n = self.inner.read(buf)?;
+                    }
+                }
+            }
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: LenSource + Seek> Seek for TruncationGuard<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+/// Opens the file at `path` and runs `engine` over its contents.
+///
+/// # Returns
+///
+/// * `Ok(())` - On successful completion
+/// * `Err(GrepError::Io(e, Some(path)))` - If the file can't be opened or a read fails
+pub fn search_path(
+    path: &Path,
+    engine: &GrepEngine<'_>,
+    sink: &mut dyn MatchSink,
+) -> Result<(), GrepError> {
+    search_path_with_buffer(path, engine, sink, &mut Vec::new())
+}
+
+/// Like [`search_path`], reusing `buf` as the chunker's working buffer.
+///
+/// Files are seekable, so every `GrepOptions::byte_range` is supported.
+pub fn search_path_with_buffer(
+    path: &Path,
+    engine: &GrepEngine<'_>,
+    sink: &mut dyn MatchSink,
+    buf: &mut Vec<u8>,
+) -> Result<(), GrepError> {
+    let file = File::open(path).map_err(|e| GrepError::io_at(e, path))?;
+    search_file_with_buffer(file, path, engine, sink, buf)
+}
+
+/// Runs `engine` over an opened `file`, attributing errors to `path`.
+pub(crate) fn search_file_with_buffer<R: LenSource + Seek>(
+    file: R,
+    path: &Path,
+    engine: &GrepEngine<'_>,
+    sink: &mut dyn MatchSink,
+    buf: &mut Vec<u8>,
+) -> Result<(), GrepError> {
+    let mut reader = TruncationGuard::new(file, engine.options().on_truncation);
+    engine
+        .seekable_with_buffer(&mut reader, sink, buf)
+        .map_err(|e| GrepError::io_at(e, path))
+}
+
+/// Searches each of `paths` in turn, e.g. a list from `git ls-files`.
+///
+/// The `file_id` of each match is the index of its path in `paths`, as in a
+/// [`FileRegistry`](crate::io::FileRegistry) built from them; `opts.file_id`
+/// is ignored. A file that can't be opened or read doesn't stop
+/// the search: its error, carrying the path, is collected and the next file is
+/// searched. One chunker buffer is reused across all files.
+///
+/// # Returns
+///
+/// The per-file errors in `paths` order; empty when every file was searched.
+pub fn search_paths(
+    paths: &[PathBuf],
+    needle: &[u8],
+    opts: &GrepOptions,
+    sink: &mut dyn MatchSink,
+) -> Vec<GrepError> {
+    let pattern = PreparedPattern::new(needle);
+    let mut buf = Vec::new();
+    let mut errors = Vec::new();
+    for (id, path) in paths.iter().enumerate() {
+        let engine = GrepEngine::from_prepared(&pattern, opts.clone().with_file_id(id as u32));
+        if let Err(e) = search_path_with_buffer(path, &engine, sink, &mut buf) {
+            errors.push(e);
+        }
+    }
+    errors
+}
@@ -1 +1,20 @@
-pub mod chunker;
\ No newline at end of file
+pub mod aligned;
+pub mod chunker;
+pub mod collect;
+pub mod decode;
+pub mod decompress;
+pub mod fs;
+pub mod output;
+pub mod pool;
+pub mod registry;
+pub mod stdin;
+#[cfg(feature = "testutil")]
+pub mod testutil;
+pub mod transform;
+pub mod walk;
+
+pub use collect::collect_matching_lines;
+pub use fs::{search_path, search_paths};
+pub use registry::FileRegistry;
+pub use stdin::search_stdin;
+pub use walk::search_dir;
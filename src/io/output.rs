@@ -0,0 +1,1229 @@
+//! Output sinks that write matches to a `Write`.
+//!
+//! [`PrintSink`] produces fixed `path:line:offset` records; [`FormatSink`]
+//! renders a user-supplied template per match. [`OffsetSink`] writes bare
+//! offsets for piping into other tools. [`HistogramSink`] prints nothing
+//! and instead summarizes where in the input the matches fall; [`RunSink`]
+//! collapses runs of overlapping matches. [`TtySink`] prints matching lines
+//! with their matches highlighted when writing to a terminal. [`SortingSink`]
+//! sits in front of another sink and reorders what reaches it, and
+//! [`SamplingSink`] thins it out; [`BufferedSink`] lets a formatting sink
+//! render into memory and writes the result in large blocks.
+//!
+//! Paths are kept as `PathBuf` all the way to the writer and only converted
+//! for display here, at the print boundary. [`PathStyle`] picks between a lossy
+//! UTF-8 rendering (invalid sequences become `U+FFFD`) and the raw bytes.
+//! Line text is written raw unless a sink is made `lossy`.
+
+use std::borrow::Cow;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::engine::{Match, MatchRegion, MatchSink};
+use crate::error::GrepError;
+
+/// How file paths are rendered in output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Convert to UTF-8, replacing invalid sequences with `U+FFFD`.
+    #[default]
+    Lossy,
+    /// Write the path bytes verbatim (Unix). Falls back to `Lossy` elsewhere.
+    Raw,
+}
+
+/// Writes `path` to `out` according to `style`.
+pub fn write_path<W: Write + ?Sized>(out: &mut W, path: &Path, style: PathStyle) -> io::Result<()> {
+    #[cfg(unix)]
+    if style == PathStyle::Raw {
+        use std::os::unix::ffi::OsStrExt;
+        return out.write_all(path.as_os_str().as_bytes());
+    }
+    let _ = style;
+    out.write_all(path.to_string_lossy().as_bytes())
+}
+
+/// Returns line `text` as written: verbatim, or if `lossy` with invalid UTF-8
+/// sequences replaced by `U+FFFD`.
+fn line_text(text: &[u8], lossy: bool) -> Cow<'_, [u8]> {
+    if !lossy {
+        return Cow::Borrowed(text);
+    }
+    match String::from_utf8_lossy(text) {
+        Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+        Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+    }
+}
+
+/// A sink printing one `path:line:offset` record per match.
+///
+/// `paths` maps `file_id` to its path; the line number is omitted when it is
+/// unknown (0), giving `path:offset`. Write errors can't be surfaced from
+/// `on_match`, so the first one is kept and returned by [`PrintSink::finish`].
+pub struct PrintSink<W: Write> {
+    out: W,
+    paths: Vec<PathBuf>,
+    style: PathStyle,
+    err: Option<io::Error>,
+}
+
+impl<W: Write> PrintSink<W> {
+    /// Creates a sink writing to `out`, resolving `file_id`s through `paths`.
+    pub fn new(out: W, paths: Vec<PathBuf>, style: PathStyle) -> Self {
+        Self {
+            out,
+            paths,
+            style,
+            err: None,
+        }
+    }
+
+    /// Flushes the writer and returns it, or the first write error.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_match(&mut self, m: &Match) -> io::Result<()> {
+        if let Some(path) = self.paths.get(m.file_id as usize) {
+            write_path(&mut self.out, path, self.style)?;
+            self.out.write_all(b":")?;
+        }
+        if m.line_no != 0 {
+            write!(self.out, "{}:", m.line_no)?;
+        }
+        writeln!(self.out, "{}", m.off)
+    }
+}
+
+impl<W: Write> WriterSink for PrintSink<W> {
+    type Writer = W;
+
+    fn writer_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+}
+
+impl<W: Write> MatchSink for PrintSink<W> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            col: 0,
+            file_id,
+            pattern: 0,
+            bytes_read: off + u64::from(len),
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        if self.err.is_none()
+            && let Err(e) = self.write_match(m)
+        {
+            self.err = Some(e);
+        }
+    }
+}
+
+/// A sink writing one decimal match offset per line.
+///
+/// [`OffsetSink::with_len`] and [`OffsetSink::with_file_id`] append the match
+/// length and file id as extra tab-separated columns, in that order. Output is
+/// buffered and flushed by [`OffsetSink::finish`], or on drop (ignoring errors).
+pub struct OffsetSink<W: Write> {
+    out: Option<BufWriter<W>>,
+    len: bool,
+    file_id: bool,
+    err: Option<io::Error>,
+}
+
+impl<W: Write> OffsetSink<W> {
+    /// Creates a sink writing offsets only to `out`.
+    pub fn new(out: W) -> Self {
+        Self {
+            out: Some(BufWriter::new(out)),
+            len: false,
+            file_id: false,
+            err: None,
+        }
+    }
+
+    /// Adds a column with the match length.
+    pub fn with_len(mut self) -> Self {
+        self.len = true;
+        self
+    }
+
+    /// Adds a column with the file id.
+    pub fn with_file_id(mut self) -> Self {
+        self.file_id = true;
+        self
+    }
+
+    /// Flushes the buffered output and returns the writer, or the first write error.
+    pub fn finish(mut self) -> io::Result<W> {
+        let out = self.out.take().expect("writer is only taken by finish");
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        out.into_inner().map_err(|e| e.into_error())
+    }
+
+    fn write_match(&mut self, off: u64, len: u32, file_id: u32) -> io::Result<()> {
+        let out = self.out.as_mut().expect("writer is only taken by finish");
+        write!(out, "{}", off)?;
+        if self.len {
+            write!(out, "\t{}", len)?;
+        }
+        if self.file_id {
+            write!(out, "\t{}", file_id)?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+impl<W: Write> MatchSink for OffsetSink<W> {
+    fn on_match(&mut self, off: u64, len: u32, _line_no: u32, file_id: u32) {
+        if self.err.is_none()
+            && let Err(e) = self.write_match(off, len, file_id)
+        {
+            self.err = Some(e);
+        }
+    }
+}
+
+impl<W: Write> Drop for OffsetSink<W> {
+    fn drop(&mut self) {
+        if let Some(out) = self.out.as_mut() {
+            let _ = out.flush();
+        }
+    }
+}
+
+/// A sink rendering matches into a writer it exposes.
+pub trait WriterSink: MatchSink {
+    type Writer: Write;
+
+    /// Returns the writer the sink renders into.
+    fn writer_mut(&mut self) -> &mut Self::Writer;
+}
+
+/// A sink batching the output of a formatting sink.
+///
+/// `inner` renders each match into its `Vec<u8>`; once that holds `block`
+/// bytes they are written to `out` in one call. The rest is written on every
+/// `on_finish` and by [`BufferedSink::finish`], or on drop (ignoring errors).
+/// The first write error is kept and returned by `finish`; output is dropped
+/// after it.
+pub struct BufferedSink<S: WriterSink<Writer = Vec<u8>>, W: Write> {
+    inner: S,
+    out: Option<W>,
+    block: usize,
+    err: Option<io::Error>,
+}
+
+impl<S: WriterSink<Writer = Vec<u8>>, W: Write> BufferedSink<S, W> {
+    /// Default block size.
+    pub const DEFAULT_BLOCK: usize = 64 * 1024;
+
+    /// Creates a sink forwarding to `inner` and writing its output to `out` in
+    /// blocks of [`BufferedSink::DEFAULT_BLOCK`] bytes.
+    pub fn new(inner: S, out: W) -> Self {
+        Self {
+            inner,
+            out: Some(out),
+            block: Self::DEFAULT_BLOCK,
+            err: None,
+        }
+    }
+
+    /// Sets the block size; at least one byte.
+    pub fn with_block(mut self, block: usize) -> Self {
+        self.block = block.max(1);
+        self
+    }
+
+    /// Writes the remaining output, flushes and returns the writer, or the
+    /// first write error.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_out(0);
+        let mut out = self.out.take().expect("writer is only taken by finish");
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        out.flush()?;
+        Ok(out)
+    }
+
+    /// Writes the rendered output to `out` once it holds at least `min` bytes.
+    fn write_out(&mut self, min: usize) {
+        let buf = self.inner.writer_mut();
+        if buf.is_empty() || buf.len() < min {
+            return;
+        }
+        if self.err.is_none()
+            && let Some(out) = self.out.as_mut()
+            && let Err(e) = out.write_all(buf)
+        {
+            self.err = Some(e);
+        }
+        buf.clear();
+    }
+}
+
+impl<S: WriterSink<Writer = Vec<u8>>, W: Write> MatchSink for BufferedSink<S, W> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.inner.on_match(off, len, line_no, file_id);
+        self.write_out(self.block);
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.inner.on_match_info(m);
+        self.write_out(self.block);
+    }
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        self.inner.on_match_line(m, line);
+        self.write_out(self.block);
+    }
+
+    fn on_start(&mut self, file_id: u32) {
+        self.inner.on_start(file_id);
+    }
+
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        self.inner.on_match_region(m, region);
+    }
+
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        self.inner.on_capture(group, off, len);
+    }
+
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        self.inner.on_chunk_boundary(global_off);
+    }
+
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        self.inner.on_finish(file_id, total_matches);
+        self.write_out(0);
+    }
+}
+
+impl<S: WriterSink<Writer = Vec<u8>>, W: Write> Drop for BufferedSink<S, W> {
+    fn drop(&mut self) {
+        self.write_out(0);
+        if let Some(out) = self.out.as_mut() {
+            let _ = out.flush();
+        }
+    }
+}
+
+/// A placeholder in a [`FormatSink`] template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    File,
+    Line,
+    Col,
+    Offset,
+    Len,
+    Text,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Literal(Vec<u8>),
+    Field(Field),
+}
+
+/// A sink that writes each match through a format template.
+///
+/// Supported placeholders are `{file}`, `{line}`, `{col}`, `{offset}`, `{len}`
+/// and `{text}` (the matching line, without its newline). `{{` and `}}` produce
+/// literal braces. `{line}`, `{col}` and `{text}` need the line-aware engine
+/// (`GrepFlags::LINE_NUMBER`); otherwise they expand to `0`, `0` and nothing.
+/// `{file}` resolves `file_id` through `paths` and is empty for unknown ids.
+pub struct FormatSink<W: Write> {
+    out: W,
+    pieces: Vec<Piece>,
+    paths: Vec<PathBuf>,
+    style: PathStyle,
+    lossy: bool,
+    err: Option<io::Error>,
+}
+
+impl<W: Write> FormatSink<W> {
+    /// Creates a sink writing to `out` using `template`.
+    ///
+    /// # Returns
+    ///
+    /// * `Err(GrepError::Format(_))` - On an unknown placeholder or an unmatched brace
+    pub fn new(out: W, template: &str, paths: Vec<PathBuf>) -> Result<Self, GrepError> {
+        Ok(Self {
+            out,
+            pieces: parse_template(template)?,
+            paths,
+            style: PathStyle::Lossy,
+            lossy: false,
+            err: None,
+        })
+    }
+
+    /// Sets how `{file}` renders paths (lossy by default).
+    pub fn with_path_style(mut self, style: PathStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Replaces invalid UTF-8 in `{text}` with `U+FFFD`, so stray bytes can't
+    /// garble a terminal. Off by default: the line is written raw.
+    pub fn lossy(mut self, yes: bool) -> Self {
+        self.lossy = yes;
+        self
+    }
+
+    /// Flushes the writer and returns it, or the first write error.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_match(&mut self, m: &Match, line: &[u8]) -> io::Result<()> {
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(bytes) => self.out.write_all(bytes)?,
+                Piece::Field(Field::File) => {
+                    if let Some(path) = self.paths.get(m.file_id as usize) {
+                        write_path(&mut self.out, path, self.style)?;
+                    }
+                }
+                Piece::Field(Field::Line) => write!(self.out, "{}", m.line_no)?,
+                Piece::Field(Field::Col) => write!(self.out, "{}", m.col)?,
+                Piece::Field(Field::Offset) => write!(self.out, "{}", m.off)?,
+                Piece::Field(Field::Len) => write!(self.out, "{}", m.len)?,
+                Piece::Field(Field::Text) => self.out.write_all(&line_text(line, self.lossy))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, m: &Match, line: &[u8]) {
+        if self.err.is_none()
+            && let Err(e) = self.write_match(m, line)
+        {
+            self.err = Some(e);
+        }
+    }
+}
+
+impl<W: Write> WriterSink for FormatSink<W> {
+    type Writer = W;
+
+    fn writer_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+}
+
+impl<W: Write> MatchSink for FormatSink<W> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            col: 0,
+            file_id,
+            pattern: 0,
+            bytes_read: off + u64::from(len),
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.record(m, b"");
+    }
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        self.record(m, line);
+    }
+}
+
+/// Whether a [`TtySink`] colors its output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Always emit ANSI color codes.
+    Always,
+    /// Never emit them.
+    Never,
+    /// Emit them only when writing to a terminal.
+    #[default]
+    Auto,
+}
+
+impl ColorChoice {
+    /// Returns whether to color output written to a terminal (`is_tty`) or not.
+    pub fn use_color(self, is_tty: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_tty,
+        }
+    }
+}
+
+const COLOR_PATH: &[u8] = b"\x1b[35m";
+const COLOR_LINE_NO: &[u8] = b"\x1b[32m";
+const COLOR_MATCH: &[u8] = b"\x1b[1;31m";
+const COLOR_RESET: &[u8] = b"\x1b[0m";
+
+/// The line a [`TtySink`] is collecting matches for.
+struct PendingLine {
+    file_id: u32,
+    line_no: u32,
+    text: Vec<u8>,
+    /// Byte ranges of the matches within `text`, with their pattern index.
+    spans: Vec<(usize, usize, u32)>,
+}
+
+/// A sink printing matching lines for a terminal, `path:line:text`, with the
+/// matches highlighted.
+///
+/// Each matching line is printed once with all of its matches marked, so the
+/// line-aware engine (`GrepFlags::LINE_NUMBER`) is expected, with byte columns
+/// (`ColumnUnit::Byte`). Without it, matches print as `path:offset`. The path
+/// is omitted for unknown `file_id`s. Write errors are kept and returned by
+/// [`TtySink::finish`], as in [`PrintSink`]. A line still collecting matches is
+/// printed by `on_finish` or `finish`; dropping the sink prints it too, but
+/// ignores write errors.
+///
+/// Matches are highlighted in bold red; with [`TtySink::with_palette`] each
+/// pattern of a multi-pattern engine gets its own color instead.
+pub struct TtySink<W: Write> {
+    out: Option<W>,
+    paths: Vec<PathBuf>,
+    style: PathStyle,
+    color: bool,
+    palette: Vec<Vec<u8>>,
+    lossy: bool,
+    pending: Option<PendingLine>,
+    err: Option<io::Error>,
+}
+
+impl<W: Write> TtySink<W> {
+    /// Creates a sink writing to `out`, resolving `file_id`s through `paths`.
+    ///
+    /// `is_tty` tells whether `out` is a terminal, which decides
+    /// `ColorChoice::Auto`; [`TtySink::detect`] asks the writer instead.
+    pub fn new(out: W, paths: Vec<PathBuf>, color: ColorChoice, is_tty: bool) -> Self {
+        Self {
+            out: Some(out),
+            paths,
+            style: PathStyle::Lossy,
+            color: color.use_color(is_tty),
+            palette: vec![COLOR_MATCH.to_vec()],
+            lossy: false,
+            pending: None,
+            err: None,
+        }
+    }
+
+    /// Like [`TtySink::new`], checking whether `out` is a terminal itself.
+    pub fn detect(out: W, paths: Vec<PathBuf>, color: ColorChoice) -> Self
+    where
+        W: IsTerminal,
+    {
+        let is_tty = out.is_terminal();
+        Self::new(out, paths, color, is_tty)
+    }
+
+    /// Sets how paths render (lossy by default).
+    pub fn with_path_style(mut self, style: PathStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Replaces invalid UTF-8 in printed lines with `U+FFFD`, so stray bytes
+    /// can't garble the terminal. Off by default: lines are written raw.
+    pub fn lossy(mut self, yes: bool) -> Self {
+        self.lossy = yes;
+        self
+    }
+
+    /// Highlights matches of pattern `i` (see `Match::pattern`) with the ANSI
+    /// escape sequence `palette[i % palette.len()]`, e.g. `b"\x1b[1;34m"`,
+    /// cycling when there are more patterns than colors.
+    ///
+    /// Overlapping matches are highlighted as one run in the color of the
+    /// match starting first, or of the lower pattern index if both start
+    /// together. An empty palette restores the default bold red.
+    pub fn with_palette(mut self, palette: Vec<Vec<u8>>) -> Self {
+        self.palette = if palette.is_empty() {
+            vec![COLOR_MATCH.to_vec()]
+        } else {
+            palette
+        };
+        self
+    }
+
+    /// Returns whether the output is colored.
+    pub fn colored(&self) -> bool {
+        self.color
+    }
+
+    /// Prints the last line, flushes the writer and returns it, or the first
+    /// write error.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush_line();
+        let mut out = self.out.take().expect("writer is only taken by finish");
+        if let Some(e) = self.err.take() {
+            return Err(e);
+        }
+        out.flush()?;
+        Ok(out)
+    }
+
+    fn out(&mut self) -> &mut W {
+        self.out.as_mut().expect("writer is only taken by finish")
+    }
+
+    /// Writes `bytes` wrapped in `color` if coloring.
+    fn paint(&mut self, color: &[u8], bytes: &[u8]) -> io::Result<()> {
+        if self.color {
+            self.out().write_all(color)?;
+            self.out().write_all(bytes)?;
+            self.out().write_all(COLOR_RESET)
+        } else {
+            self.out().write_all(bytes)
+        }
+    }
+
+    fn write_prefix(&mut self, file_id: u32) -> io::Result<()> {
+        if let Some(path) = self.paths.get(file_id as usize) {
+            let mut rendered = Vec::new();
+            write_path(&mut rendered, path, self.style)?;
+            self.paint(COLOR_PATH, &rendered)?;
+            self.out().write_all(b":")?;
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &PendingLine) -> io::Result<()> {
+        self.write_prefix(line.file_id)?;
+        self.paint(COLOR_LINE_NO, line.line_no.to_string().as_bytes())?;
+        self.out().write_all(b":")?;
+        // Overlapping matches are highlighted as one, in the color of the first;
+        // touching ones only if they come from the same pattern.
+        let mut spans = line.spans.clone();
+        spans.sort_unstable_by_key(|&(start, end, pattern)| (start, pattern, end));
+        let mut merged: Vec<(usize, usize, u32)> = Vec::with_capacity(spans.len());
+        for (start, end, pattern) in spans {
+            match merged.last_mut() {
+                Some(last) if start < last.1 || (start == last.1 && pattern == last.2) => {
+                    last.1 = last.1.max(end)
+                }
+                _ => merged.push((start, end, pattern)),
+            }
+        }
+        let mut at = 0;
+        // Each piece is converted alone, so the spans keep their byte offsets.
+        let lossy = self.lossy;
+        for (start, end, pattern) in merged {
+            self.out()
+                .write_all(&line_text(&line.text[at..start], lossy))?;
+            let color = self.palette[pattern as usize % self.palette.len()].clone();
+            self.paint(&color, &line_text(&line.text[start..end], lossy))?;
+            at = end;
+        }
+        self.out().write_all(&line_text(&line.text[at..], lossy))?;
+        self.out().write_all(b"\n")
+    }
+
+    /// Prints the pending line, if any.
+    fn flush_line(&mut self) {
+        if let Some(line) = self.pending.take() {
+            let res = self.write_line(&line);
+            self.record(res);
+        }
+    }
+
+    fn record(&mut self, res: io::Result<()>) {
+        if self.err.is_none()
+            && let Err(e) = res
+        {
+            self.err = Some(e);
+        }
+    }
+}
+
+impl<W: Write> MatchSink for TtySink<W> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            col: 0,
+            file_id,
+            pattern: 0,
+            bytes_read: off + u64::from(len),
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.flush_line();
+        let res = self
+            .write_prefix(m.file_id)
+            .and_then(|()| writeln!(self.out(), "{}", m.match_off));
+        self.record(res);
+    }
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        let same = self
+            .pending
+            .as_ref()
+            .is_some_and(|p| p.file_id == m.file_id && p.line_no == m.line_no);
+        if !same {
+            self.flush_line();
+            self.pending = Some(PendingLine {
+                file_id: m.file_id,
+                line_no: m.line_no,
+                text: line.to_vec(),
+                spans: Vec::new(),
+            });
+        }
+        let pending = self.pending.as_mut().expect("pending line was just set");
+        let start = (m.col.saturating_sub(1) as usize).min(pending.text.len());
+        let end = (start + m.len as usize).min(pending.text.len());
+        pending.spans.push((start, end, m.pattern));
+    }
+
+    fn on_finish(&mut self, _file_id: u32, _total_matches: u64) {
+        self.flush_line();
+    }
+}
+
+impl<W: Write> Drop for TtySink<W> {
+    fn drop(&mut self) {
+        // Best effort for a sink dropped without `finish` or `on_finish`.
+        if self.out.is_some() {
+            self.flush_line();
+            let _ = self.out().flush();
+        }
+    }
+}
+
+/// A sink that buffers matches and forwards them to `inner` sorted by
+/// `(file_id, off)`.
+///
+/// Buffered matches are flushed on every `on_finish` and by
+/// [`SortingSink::finish`]; a walker calls `on_finish` after each file, so
+/// sort across files by calling only `finish` at the end of a parallel search
+/// instead (see [`SortingSink::flush_on_finish`]). Every buffered match costs
+/// a `Match` plus a copy of its line in line-aware mode. With
+/// [`SortingSink::with_cap`] the buffer is flushed early once full, which bounds
+/// memory but only keeps each batch sorted.
+pub struct SortingSink<S: MatchSink> {
+    inner: S,
+    buf: Vec<(Match, Option<Box<[u8]>>)>,
+    cap: Option<usize>,
+    flush_on_finish: bool,
+}
+
+impl<S: MatchSink> SortingSink<S> {
+    /// Creates a sorting sink in front of `inner`.
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            cap: None,
+            flush_on_finish: true,
+        }
+    }
+
+    /// Flushes early once `cap` matches are buffered.
+    pub fn with_cap(mut self, cap: usize) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// Sets whether `on_finish` flushes the buffer (the default) or leaves it to
+    /// [`SortingSink::finish`].
+    pub fn flush_on_finish(mut self, yes: bool) -> Self {
+        self.flush_on_finish = yes;
+        self
+    }
+
+    /// Flushes the remaining matches and returns the inner sink.
+    pub fn finish(mut self) -> S {
+        self.flush();
+        self.inner
+    }
+
+    fn flush(&mut self) {
+        self.buf.sort_by_key(|(m, _)| (m.file_id, m.off));
+        for (m, line) in self.buf.drain(..) {
+            match line {
+                Some(line) => self.inner.on_match_line(&m, &line),
+                None => self.inner.on_match_info(&m),
+            }
+        }
+    }
+
+    fn push(&mut self, m: &Match, line: Option<&[u8]>) {
+        self.buf.push((*m, line.map(Box::from)));
+        if self.cap.is_some_and(|cap| self.buf.len() >= cap) {
+            self.flush();
+        }
+    }
+}
+
+impl<S: MatchSink> MatchSink for SortingSink<S> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            col: 0,
+            file_id,
+            pattern: 0,
+            bytes_read: off + u64::from(len),
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.push(m, None);
+    }
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        self.push(m, Some(line));
+    }
+
+    fn on_start(&mut self, file_id: u32) {
+        self.inner.on_start(file_id);
+    }
+
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        if self.flush_on_finish {
+            self.flush();
+        }
+        self.inner.on_finish(file_id, total_matches);
+    }
+}
+
+/// A sink printing every match as [`Match`]'s `Display` renders it,
+/// `file 0, line 3, offset 42, len 6`, one per line, for quick debugging.
+///
+/// Writes to stderr unless built with [`DebugSink::with_writer`]. Write errors
+/// are ignored.
+#[derive(Debug)]
+pub struct DebugSink<W: Write = io::Stderr> {
+    out: W,
+}
+
+impl DebugSink {
+    /// Creates a sink printing to stderr.
+    pub fn new() -> Self {
+        Self::with_writer(io::stderr())
+    }
+}
+
+impl Default for DebugSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write> DebugSink<W> {
+    /// Creates a sink printing to `out` instead.
+    pub fn with_writer(out: W) -> Self {
+        Self { out }
+    }
+
+    /// Consumes the sink and returns its writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> MatchSink for DebugSink<W> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        self.on_match_info(&Match {
+            off,
+            match_off: off,
+            len,
+            line_no,
+            file_id,
+            ..Match::default()
+        });
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        let _ = writeln!(self.out, "{m}");
+    }
+}
+
+/// A source of the current time, so [`SamplingSink`] can be tested without
+/// sleeping.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The real clock, [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A token bucket allowing `rate` events per second, in bursts of up to `rate`.
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// Takes a token if one is available at `now`.
+    fn take(&mut self, now: Instant) -> bool {
+        if let Some(last) = self.last {
+            let refill = now.saturating_duration_since(last).as_secs_f64() * self.rate;
+            self.tokens = (self.tokens + refill).min(self.rate);
+        }
+        self.last = Some(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A sink in front of `inner` that forwards only a sample of the matches, for
+/// output that can't keep up with dense results.
+///
+/// With [`SamplingSink::every_nth`] the 1st, (n+1)th, (2n+1)th... match seen is
+/// forwarded; with [`SamplingSink::per_second`] at most `rate` matches a second,
+/// after an initial burst of `rate`. Both limits can be combined, in which case
+/// a match must pass both. [`SamplingSink::total`] counts every match seen, and
+/// `on_start`/`on_finish` are always forwarded with the engine's totals.
+/// Capture groups and the `on_match_region` call before a match follow it.
+pub struct SamplingSink<S: MatchSink, C: Clock = SystemClock> {
+    inner: S,
+    clock: C,
+    every: u64,
+    bucket: Option<TokenBucket>,
+    total: u64,
+    forwarded: u64,
+    /// Whether the last match seen was forwarded, for its captures.
+    last_forwarded: bool,
+    /// The region reported for the next match, held until it is admitted.
+    region: Option<(Match, MatchRegion)>,
+}
+
+impl<S: MatchSink> SamplingSink<S> {
+    /// Forwards every `n`th match, starting with the first.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    pub fn every_nth(inner: S, n: u64) -> Self {
+        assert!(n > 0, "SamplingSink needs n of at least 1");
+        Self {
+            inner,
+            clock: SystemClock,
+            every: n,
+            bucket: None,
+            total: 0,
+            forwarded: 0,
+            last_forwarded: false,
+            region: None,
+        }
+    }
+
+    /// Forwards at most `rate` matches per second.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is not positive.
+    pub fn per_second(inner: S, rate: f64) -> Self {
+        Self::every_nth(inner, 1).with_rate(rate)
+    }
+}
+
+impl<S: MatchSink, C: Clock> SamplingSink<S, C> {
+    /// Also limits forwarded matches to `rate` per second.
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is not positive.
+    pub fn with_rate(mut self, rate: f64) -> Self {
+        assert!(rate > 0.0, "SamplingSink rate must be positive");
+        self.bucket = Some(TokenBucket {
+            rate,
+            tokens: rate,
+            last: None,
+        });
+        self
+    }
+
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock<C2: Clock>(self, clock: C2) -> SamplingSink<S, C2> {
+        SamplingSink {
+            inner: self.inner,
+            clock,
+            every: self.every,
+            bucket: self.bucket,
+            total: self.total,
+            forwarded: self.forwarded,
+            last_forwarded: self.last_forwarded,
+            region: self.region,
+        }
+    }
+
+    /// Returns the number of matches seen, forwarded or not.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the number of matches forwarded to the inner sink.
+    pub fn forwarded(&self) -> u64 {
+        self.forwarded
+    }
+
+    /// Returns the inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Consumes the sampler and returns the inner sink.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Counts a match and decides whether it is forwarded.
+    fn admit(&mut self) -> bool {
+        let nth = self.total.is_multiple_of(self.every);
+        self.total += 1;
+        let admit = nth
+            && match &mut self.bucket {
+                Some(bucket) => bucket.take(self.clock.now()),
+                None => true,
+            };
+        self.forwarded += u64::from(admit);
+        self.last_forwarded = admit;
+        if let Some((m, region)) = self.region.take()
+            && admit
+        {
+            self.inner.on_match_region(&m, region);
+        }
+        admit
+    }
+}
+
+impl<S: MatchSink, C: Clock> MatchSink for SamplingSink<S, C> {
+    fn on_match(&mut self, off: u64, len: u32, line_no: u32, file_id: u32) {
+        if self.admit() {
+            self.inner.on_match(off, len, line_no, file_id);
+        }
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        if self.admit() {
+            self.inner.on_match_info(m);
+        }
+    }
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        if self.admit() {
+            self.inner.on_match_line(m, line);
+        }
+    }
+
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        self.region = Some((*m, region));
+    }
+
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        if self.last_forwarded {
+            self.inner.on_capture(group, off, len);
+        }
+    }
+
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        self.inner.on_chunk_boundary(global_off);
+    }
+
+    fn on_start(&mut self, file_id: u32) {
+        self.inner.on_start(file_id);
+    }
+
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        self.inner.on_finish(file_id, total_matches);
+    }
+}
+
+/// A sink that counts matches per fixed-size bin of match offsets.
+///
+/// Bin `i` covers offsets `[i * bin_size, (i + 1) * bin_size)`, so an offset on a
+/// boundary belongs to the bin it starts. Bins after the last match are not
+/// included in the result.
+#[derive(Debug)]
+pub struct HistogramSink {
+    bin_size: u64,
+    bins: Vec<u64>,
+}
+
+impl HistogramSink {
+    /// Creates a histogram with bins of `bin_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `bin_size` is zero.
+    pub fn new(bin_size: u64) -> Self {
+        assert!(bin_size > 0, "histogram bin size must be non-zero");
+        Self {
+            bin_size,
+            bins: Vec::new(),
+        }
+    }
+
+    /// Returns the match counts per bin.
+    pub fn finish(self) -> Vec<u64> {
+        self.bins
+    }
+}
+
+impl MatchSink for HistogramSink {
+    fn on_match(&mut self, off: u64, _len: u32, _line_no: u32, _file_id: u32) {
+        let bin = (off / self.bin_size) as usize;
+        if bin >= self.bins.len() {
+            self.bins.resize(bin + 1, 0);
+        }
+        self.bins[bin] += 1;
+    }
+}
+
+/// A run of matches that overlap or touch, as collected by [`RunSink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub file_id: u32,
+    /// Offset of the first match.
+    pub start: u64,
+    /// Bytes from the first match start to the end of the last match.
+    pub len: u64,
+    /// Number of matches in the run.
+    pub count: u64,
+}
+
+/// A sink coalescing consecutive matches into [`Run`]s.
+///
+/// A match joins the current run when it starts no later than the run's end,
+/// i.e. it overlaps or touches the previous match; `aa` in `aaaaa` is a single
+/// run of 5 bytes and 4 matches. Runs use match starts (`Match::match_off`),
+/// so `REPORT_LINE_OFFSET` doesn't affect them, and never span two files.
+#[derive(Debug, Default)]
+pub struct RunSink {
+    runs: Vec<Run>,
+}
+
+impl RunSink {
+    /// Creates an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the runs in the order they were reported.
+    pub fn finish(self) -> Vec<Run> {
+        self.runs
+    }
+
+    fn push(&mut self, file_id: u32, off: u64, len: u32) {
+        let end = off + u64::from(len);
+        if let Some(run) = self.runs.last_mut()
+            && run.file_id == file_id
+            && off >= run.start
+            && off <= run.start + run.len
+        {
+            run.len = run.len.max(end - run.start);
+            run.count += 1;
+            return;
+        }
+        self.runs.push(Run {
+            file_id,
+            start: off,
+            len: u64::from(len),
+            count: 1,
+        });
+    }
+}
+
+impl MatchSink for RunSink {
+    fn on_match(&mut self, off: u64, len: u32, _line_no: u32, file_id: u32) {
+        self.push(file_id, off, len);
+    }
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.push(m.file_id, m.match_off, m.len);
+    }
+}
+
+fn parse_template(template: &str) -> Result<Vec<Piece>, GrepError> {
+    let mut pieces = Vec::new();
+    let mut lit = Vec::new();
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        lit.extend_from_slice(&rest.as_bytes()[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            lit.push(tail.as_bytes()[0]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err(GrepError::Format(format!(
+                "unmatched '}}' at byte {}",
+                template.len() - tail.len()
+            )));
+        }
+        let end = tail
+            .find('}')
+            .ok_or_else(|| GrepError::Format("unterminated '{'".to_owned()))?;
+        let field = match &tail[1..end] {
+            "file" => Field::File,
+            "line" => Field::Line,
+            "col" => Field::Col,
+            "offset" => Field::Offset,
+            "len" => Field::Len,
+            "text" => Field::Text,
+            other => {
+                return Err(GrepError::Format(format!(
+                    "unknown placeholder {{{}}}",
+                    other
+                )));
+            }
+        };
+        if !lit.is_empty() {
+            pieces.push(Piece::Literal(mem::take(&mut lit)));
+        }
+        pieces.push(Piece::Field(field));
+        rest = &tail[end + 1..];
+    }
+    lit.extend_from_slice(rest.as_bytes());
+    if !lit.is_empty() {
+        pieces.push(Piece::Literal(lit));
+    }
+    Ok(pieces)
+}
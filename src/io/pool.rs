@@ -0,0 +1,95 @@
+//! Reusable chunker buffers.
+//!
+//! Every search needs a working buffer of `chunk_bytes` plus overlap. Searching
+//! many files, possibly from several threads, would otherwise allocate (and
+//! zero) one per file. A [`BufferPool`] keeps returned buffers around and hands
+//! them out again; it pairs with [`GrepEngine::search_with_buffer`] and
+//! [`Chunker::with_buffer`](crate::io::chunker::Chunker::with_buffer).
+//!
+//! [`GrepEngine::search_with_buffer`]: crate::engine::GrepEngine::search_with_buffer
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A thread-safe pool of byte buffers.
+#[derive(Debug, Default)]
+pub struct BufferPool {
+    idle: Mutex<Vec<Vec<u8>>>,
+    created: AtomicUsize,
+    reused: AtomicUsize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes an idle buffer, or a new empty one if none is idle.
+    ///
+    /// The buffer goes back to the pool when the returned guard is dropped.
+    pub fn get(&self) -> PooledBuffer<'_> {
+        let buf = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let buf = match buf {
+            Some(buf) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                buf
+            }
+            None => {
+                self.created.fetch_add(1, Ordering::Relaxed);
+                Vec::new()
+            }
+        };
+        PooledBuffer {
+            pool: self,
+            buf: Some(buf),
+        }
+    }
+
+    /// Number of buffers created because none was idle.
+    pub fn created(&self) -> usize {
+        self.created.load(Ordering::Relaxed)
+    }
+
+    /// Number of times an idle buffer was handed out again.
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    fn put(&self, buf: Vec<u8>) {
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(buf);
+    }
+}
+
+/// A buffer borrowed from a [`BufferPool`], returned to it on drop.
+#[derive(Debug)]
+pub struct PooledBuffer<'a> {
+    pool: &'a BufferPool,
+    buf: Option<Vec<u8>>,
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        self.buf.as_ref().expect("buffer is present until drop")
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        self.buf.as_mut().expect("buffer is present until drop")
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        if let Some(buf) = self.buf.take() {
+            self.pool.put(buf);
+        }
+    }
+}
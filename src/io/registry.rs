@@ -0,0 +1,83 @@
+//! Mapping between `file_id`s and paths.
+//!
+//! The engine only knows a file by the `GrepOptions::file_id` it was given.
+//! [`FileRegistry`] hands out those ids for paths, in registration order, and
+//! resolves them back. Ids are positions in the registry, matching how
+//! [`search_paths`](crate::io::search_paths) and
+//! [`search_dir`](crate::io::search_dir) number files, so a registry built
+//! from their path lists resolves their matches. Output sinks take the same
+//! list through `Vec::from(registry)`.
+
+use std::path::{Path, PathBuf};
+
+/// Paths indexed by `file_id`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileRegistry {
+    paths: Vec<PathBuf>,
+}
+
+impl FileRegistry {
+    /// Creates an empty registry; the first registered path gets id 0, the
+    /// default `GrepOptions::file_id`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `path` and returns its id, one more than the last one handed
+    /// out. Registering a path twice gives it two ids.
+    pub fn register(&mut self, path: impl Into<PathBuf>) -> u32 {
+        let id = u32::try_from(self.paths.len()).expect("more than u32::MAX files registered");
+        self.paths.push(path.into());
+        id
+    }
+
+    /// Returns the path registered as `id`.
+    pub fn path(&self, id: u32) -> Option<&Path> {
+        self.paths.get(id as usize).map(PathBuf::as_path)
+    }
+
+    /// Returns the number of registered paths.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns whether no path is registered.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns the registered paths, indexed by id.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Iterates over `(id, path)` pairs in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Path)> {
+        self.paths
+            .iter()
+            .enumerate()
+            .map(|(id, path)| (id as u32, path.as_path()))
+    }
+}
+
+impl From<Vec<PathBuf>> for FileRegistry {
+    fn from(paths: Vec<PathBuf>) -> Self {
+        Self { paths }
+    }
+}
+
+impl From<FileRegistry> for Vec<PathBuf> {
+    fn from(registry: FileRegistry) -> Self {
+        registry.paths
+    }
+}
+
+impl<P: Into<PathBuf>> FromIterator<P> for FileRegistry {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        for path in iter {
+            registry.register(path);
+        }
+        registry
+    }
+}
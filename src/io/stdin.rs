@@ -0,0 +1,34 @@
+//! Standard input search helpers.
+//!
+//! `stdin().lock()` has a small internal buffer (8 KiB); for pipelines moving
+//! gigabytes that means many short reads. The helpers here wrap the source in
+//! a `BufReader` sized to `GrepOptions::chunk_bytes` so each chunk is filled
+//! with few large reads.
+
+use std::io::{self, BufReader, Read};
+
+use crate::engine::{GrepEngine, GrepOptions, MatchSink};
+
+/// Searches standard input for `needle`, reporting matches to `sink`.
+///
+/// # Returns
+///
+/// * `Ok(())` - On successful completion
+/// * `Err(e)` - On I/O errors
+pub fn search_stdin(needle: &[u8], opts: GrepOptions, sink: &mut dyn MatchSink) -> io::Result<()> {
+    search_buffered(io::stdin().lock(), needle, opts, sink)
+}
+
+/// Searches `reader` through a `BufReader` sized to `opts.chunk_bytes`.
+///
+/// This is the code path behind [`search_stdin`], exposed so it can be driven
+/// by any `Read` (and tested without a real stdin).
+pub fn search_buffered<R: Read>(
+    reader: R,
+    needle: &[u8],
+    opts: GrepOptions,
+    sink: &mut dyn MatchSink,
+) -> io::Result<()> {
+    let mut reader = BufReader::with_capacity(opts.chunk_bytes.max(1), reader);
+    GrepEngine::new_literal(needle, opts).search(&mut reader, sink)
+}
@@ -0,0 +1,228 @@
+//! Readers and filesystems for exercising the engine against awkward input.
+//!
+//! Real sources rarely fill the whole buffer they are given: pipes, sockets
+//! and decoders return whatever is at hand. [`ChunkedReader`] reproduces that
+//! on top of any reader, so chunking and overlap logic can be checked against
+//! the most fragmented read patterns.
+//!
+//! [`MemFs`] is an in-memory [`FsProvider`] for the walker, where symlink
+//! loops and permission errors are set up without touching the disk.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::io::walk::{FileKind, FsMetadata, FsProvider};
+
+/// A reader returning at most `max_per_read` bytes per `read` call.
+#[derive(Debug)]
+pub struct ChunkedReader<R> {
+    inner: R,
+    max_per_read: usize,
+}
+
+impl<R: Read> ChunkedReader<R> {
+    /// Wraps `inner`, capping every read at `max_per_read` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `max_per_read` is zero, since a reader returning no bytes signals EOF.
+    pub fn new(inner: R, max_per_read: usize) -> Self {
+        assert!(max_per_read > 0, "max_per_read must be non-zero");
+        Self {
+            inner,
+            max_per_read,
+        }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.max_per_read);
+        self.inner.read(&mut buf[..n])
+    }
+}
+
+/// Symlinks followed while resolving one path before giving up, as `ELOOP`.
+const MAX_LINKS: usize = 40;
+
+#[derive(Clone, Debug)]
+enum MemEntry {
+    File(Vec<u8>),
+    Dir,
+    Symlink(PathBuf),
+}
+
+/// An in-memory filesystem for walker tests.
+///
+/// Paths are used as given, so build the tree with the same absolute or
+/// relative form the walker is handed. Parent directories are created
+/// implicitly. Symlink targets may be relative to the link's directory.
+#[derive(Clone, Debug, Default)]
+pub struct MemFs {
+    entries: BTreeMap<PathBuf, MemEntry>,
+    denied: BTreeSet<PathBuf>,
+}
+
+impl MemFs {
+    /// Creates an empty filesystem.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file holding `data`.
+    pub fn file(self, path: impl AsRef<Path>, data: &[u8]) -> Self {
+        self.insert(path.as_ref(), MemEntry::File(data.to_vec()))
+    }
+
+    /// Adds an empty directory.
+    pub fn dir(self, path: impl AsRef<Path>) -> Self {
+        self.insert(path.as_ref(), MemEntry::Dir)
+    }
+
+    /// Adds a symlink at `path` pointing to `target`.
+    pub fn symlink(self, path: impl AsRef<Path>, target: impl Into<PathBuf>) -> Self {
+        self.insert(path.as_ref(), MemEntry::Symlink(target.into()))
+    }
+
+    /// Makes opening or listing `path` fail with `PermissionDenied`, as for a
+    /// file or directory without read permission. Its metadata stays readable.
+    pub fn deny(mut self, path: impl AsRef<Path>) -> Self {
+        self.denied.insert(path.as_ref().to_path_buf());
+        self
+    }
+
+    fn insert(mut self, path: &Path, entry: MemEntry) -> Self {
+        for parent in path.ancestors().skip(1) {
+            if parent.as_os_str().is_empty() {
+                break;
+            }
+            self.entries
+                .entry(parent.to_path_buf())
+                .or_insert(MemEntry::Dir);
+        }
+        self.entries.insert(path.to_path_buf(), entry);
+        self
+    }
+
+    /// Resolves every symlink in `path`, including a final one if `follow_last`.
+    fn resolve(&self, path: &Path, follow_last: bool, links: &mut usize) -> io::Result<PathBuf> {
+        let mut out = PathBuf::new();
+        let mut components = path.components().peekable();
+        while let Some(comp) = components.next() {
+            match comp {
+                Component::CurDir => continue,
+                Component::ParentDir => {
+                    out.pop();
+                    continue;
+                }
+                _ => out.push(comp),
+            }
+            let last = components.peek().is_none();
+            if let Some(MemEntry::Symlink(target)) = self.entries.get(&out)
+                && (follow_last || !last)
+            {
+                *links += 1;
+                if *links > MAX_LINKS {
+                    return Err(io::Error::other(format!(
+                        "too many levels of symbolic links: {}",
+                        path.display()
+                    )));
+                }
+                let target = out.parent().unwrap_or(Path::new("")).join(target);
+                out = self.resolve(&target, true, links)?;
+            }
+        }
+        Ok(out)
+    }
+
+    fn lookup(&self, path: &Path, follow_last: bool) -> io::Result<(PathBuf, &MemEntry)> {
+        let real = self.resolve(path, follow_last, &mut 0)?;
+        match self.entries.get(&real) {
+            Some(entry) => Ok((real, entry)),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no such file or directory: {}", path.display()),
+            )),
+        }
+    }
+
+    fn check_access(&self, real: &Path, path: &Path) -> io::Result<()> {
+        if self.denied.contains(real) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("permission denied: {}", path.display()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn metadata_of(entry: &MemEntry) -> FsMetadata {
+    match entry {
+        MemEntry::File(data) => FsMetadata {
+            kind: FileKind::File,
+            len: data.len() as u64,
+        },
+        MemEntry::Dir => FsMetadata {
+            kind: FileKind::Dir,
+            len: 0,
+        },
+        MemEntry::Symlink(target) => FsMetadata {
+            kind: FileKind::Symlink,
+            len: target.as_os_str().len() as u64,
+        },
+    }
+}
+
+impl FsProvider for MemFs {
+    type File = Cursor<Vec<u8>>;
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let (real, entry) = self.lookup(dir, true)?;
+        self.check_access(&real, dir)?;
+        if !matches!(entry, MemEntry::Dir) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("not a directory: {}", dir.display()),
+            ));
+        }
+        Ok(self
+            .entries
+            .keys()
+            .filter(|p| p.parent() == Some(real.as_path()))
+            .filter_map(|p| p.file_name())
+            .map(|name| dir.join(name))
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.lookup(path, true).map(|(_, entry)| metadata_of(entry))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.lookup(path, false)
+            .map(|(_, entry)| metadata_of(entry))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.lookup(path, true).map(|(real, _)| real)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Self::File> {
+        let (real, entry) = self.lookup(path, true)?;
+        self.check_access(&real, path)?;
+        match entry {
+            MemEntry::File(data) => Ok(Cursor::new(data.clone())),
+            _ => Err(io::Error::new(
+                io::ErrorKind::IsADirectory,
+                format!("is a directory: {}", path.display()),
+            )),
+        }
+    }
+}
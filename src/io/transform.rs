@@ -0,0 +1,46 @@
+//! Byte transforms applied to a source as it is read.
+//!
+//! [`MapSource`] runs a caller-supplied function over every buffer the inner
+//! reader fills, before the chunker sees it, e.g. to undo a repeating-key XOR
+//! or a stream cipher. The function must not change the length, so offsets
+//! reported by a search are offsets into the transformed stream, which are
+//! also offsets into the original one.
+
+use std::io::{self, Read};
+
+/// A reader passing each freshly read buffer through `f` in place.
+///
+/// `f` sees the bytes of one `read` call at a time, in stream order; a
+/// position-dependent transform keeps its own position across calls.
+pub struct MapSource<R, F> {
+    inner: R,
+    f: F,
+}
+
+impl<R: Read, F: FnMut(&mut [u8])> MapSource<R, F> {
+    /// Wraps `inner`, transforming its bytes with `f`.
+    pub fn new(inner: R, f: F) -> Self {
+        Self { inner, f }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, F: FnMut(&mut [u8])> Read for MapSource<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        (self.f)(&mut buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: std::fmt::Debug, F> std::fmt::Debug for MapSource<R, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapSource")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
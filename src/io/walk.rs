@@ -0,0 +1,323 @@
+//! Recursive directory search.
+//!
+//! The walker lists regular files under a root in a deterministic order
+//! (entries sorted by name, depth-first) and searches each one, assigning
+//! `file_id`s by position in that order. [`WalkOptions`] bounds the recursion
+//! depth and file sizes and controls whether symlinks are followed.
+//!
+//! Filesystem access goes through [`FsProvider`]. The plain functions use the
+//! real filesystem ([`StdFs`]); the `_in` variants take any provider, such as
+//! the in-memory `io::testutil::MemFs` for tests (with the `testutil`
+//! feature).
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Seek};
+use std::path::{Path, PathBuf};
+
+use crate::engine::{CountSink, GrepEngine, GrepOptions, MatchSink, PreparedPattern};
+use crate::error::GrepError;
+use crate::io::fs::{LenSource, TruncationGuard, search_file_with_buffer};
+use crate::io::pool::BufferPool;
+use crate::io::registry::FileRegistry;
+
+/// Per-file match counts for a directory search (`grep -rc`).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CountSummary {
+    /// `(path, count)` pairs in walk order.
+    pub files: Vec<(PathBuf, u64)>,
+    /// Total number of matches across all files.
+    pub total: u64,
+}
+
+/// Limits applied while walking a directory tree.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Maximum directory depth to descend into. `Some(0)` lists only the root's
+    /// direct entries; `None` is unlimited.
+    pub max_depth: Option<usize>,
+    /// Skip files whose size (from metadata, checked before opening) exceeds this.
+    pub max_filesize: Option<u64>,
+    /// Follow symbolic links to files and directories. Directory cycles are
+    /// detected and each real directory is visited once.
+    pub follow_symlinks: bool,
+}
+
+/// What kind of entry a path names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    /// Sockets, devices and the like, which the walker skips.
+    Other,
+}
+
+/// The parts of a path's metadata the walker looks at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FsMetadata {
+    pub kind: FileKind,
+    /// Size in bytes.
+    pub len: u64,
+}
+
+impl From<fs::Metadata> for FsMetadata {
+    fn from(meta: fs::Metadata) -> Self {
+        let ty = meta.file_type();
+        let kind = if ty.is_file() {
+            FileKind::File
+        } else if ty.is_dir() {
+            FileKind::Dir
+        } else if ty.is_symlink() {
+            FileKind::Symlink
+        } else {
+            FileKind::Other
+        };
+        Self {
+            kind,
+            len: meta.len(),
+        }
+    }
+}
+
+/// Filesystem operations the walker needs, mirroring `std::fs`.
+pub trait FsProvider {
+    /// An opened file.
+    type File: LenSource + Seek;
+
+    /// Lists the paths of the entries in `dir`, in any order.
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Returns the metadata of `path`, following symlinks.
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// Returns the metadata of `path` itself, without following a symlink.
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// Resolves `path` to its real location, following every symlink.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Opens the file at `path` for reading.
+    fn open(&self, path: &Path) -> io::Result<Self::File>;
+}
+
+/// The real filesystem.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdFs;
+
+impl FsProvider for StdFs {
+    type File = File;
+
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(dir)?.map(|e| e.map(|e| e.path())).collect()
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        fs::metadata(path).map(FsMetadata::from)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        fs::symlink_metadata(path).map(FsMetadata::from)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn open(&self, path: &Path) -> io::Result<File> {
+        File::open(path)
+    }
+}
+
+/// Lists the regular files under `root`, sorted depth-first by name.
+pub fn collect_files(root: &Path, walk_opts: &WalkOptions) -> Result<Vec<PathBuf>, GrepError> {
+    collect_files_in(&StdFs, root, walk_opts)
+}
+
+/// Like [`collect_files`], on the filesystem `fs`.
+pub fn collect_files_in<F: FsProvider>(
+    fs: &F,
+    root: &Path,
+    walk_opts: &WalkOptions,
+) -> Result<Vec<PathBuf>, GrepError> {
+    let mut out = Vec::new();
+    let mut visited = HashSet::new();
+    if walk_opts.follow_symlinks {
+        visited.insert(
+            fs.canonicalize(root)
+                .map_err(|e| GrepError::io_at(e, root))?,
+        );
+    }
+    walk(fs, root, 0, walk_opts, &mut visited, &mut out)?;
+    Ok(out)
+}
+
+fn walk<F: FsProvider>(
+    fs: &F,
+    dir: &Path,
+    depth: usize,
+    walk_opts: &WalkOptions,
+    visited: &mut HashSet<PathBuf>,
+    out: &mut Vec<PathBuf>,
+) -> Result<(), GrepError> {
+    let mut entries = fs.read_dir(dir).map_err(|e| GrepError::io_at(e, dir))?;
+    entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+    for path in entries {
+        let meta = if walk_opts.follow_symlinks {
+            fs.metadata(&path)
+        } else {
+            fs.symlink_metadata(&path)
+        };
+        let meta = meta.map_err(|e| GrepError::io_at(e, &path))?;
+
+        match meta.kind {
+            FileKind::Dir => {
+                if walk_opts.max_depth.is_some_and(|max| depth >= max) {
+                    continue;
+                }
+                if walk_opts.follow_symlinks {
+                    let real = fs
+                        .canonicalize(&path)
+                        .map_err(|e| GrepError::io_at(e, &path))?;
+                    if !visited.insert(real) {
+                        continue;
+                    }
+                }
+                walk(fs, &path, depth + 1, walk_opts, visited, out)?;
+            }
+            FileKind::File => {
+                if walk_opts.max_filesize.is_some_and(|max| meta.len > max) {
+                    continue;
+                }
+                out.push(path);
+            }
+            FileKind::Symlink | FileKind::Other => {}
+        }
+    }
+    Ok(())
+}
+
+/// Searches every file under `root`, reporting matches to `sink`.
+///
+/// The `file_id` of each match is the index of its file in the returned list,
+/// which `FileRegistry::from` resolves; `opts.file_id` is ignored. One chunker
+/// buffer is reused across all files.
+pub fn search_dir(
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    sink: &mut dyn MatchSink,
+) -> Result<Vec<PathBuf>, GrepError> {
+    search_dir_pooled(root, pattern, opts, walk_opts, sink, &BufferPool::new())
+}
+
+/// Like [`search_dir`], taking chunker buffers from `pool`.
+///
+/// Each file borrows a buffer for the duration of its search, so walkers on
+/// several threads sharing one pool settle at one buffer per thread.
+pub fn search_dir_pooled(
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    sink: &mut dyn MatchSink,
+    pool: &BufferPool,
+) -> Result<Vec<PathBuf>, GrepError> {
+    search_dir_in(&StdFs, root, pattern, opts, walk_opts, sink, pool)
+}
+
+/// Like [`search_dir_pooled`], on the filesystem `fs`.
+pub fn search_dir_in<F: FsProvider>(
+    fs: &F,
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    sink: &mut dyn MatchSink,
+    pool: &BufferPool,
+) -> Result<Vec<PathBuf>, GrepError> {
+    let files = FileRegistry::from(collect_files_in(fs, root, walk_opts)?);
+    for (id, path) in files.iter() {
+        let engine = GrepEngine::from_prepared(pattern, opts.clone().with_file_id(id));
+        let file = fs.open(path).map_err(|e| GrepError::io_at(e, path))?;
+        search_file_with_buffer(file, path, &engine, sink, &mut pool.get())?;
+    }
+    Ok(files.into())
+}
+
+/// Counts matches per file under `root`.
+///
+/// Files without matches are left out of the summary unless `include_zero` is set.
+pub fn count_dir(
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    include_zero: bool,
+) -> Result<CountSummary, GrepError> {
+    count_dir_in(&StdFs, root, pattern, opts, walk_opts, include_zero)
+}
+
+/// Like [`count_dir`], on the filesystem `fs`.
+pub fn count_dir_in<F: FsProvider>(
+    fs: &F,
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    include_zero: bool,
+) -> Result<CountSummary, GrepError> {
+    let mut sink = CountSink::default();
+    let pool = BufferPool::new();
+    let files = search_dir_in(fs, root, pattern, opts, walk_opts, &mut sink, &pool)?;
+    let files = files
+        .into_iter()
+        .enumerate()
+        .map(|(id, path)| (path, sink.count_for(id as u32)))
+        .filter(|&(_, n)| include_zero || n > 0)
+        .collect();
+    Ok(CountSummary {
+        files,
+        total: sink.total,
+    })
+}
+
+/// Lists the files under `root` that contain the pattern (`grep -rl`), in walk order.
+///
+/// Each file is read only up to its first match. With `invert`, lists the files
+/// without a match instead (`grep -rL`), which does require reading them whole.
+pub fn files_with_matches(
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    invert: bool,
+) -> Result<Vec<PathBuf>, GrepError> {
+    files_with_matches_in(&StdFs, root, pattern, opts, walk_opts, invert)
+}
+
+/// Like [`files_with_matches`], on the filesystem `fs`.
+pub fn files_with_matches_in<F: FsProvider>(
+    fs: &F,
+    root: &Path,
+    pattern: &PreparedPattern,
+    opts: &GrepOptions,
+    walk_opts: &WalkOptions,
+    invert: bool,
+) -> Result<Vec<PathBuf>, GrepError> {
+    let engine = GrepEngine::from_prepared(pattern, opts.clone());
+    let mut out = Vec::new();
+    for path in collect_files_in(fs, root, walk_opts)? {
+        let file = fs.open(&path).map_err(|e| GrepError::io_at(e, &path))?;
+        let mut reader = TruncationGuard::new(file, opts.on_truncation);
+        let found = engine
+            .contains_in(&mut reader)
+            .map_err(|e| GrepError::io_at(e, &path))?;
+        if found != invert {
+            out.push(path);
+        }
+    }
+    Ok(out)
+}
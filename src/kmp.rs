@@ -0,0 +1,105 @@
+//! Streaming literal search that never rescans a byte.
+//!
+//! The engine finds matches spanning chunk boundaries by carrying the last
+//! `needle.len() - 1` bytes of each chunk into the next and searching them
+//! again. [`KmpSearcher`] instead carries the Knuth-Morris-Pratt automaton
+//! state: how much of the needle the input seen so far ends with. Fed the
+//! input in pieces of any size, it reports the same matches as a search of
+//! the whole input, reading each byte exactly once.
+//!
+//! While no partial match is pending, the searcher skips ahead with `memchr`
+//! on the needle's first byte; only bytes after a candidate are stepped one at
+//! a time.
+
+use memchr::memchr;
+
+use crate::engine::Control;
+use crate::matcher::failure;
+
+/// An incremental KMP search for one literal needle.
+///
+/// Matches may overlap, as in the engine: `aa` matches `aaa` at 0 and 1.
+#[derive(Clone, Debug)]
+pub struct KmpSearcher {
+    needle: Vec<u8>,
+    fail: Vec<usize>,
+    /// Length of the needle prefix the input fed so far ends with.
+    state: usize,
+    /// Bytes fed so far.
+    pos: u64,
+}
+
+impl KmpSearcher {
+    /// Creates a searcher for `needle`, at stream offset 0. An empty needle
+    /// never matches.
+    pub fn new(needle: &[u8]) -> Self {
+        Self {
+            needle: needle.to_vec(),
+            fail: failure(needle),
+            state: 0,
+            pos: 0,
+        }
+    }
+
+    /// Returns the needle.
+    pub fn needle(&self) -> &[u8] {
+        &self.needle
+    }
+
+    /// Returns the number of bytes fed so far, each of which was scanned once.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns how many of the last bytes fed are a prefix of the needle: a
+    /// match may still start that far back from [`KmpSearcher::position`].
+    pub fn pending(&self) -> usize {
+        self.state
+    }
+
+    /// Forgets any partial match and starts over at offset 0.
+    pub fn reset(&mut self) {
+        self.state = 0;
+        self.pos = 0;
+    }
+
+    /// Scans the next `bytes` of the stream, calling `on_match` with the start
+    /// offset of every match that ends in them.
+    ///
+    /// Returns `Control::Stop` as soon as `on_match` does; the rest of `bytes`
+    /// is then skipped, and the offset of the next feed counts from the end of
+    /// `bytes` regardless.
+    pub fn feed(&mut self, bytes: &[u8], mut on_match: impl FnMut(u64) -> Control) -> Control {
+        let base = self.pos;
+        self.pos += bytes.len() as u64;
+        let n = self.needle.len();
+        if n == 0 {
+            return Control::Continue;
+        }
+        let mut i = 0;
+        while i < bytes.len() {
+            if self.state == 0 {
+                match memchr(self.needle[0], &bytes[i..]) {
+                    Some(skip) => i += skip,
+                    None => break,
+                }
+            }
+            let b = bytes[i];
+            while self.state > 0 && self.needle[self.state] != b {
+                self.state = self.fail[self.state - 1];
+            }
+            if self.needle[self.state] == b {
+                self.state += 1;
+            }
+            if self.state == n {
+                self.state = self.fail[n - 1];
+                let start = base + (i + 1) as u64 - n as u64;
+                if on_match(start) == Control::Stop {
+                    return Control::Stop;
+                }
+            }
+            i += 1;
+        }
+        Control::Continue
+    }
+}
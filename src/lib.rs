@@ -1,12 +1,26 @@
-use memchr::memmem;
+#![cfg_attr(not(feature = "std"), no_std)]
 
+use memchr::{memchr, memmem};
+
+#[cfg(feature = "std")]
 pub mod engine;
+#[cfg(feature = "std")]
+pub mod error;
+#[cfg(feature = "std")]
 pub mod io;
+#[cfg(feature = "std")]
+pub mod kmp;
+pub mod lines;
+#[cfg(feature = "std")]
+mod matcher;
+#[cfg(feature = "std")]
+pub mod skip;
 
 /// Returns the index of the first occurrence of `needle` in `haystack`.
 ///
-/// This function uses the efficient `memchr::memmem` implementation for searching.
-/// An empty needle will match at position 0.
+/// This function uses the efficient `memchr::memmem` implementation for searching,
+/// except for single-byte needles, which are located with `memchr`. An empty
+/// needle will match at position 0.
 ///
 /// # Arguments
 ///
@@ -27,10 +41,54 @@ pub mod io;
 /// assert_eq!(find(b"hello", b"xyz"), None);
 /// ```
 pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if cfg!(feature = "scalar") {
+        return find_scalar(haystack, needle);
+    }
+    match needle.len() {
+        0 => Some(0),
+        1 => memchr(needle[0], haystack),
+        _ => memmem::find(haystack, needle),
+    }
+}
+
+/// Like [`find`], comparing at every position one byte at a time.
+///
+/// This is the reference the vectorized search is checked against, and what
+/// `find` and the literal engine use with the `scalar` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::{find, find_scalar};
+/// assert_eq!(find_scalar(b"hello", b"ll"), find(b"hello", b"ll"));
+/// ```
+pub fn find_scalar(haystack: &[u8], needle: &[u8]) -> Option<usize> {
     if needle.is_empty() {
-        return Some(0)
+        return Some(0);
     }
-    memmem::find(haystack, needle)
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Returns whether literal searches run on SIMD instructions.
+///
+/// The searches go through `memchr`, which uses SSE2/AVX2 on x86-64 (AVX2
+/// when detected at runtime), NEON on aarch64 and simd128 on wasm32 when
+/// compiled with it. Elsewhere it falls back to a portable word-at-a-time
+/// search, with the same results. The `scalar` feature turns SIMD off on any
+/// target.
+///
+/// # Examples
+///
+/// ```rust
+/// if !simd_grep::simd_available() {
+///     eprintln!("note: searching without SIMD");
+/// }
+/// ```
+pub fn simd_available() -> bool {
+    !cfg!(feature = "scalar")
+        && (cfg!(target_arch = "x86_64")
+            || cfg!(all(target_arch = "aarch64", target_feature = "neon"))
+            || cfg!(all(target_arch = "wasm32", target_feature = "simd128")))
 }
 
 /// Checks whether `needle` is contained within `haystack`.
@@ -58,3 +116,104 @@ pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
 pub fn contains(haystack: &[u8], needle: &[u8]) -> bool {
     find(haystack, needle).is_some()
 }
+
+/// Checks whether `haystack` begins with `needle`.
+///
+/// Only the first `needle.len()` bytes are compared; unlike `find(..) == Some(0)`,
+/// a miss never scans the rest of the buffer. An empty needle always matches.
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::starts_with;
+/// assert!(starts_with(b"GET /index", b"GET "));
+/// assert!(!starts_with(b"GE", b"GET "));
+/// ```
+#[inline]
+pub fn starts_with(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.get(..needle.len()) == Some(needle)
+}
+
+/// Checks whether `haystack` ends with `needle`.
+///
+/// Only the last `needle.len()` bytes are compared. An empty needle always matches.
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::ends_with;
+/// assert!(ends_with(b"line\r\n", b"\r\n"));
+/// assert!(!ends_with(b"line\n", b"\r\n"));
+/// ```
+#[inline]
+pub fn ends_with(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .len()
+        .checked_sub(needle.len())
+        .is_some_and(|start| &haystack[start..] == needle)
+}
+
+/// Counts the lines in `haystack`, as the engine numbers them.
+///
+/// Every `\n` ends a line, and a non-empty buffer that doesn't end in `\n` has
+/// one more, unterminated, last line. An empty buffer has no lines.
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::count_lines;
+/// assert_eq!(count_lines(b"a\nb\n"), 2);
+/// assert_eq!(count_lines(b"a\nb"), 2);
+/// assert_eq!(count_lines(b""), 0);
+/// ```
+pub fn count_lines(haystack: &[u8]) -> usize {
+    let unterminated = !haystack.is_empty() && !haystack.ends_with(b"\n");
+    lines::count_newlines(haystack) + usize::from(unterminated)
+}
+
+/// Returns an iterator over every start offset of `needle` in `haystack`,
+/// including overlapping occurrences.
+///
+/// After each match the search resumes one byte later, the same way the engine
+/// reports matches. This differs from `memchr::memmem::find_iter`, which resumes
+/// after the end of each match and so never yields overlapping occurrences. An
+/// empty needle yields a single match at 0, like [`find`].
+///
+/// # Examples
+///
+/// ```rust
+/// use simd_grep::find_overlapping_iter;
+/// let offs: Vec<usize> = find_overlapping_iter(b"aaaaa", b"aa").collect();
+/// assert_eq!(offs, vec![0, 1, 2, 3]);
+/// ```
+pub fn find_overlapping_iter<'h, 'n>(haystack: &'h [u8], needle: &'n [u8]) -> OverlapIter<'h, 'n> {
+    OverlapIter {
+        haystack,
+        finder: memmem::Finder::new(needle),
+        pos: Some(0),
+    }
+}
+
+/// Iterator returned by [`find_overlapping_iter`].
+#[derive(Clone, Debug)]
+pub struct OverlapIter<'h, 'n> {
+    haystack: &'h [u8],
+    finder: memmem::Finder<'n>,
+    /// Where the next search starts; `None` once exhausted.
+    pos: Option<usize>,
+}
+
+impl Iterator for OverlapIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let pos = self.pos?;
+        if self.finder.needle().is_empty() {
+            self.pos = None;
+            return Some(0);
+        }
+        let found = self.finder.find(&self.haystack[pos..]).map(|i| pos + i);
+        self.pos = found.map(|at| at + 1);
+        found
+    }
+}
@@ -0,0 +1,278 @@
+//! Line bookkeeping for line-aware search (S2).
+//!
+//! [`LineTracker`] follows a position that only moves forward through the
+//! stream, counting `\n` bytes it passes over. The engine advances it to each
+//! reported match and, at the end of every chunk, to the point where the next
+//! chunk begins, so carried-over bytes are never counted twice.
+//!
+//! Counting goes through `memchr`, which scans with SIMD where available.
+//!
+//! Only `\n` ends a line, so `\r\n` needs no special state across chunks: a
+//! `\r` at the end of one chunk is simply the last byte of its line, and the
+//! line ends at the `\n` that starts the next chunk. The `\r` stays part of
+//! the line bytes handed to sinks.
+//!
+//! With `GrepOptions::record_separator` the tracker counts records ended by
+//! another byte or byte sequence instead. A multi-byte separator may straddle
+//! the position the tracker stops at; it is counted on the next advance, once
+//! its last byte is passed.
+//!
+//! [`LineIndex`] records every line start of an in-memory buffer instead, to
+//! map arbitrary offsets to lines and columns afterwards.
+
+use memchr::memchr_iter;
+#[cfg(feature = "std")]
+use memchr::{memchr, memmem, memrchr};
+
+/// Counts the `\n` bytes in `haystack`.
+pub fn count_newlines(haystack: &[u8]) -> usize {
+    memchr_iter(b'\n', haystack).count()
+}
+
+/// What ends a line (or record).
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub(crate) enum Separator {
+    Byte(u8),
+    /// A sequence of two or more bytes. Occurrences are taken left to right
+    /// without overlapping.
+    Seq(Box<memmem::Finder<'static>>),
+}
+
+#[cfg(feature = "std")]
+impl Separator {
+    /// A separator for `bytes`; an empty one stands for `\n`.
+    pub(crate) fn new(bytes: &[u8]) -> Self {
+        match bytes {
+            [] => Separator::Byte(b'\n'),
+            &[b] => Separator::Byte(b),
+            _ => Separator::Seq(Box::new(memmem::Finder::new(bytes).into_owned())),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Separator::Byte(_) => 1,
+            Separator::Seq(finder) => finder.needle().len(),
+        }
+    }
+
+    /// Returns the start of the first separator in `hay`.
+    pub(crate) fn find(&self, hay: &[u8]) -> Option<usize> {
+        match self {
+            Separator::Byte(b) => memchr(*b, hay),
+            Separator::Seq(finder) => finder.find(hay),
+        }
+    }
+}
+
+/// Tracks the line number and line start of a forward-moving stream position.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub(crate) struct LineTracker {
+    sep: Separator,
+    /// Global offset up to which newlines have been counted.
+    pos: u64,
+    /// 1-based number of the line containing `pos`.
+    line_no: u32,
+    /// Global offset where the line containing `pos` begins, just past the
+    /// last separator counted.
+    line_start: u64,
+    /// Every line start passed, when recording them for a [`LineIndex`].
+    starts: Option<Vec<u64>>,
+}
+
+#[cfg(feature = "std")]
+impl LineTracker {
+    pub(crate) fn new() -> Self {
+        Self::starting_at(1)
+    }
+
+    /// Starts at line `line_no` instead of line 1, for a stream resumed
+    /// partway through.
+    pub(crate) fn starting_at(line_no: u32) -> Self {
+        Self {
+            sep: Separator::Byte(b'\n'),
+            pos: 0,
+            line_no,
+            line_start: 0,
+            starts: None,
+        }
+    }
+
+    /// Also records the start of every line passed, for
+    /// [`LineTracker::take_starts`].
+    pub(crate) fn recording(mut self) -> Self {
+        self.starts = Some(Vec::new());
+        self
+    }
+
+    /// Returns the line starts recorded so far, in ascending order.
+    pub(crate) fn take_starts(&mut self) -> Vec<u64> {
+        self.starts.take().unwrap_or_default()
+    }
+
+    /// Counts lines ended by `sep` instead of `\n`.
+    pub(crate) fn separated_by(mut self, sep: Separator) -> Self {
+        self.sep = sep;
+        self
+    }
+
+    /// Returns what ends a line.
+    pub(crate) fn separator(&self) -> &Separator {
+        &self.sep
+    }
+
+    /// Moves the tracked position forward to global offset `to`.
+    ///
+    /// `chunk` must be the current chunk starting at global offset `base`, and
+    /// must cover `[self.pos, to)`.
+    pub(crate) fn advance(&mut self, chunk: &[u8], base: u64, to: u64) {
+        debug_assert!(base <= self.pos && self.pos <= to);
+        match &self.sep {
+            Separator::Byte(b) => {
+                let from = (self.pos - base) as usize;
+                let span = &chunk[from..(to - base) as usize];
+                if let Some(last) = memrchr(*b, span) {
+                    let pos = self.pos;
+                    let newlines = memchr_iter(*b, span);
+                    let count = match &mut self.starts {
+                        Some(starts) => {
+                            let before = starts.len();
+                            starts.extend(newlines.map(|i| pos + i as u64 + 1));
+                            starts.len() - before
+                        }
+                        None => newlines.count(),
+                    };
+                    self.line_no += count as u32;
+                    self.line_start = pos + last as u64 + 1;
+                }
+            }
+            Separator::Seq(finder) => {
+                // Look back for a separator that started before `pos` but ended
+                // after it, without recounting the last one counted.
+                let len = finder.needle().len() as u64;
+                let from = (self.pos + 1)
+                    .saturating_sub(len)
+                    .max(self.line_start)
+                    .max(base);
+                let span = &chunk[(from - base) as usize..(to - base) as usize];
+                let mut ends = finder.find_iter(span).map(|i| from + i as u64 + len);
+                if let Some(first) = ends.next() {
+                    let starts = &mut self.starts;
+                    let (count, last) = ends.fold((1, first), |(n, prev), end| {
+                        if let Some(starts) = starts {
+                            starts.push(prev);
+                        }
+                        (n + 1, end)
+                    });
+                    if let Some(starts) = starts {
+                        starts.push(last);
+                    }
+                    self.line_no += count;
+                    self.line_start = last;
+                }
+            }
+        }
+        self.pos = to;
+    }
+
+    /// Returns the offset in `chunk` (at global offset `base`, starting at the
+    /// tracked position) of the last line start in `chunk[..limit]`, or 0.
+    pub(crate) fn last_line_start(&self, chunk: &[u8], base: u64, limit: usize) -> usize {
+        match &self.sep {
+            Separator::Byte(b) => memrchr(*b, &chunk[..limit]).map_or(0, |i| i + 1),
+            Separator::Seq(_) => {
+                // Overlapping occurrences make a search from the end unreliable,
+                // so count forward as `advance` will.
+                let mut probe = self.clone();
+                probe.advance(chunk, base, base + limit as u64);
+                probe.line_start.saturating_sub(base) as usize
+            }
+        }
+    }
+
+    /// Returns the 1-based line number at the tracked position.
+    pub(crate) fn line_no(&self) -> u32 {
+        self.line_no
+    }
+
+    /// Returns the global offset of the start of the current line.
+    pub(crate) fn line_start(&self) -> u64 {
+        self.line_start
+    }
+}
+
+/// The line starts of an in-memory buffer, to map offsets to lines and
+/// columns without scanning the buffer again.
+///
+/// [`LineIndex::new`] splits a whole buffer at `\n`.
+/// [`GrepEngine::search_all_in_memory`] collects the line starts its search
+/// passes instead: split at `GrepOptions::record_separator`, and counted from
+/// the start of the searched window, after a stripped BOM or at a `byte_range`.
+///
+/// Columns count bytes, whatever `GrepOptions::column_unit` says: the index
+/// keeps no text to count characters in.
+///
+/// [`GrepEngine::search_all_in_memory`]: crate::engine::GrepEngine::search_all_in_memory
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Offset of the first byte of every line, ascending; always starts with
+    /// the start of the indexed window.
+    starts: Vec<u64>,
+    /// Offset just past the indexed window.
+    end: u64,
+}
+
+#[cfg(feature = "std")]
+impl LineIndex {
+    /// Indexes the lines of `haystack`.
+    pub fn new(haystack: &[u8]) -> Self {
+        let mut lines = LineTracker::new().recording();
+        lines.advance(haystack, 0, haystack.len() as u64);
+        Self::from_tracker(&mut lines, 0, haystack.len())
+    }
+
+    /// Builds the index of a window at offset `origin` and `len` bytes long
+    /// from `lines`, which recorded the window's lines relative to its start
+    /// and has passed all of it.
+    pub(crate) fn from_tracker(lines: &mut LineTracker, origin: u64, len: usize) -> Self {
+        let recorded = lines.take_starts();
+        let mut starts = Vec::with_capacity(recorded.len() + 1);
+        starts.push(origin);
+        starts.extend(recorded.into_iter().map(|start| origin + start));
+        Self {
+            starts,
+            end: origin + len as u64,
+        }
+    }
+
+    /// Returns the number of lines, as [`count_lines`](crate::count_lines)
+    /// counts them: text after the last separator is a line, but the empty
+    /// rest after a trailing separator is not, so an empty buffer has none.
+    pub fn line_count(&self) -> usize {
+        let empty_tail = self.starts.last() == Some(&self.end);
+        self.starts.len() - usize::from(empty_tail)
+    }
+
+    /// Returns the offset where 1-based line `line_no` starts, or `None` past
+    /// the end. After a trailing separator, the line past the last one starts
+    /// at the end, as in [`LineIndex::line_col`].
+    pub fn line_start(&self, line_no: u32) -> Option<u64> {
+        let i = (line_no as usize).checked_sub(1)?;
+        self.starts.get(i).copied()
+    }
+
+    /// Returns the 1-based line and byte column of offset `off`, or `None`
+    /// outside the indexed bytes. The end itself maps to just past the last
+    /// byte, where an append would go.
+    pub fn line_col(&self, off: u64) -> Option<(u32, u32)> {
+        if off < self.starts[0] || off > self.end {
+            return None;
+        }
+        let line = self.starts.partition_point(|&start| start <= off);
+        let col = off - self.starts[line - 1] + 1;
+        Some((line as u32, col as u32))
+    }
+}
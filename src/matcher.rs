@@ -0,0 +1,418 @@
+//! Match backends behind [`GrepEngine`](crate::engine::GrepEngine).
+//!
+//! The engine only needs two things from a backend: the next match at or after
+//! a position, and an upper bound on match length to size the chunk overlap.
+//! [`Matcher`] captures that, so literal, regex and multi-literal search share
+//! the same chunking, line tracking and reporting code.
+
+use std::fmt;
+
+use aho_corasick::{AhoCorasick, Input, MatchKind};
+use memchr::memmem::{Finder, FinderBuilder, Prefilter};
+use memchr::{memchr, memrchr};
+use regex::bytes::{Regex, RegexSet};
+
+/// Longest regex match the streaming engine guarantees to find whole.
+///
+/// Regexes have no fixed length, so chunks overlap by this much instead. Raise
+/// `GrepOptions::overlap` for longer matches.
+pub(crate) const REGEX_MAX_LEN: usize = 4096;
+
+/// Bytes before a chunk a regex sees as context: one UTF-8 encoded character,
+/// enough for `\b`, `^` and `\A` to tell what precedes the chunk.
+const REGEX_LOOK_BEHIND: usize = 4;
+
+/// A search backend.
+pub(crate) trait Matcher: fmt::Debug + Send + Sync {
+    /// Returns `(start, len, pattern)` of the leftmost match starting at or after
+    /// `from`, where `pattern` is the index of the needle that matched.
+    ///
+    /// Bytes before `from` may be consulted as context (e.g. for `\b`).
+    fn find_in(&self, hay: &[u8], from: usize) -> Option<(usize, usize, u32)>;
+
+    /// Upper bound on the length of a match, used to size the chunk overlap.
+    fn max_len_hint(&self) -> usize;
+
+    /// Whether no match is longer than [`Matcher::max_len_hint`], so a shorter
+    /// overlap would miss matches. False when the hint is only a default.
+    fn bounded(&self) -> bool {
+        true
+    }
+
+    /// Whether the next search resumes one byte after a match start, so matches
+    /// may overlap. Otherwise it resumes at the match end.
+    fn overlapping(&self) -> bool {
+        true
+    }
+
+    /// How many bytes before a chunk a match may depend on, e.g. for `\b` or
+    /// `^`. The engine hands them out again as context, not to be searched.
+    fn look_behind(&self) -> usize {
+        0
+    }
+
+    /// Whether matches are found line by line, so chunks must hold whole lines.
+    fn whole_lines(&self) -> bool {
+        false
+    }
+
+    /// For an overlapping matcher, finds the next match after one of `pattern`
+    /// starting at `prev`. Same as `find_in(hay, prev + 1)`, but may use the
+    /// previous match.
+    fn find_after(&self, hay: &[u8], prev: usize, pattern: u32) -> Option<(usize, usize, u32)> {
+        let _ = pattern;
+        self.find_in(hay, prev + 1)
+    }
+
+    /// Whether this is the empty literal, which the engine reports once at
+    /// offset 0 instead of at every position.
+    fn is_empty_literal(&self) -> bool {
+        false
+    }
+
+    /// The needle of a single-literal matcher.
+    fn literal(&self) -> Option<&[u8]> {
+        None
+    }
+
+    /// Calls `each(group, start, len)` for every capture group taking part in
+    /// the match `find_in` reported at `start`. Matchers without groups report
+    /// none.
+    fn captures(&self, hay: &[u8], start: usize, each: &mut dyn FnMut(u32, usize, usize)) {
+        let _ = (hay, start, each);
+    }
+
+    /// Copies the matcher into one that borrows nothing.
+    fn to_static(&self) -> Box<dyn Matcher>;
+}
+
+/// A single literal needle.
+#[derive(Clone, Debug)]
+pub(crate) struct LiteralMatcher<'n> {
+    finder: Finder<'n>,
+    /// Smallest period of a needle that overlaps itself (`aaa`, `abab`), or 0.
+    period: usize,
+}
+
+impl<'n> LiteralMatcher<'n> {
+    pub(crate) fn new(needle: &'n [u8], prefilter: bool) -> Self {
+        let prefilter = if prefilter {
+            Prefilter::Auto
+        } else {
+            Prefilter::None
+        };
+        Self {
+            finder: FinderBuilder::new()
+                .prefilter(prefilter)
+                .build_forward(needle),
+            period: overlap_period(needle),
+        }
+    }
+
+    pub(crate) fn into_owned(self) -> LiteralMatcher<'static> {
+        LiteralMatcher {
+            finder: self.finder.into_owned(),
+            period: self.period,
+        }
+    }
+
+    pub(crate) fn needle(&self) -> &[u8] {
+        self.finder.needle()
+    }
+}
+
+impl Matcher for LiteralMatcher<'_> {
+    #[inline]
+    fn find_in(&self, hay: &[u8], from: usize) -> Option<(usize, usize, u32)> {
+        let needle = self.finder.needle();
+        let hay = &hay[from..];
+        // A single byte is found with `memchr`, like `crate::find` does. Longer
+        // needles keep the prebuilt finder, which honors `GrepFlags::NO_PREFILTER`.
+        let pos = match needle.len() {
+            _ if cfg!(feature = "scalar") => crate::find_scalar(hay, needle),
+            1 => memchr(needle[0], hay),
+            _ => self.finder.find(hay),
+        };
+        pos.map(|p| (from + p, needle.len(), 0))
+    }
+
+    fn find_after(&self, hay: &[u8], prev: usize, _: u32) -> Option<(usize, usize, u32)> {
+        // No match can start less than one period after another, and a match one
+        // period later only needs the `period` bytes past the previous one checked.
+        // A run of matches (`aaa` in `aaaaaa…`) is then walked without searching.
+        let p = self.period;
+        let needle = self.finder.needle();
+        let end = prev + needle.len();
+        if p == 0 || end + p > hay.len() {
+            return self.find_in(hay, prev + 1);
+        }
+        if hay[end..end + p] == needle[needle.len() - p..] {
+            Some((prev + p, needle.len(), 0))
+        } else {
+            self.find_in(hay, prev + p + 1)
+        }
+    }
+
+    fn max_len_hint(&self) -> usize {
+        self.finder.needle().len()
+    }
+
+    fn is_empty_literal(&self) -> bool {
+        self.finder.needle().is_empty()
+    }
+
+    fn literal(&self) -> Option<&[u8]> {
+        Some(self.finder.needle())
+    }
+
+    fn to_static(&self) -> Box<dyn Matcher> {
+        Box::new(self.clone().into_owned())
+    }
+}
+
+/// Returns the smallest period of `needle` if it overlaps itself, else 0.
+///
+/// The period is the needle length minus its longest proper border (a prefix
+/// that is also a suffix), found with the KMP failure function.
+fn overlap_period(needle: &[u8]) -> usize {
+    let n = needle.len();
+    if n < 2 {
+        return 0;
+    }
+    let border = failure(needle)[n - 1];
+    if border == 0 { 0 } else { n - border }
+}
+
+/// The KMP failure function: `fail[i]` is the length of the longest proper
+/// border of `needle[..=i]`.
+pub(crate) fn failure(needle: &[u8]) -> Vec<usize> {
+    let mut fail = vec![0usize; needle.len()];
+    let mut k = 0;
+    for i in 1..needle.len() {
+        while k > 0 && needle[i] != needle[k] {
+            k = fail[k - 1];
+        }
+        if needle[i] == needle[k] {
+            k += 1;
+        }
+        fail[i] = k;
+    }
+    fail
+}
+
+/// A regular expression over bytes.
+#[derive(Clone, Debug)]
+pub(crate) struct RegexMatcher {
+    re: Regex,
+    /// Report capture groups after each match.
+    captures: bool,
+}
+
+impl RegexMatcher {
+    pub(crate) fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            re: Regex::new(pattern)?,
+            captures: false,
+        })
+    }
+
+    pub(crate) fn with_captures(self) -> Self {
+        Self {
+            captures: true,
+            ..self
+        }
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn find_in(&self, hay: &[u8], from: usize) -> Option<(usize, usize, u32)> {
+        self.re.find_at(hay, from).map(|m| (m.start(), m.len(), 0))
+    }
+
+    fn captures(&self, hay: &[u8], start: usize, each: &mut dyn FnMut(u32, usize, usize)) {
+        if !self.captures {
+            return;
+        }
+        // Searching from the match start finds that same match again.
+        let Some(caps) = self.re.captures_at(hay, start) else {
+            return;
+        };
+        for (group, m) in caps.iter().enumerate().skip(1) {
+            if let Some(m) = m {
+                each(group as u32, m.start(), m.len());
+            }
+        }
+    }
+
+    fn max_len_hint(&self) -> usize {
+        REGEX_MAX_LEN
+    }
+
+    fn bounded(&self) -> bool {
+        false
+    }
+
+    fn overlapping(&self) -> bool {
+        false
+    }
+
+    fn look_behind(&self) -> usize {
+        REGEX_LOOK_BEHIND
+    }
+
+    fn to_static(&self) -> Box<dyn Matcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Several regexes at once, matched line by line.
+///
+/// A `RegexSet` finds which regexes match a line; only those are run again to
+/// locate their matches. Each regex reports its own non-overlapping matches,
+/// so matches of different regexes may overlap; they are ordered by offset,
+/// then by index.
+#[derive(Clone, Debug)]
+pub(crate) struct RegexSetMatcher {
+    set: RegexSet,
+    regexes: Vec<Regex>,
+}
+
+impl RegexSetMatcher {
+    pub(crate) fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, regex::Error> {
+        Ok(Self {
+            set: RegexSet::new(patterns)?,
+            regexes: patterns
+                .iter()
+                .map(|p| Regex::new(p.as_ref()))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Returns the first match, by offset then index, for which `wanted` holds,
+    /// looking at the line holding `from` and then the lines after it.
+    fn search(
+        &self,
+        hay: &[u8],
+        from: usize,
+        wanted: impl Fn(usize, u32) -> bool,
+    ) -> Option<(usize, usize, u32)> {
+        let mut start = memrchr(b'\n', &hay[..from]).map_or(0, |i| i + 1);
+        loop {
+            let end = memchr(b'\n', &hay[start..]).map_or(hay.len(), |i| start + i);
+            let line = &hay[start..end];
+            let mut best: Option<(usize, usize, u32)> = None;
+            for i in self.set.matches(line).iter() {
+                let found = self.regexes[i]
+                    .find_iter(line)
+                    .map(|m| (start + m.start(), m.len(), i as u32))
+                    .find(|&(pos, _, i)| wanted(pos, i));
+                // Indices ascend, so only a smaller offset wins.
+                if let Some(m) = found
+                    && best.is_none_or(|b| m.0 < b.0)
+                {
+                    best = Some(m);
+                }
+            }
+            if best.is_some() || end == hay.len() {
+                return best;
+            }
+            start = end + 1;
+        }
+    }
+}
+
+impl Matcher for RegexSetMatcher {
+    fn find_in(&self, hay: &[u8], from: usize) -> Option<(usize, usize, u32)> {
+        self.search(hay, from, |pos, _| pos >= from)
+    }
+
+    fn find_after(&self, hay: &[u8], prev: usize, pattern: u32) -> Option<(usize, usize, u32)> {
+        self.search(hay, prev, |pos, i| {
+            pos > prev || (pos == prev && i > pattern)
+        })
+    }
+
+    fn max_len_hint(&self) -> usize {
+        REGEX_MAX_LEN
+    }
+
+    fn bounded(&self) -> bool {
+        false
+    }
+
+    fn whole_lines(&self) -> bool {
+        true
+    }
+
+    fn to_static(&self) -> Box<dyn Matcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Several literal needles at once.
+///
+/// When needles match at the same offset, the one listed first wins.
+#[derive(Clone, Debug)]
+pub(crate) struct MultiMatcher {
+    ac: AhoCorasick,
+    max_len: usize,
+    /// With every needle kept, all needles matching at an offset are reported
+    /// instead of only the first listed.
+    all: Option<Vec<Vec<u8>>>,
+}
+
+impl MultiMatcher {
+    pub(crate) fn new<P: AsRef<[u8]>>(needles: &[P]) -> Self {
+        let ac = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostFirst)
+            .build(needles)
+            .expect("literal needles always build");
+        let max_len = needles.iter().map(|n| n.as_ref().len()).max().unwrap_or(0);
+        Self {
+            ac,
+            max_len,
+            all: None,
+        }
+    }
+
+    /// Like `new`, reporting every needle that matches at an offset.
+    pub(crate) fn new_all<P: AsRef<[u8]>>(needles: &[P]) -> Self {
+        Self {
+            all: Some(needles.iter().map(|n| n.as_ref().to_vec()).collect()),
+            ..Self::new(needles)
+        }
+    }
+}
+
+impl Matcher for MultiMatcher {
+    fn find_in(&self, hay: &[u8], from: usize) -> Option<(usize, usize, u32)> {
+        self.ac
+            .find(Input::new(hay).range(from..))
+            .map(|m| (m.start(), m.len(), m.pattern().as_u32()))
+    }
+
+    fn max_len_hint(&self) -> usize {
+        self.max_len
+    }
+
+    fn find_after(&self, hay: &[u8], prev: usize, pattern: u32) -> Option<(usize, usize, u32)> {
+        // Leftmost-first search reports the lowest index matching at `prev`, so
+        // the rest of them follow in index order. The overlap past the chunk cut
+        // holds the longest needle, so each is whole in `hay`.
+        if let Some(needles) = &self.all {
+            let next = pattern as usize + 1;
+            if let Some(i) = needles[next..]
+                .iter()
+                .position(|n| hay[prev..].starts_with(n))
+            {
+                let i = next + i;
+                return Some((prev, needles[i].len(), i as u32));
+            }
+        }
+        self.find_in(hay, prev + 1)
+    }
+
+    fn to_static(&self) -> Box<dyn Matcher> {
+        Box::new(self.clone())
+    }
+}
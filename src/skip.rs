@@ -0,0 +1,79 @@
+//! Vetoing matches by their surroundings.
+//!
+//! A [`SkipRegions`] hook sees the input in stream order and decides for each
+//! candidate match whether it is kept, e.g. to ignore needles inside string
+//! literals when searching source code. It runs on top of the engine's match
+//! loop; see [`GrepEngine::search_skipping`](crate::engine::GrepEngine::search_skipping).
+
+/// Decides which candidate matches to report.
+///
+/// Chunks overlap, but the engine only asks about positions after those it has
+/// already handled, so a hook can track state by advancing through the bytes
+/// it is shown. Offsets are relative to the start of the scanned input.
+pub trait SkipRegions {
+    /// Returns whether the match starting at `chunk[pos]` is reported. `base` is
+    /// the offset of `chunk[0]`.
+    fn keep(&mut self, chunk: &[u8], base: u64, pos: usize) -> bool;
+
+    /// Called when the engine is done with `chunk[..end]`; the next chunk starts
+    /// at or shortly before `base + end`, as some matchers are handed a few
+    /// bytes of context again. Stateful hooks advance through the remaining
+    /// bytes here.
+    fn chunk_done(&mut self, chunk: &[u8], base: u64, end: usize) {
+        let _ = (chunk, base, end);
+    }
+}
+
+impl<F: FnMut(&[u8], u64, usize) -> bool> SkipRegions for F {
+    fn keep(&mut self, chunk: &[u8], base: u64, pos: usize) -> bool {
+        self(chunk, base, pos)
+    }
+}
+
+/// Skips matches starting inside double-quoted strings.
+///
+/// A backslash escapes the next byte inside a string, so `"a\"b"` is one
+/// string. State carries across chunks; strings may span lines.
+#[derive(Clone, Debug, Default)]
+pub struct QuotedStrings {
+    /// Offset of the next byte to look at.
+    pos: u64,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl QuotedStrings {
+    /// Starts outside any string.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consumes `chunk[..end]` up to where it was already seen.
+    fn advance(&mut self, chunk: &[u8], base: u64, end: usize) {
+        let start = self.pos.saturating_sub(base) as usize;
+        if start >= end {
+            return;
+        }
+        for &b in &chunk[start..end] {
+            if self.escaped {
+                self.escaped = false;
+            } else if self.in_string && b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = !self.in_string;
+            }
+        }
+        self.pos = base + end as u64;
+    }
+}
+
+impl SkipRegions for QuotedStrings {
+    fn keep(&mut self, chunk: &[u8], base: u64, pos: usize) -> bool {
+        self.advance(chunk, base, pos);
+        !self.in_string
+    }
+
+    fn chunk_done(&mut self, chunk: &[u8], base: u64, end: usize) {
+        self.advance(chunk, base, end);
+    }
+}
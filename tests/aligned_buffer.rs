@@ -0,0 +1,69 @@
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+use simd_grep::io::aligned::AlignedBuf;
+use simd_grep::io::chunker::Chunker;
+
+#[test]
+fn aligned_buf_starts_on_boundary_and_grows_zeroed() {
+    for align in [1, 16, 64, 4096] {
+        let mut buf = AlignedBuf::new(10, align);
+        assert_eq!(buf.as_ptr() as usize % align, 0);
+        buf[..3].copy_from_slice(b"abc");
+        buf.grow(100_000);
+        assert_eq!(buf.as_ptr() as usize % align, 0);
+        assert_eq!(&buf[..3], b"abc");
+        assert!(buf[3..].iter().all(|&b| b == 0));
+    }
+}
+
+#[test]
+fn every_chunk_is_aligned() {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    let mut reader = &data[..];
+    let mut chunker = Chunker::with_alignment(&mut reader, 4096, 7, 64);
+    let mut chunks = 0;
+    while let Some((_, chunk)) = chunker.next_chunk().unwrap() {
+        assert_eq!(chunk.as_ptr() as usize % 64, 0);
+        chunks += 1;
+    }
+    assert!(chunks > 1);
+}
+
+#[test]
+fn aligned_search_matches_default() {
+    let mut data = Vec::new();
+    for i in 0..5_000 {
+        data.extend_from_slice(if i % 7 == 0 { b"needle " } else { b"hay " });
+    }
+    let search = |buffer_align| {
+        let opts = GrepOptions {
+            chunk_bytes: 1000,
+            buffer_align,
+            ..Default::default()
+        };
+        let mut sink = VecSink::default();
+        GrepEngine::new_literal(b"needle", opts)
+            .search(&mut &data[..], &mut sink)
+            .unwrap();
+        sink.offs
+    };
+    let plain = search(None);
+    assert_eq!(plain.len(), 715);
+    assert_eq!(search(Some(64)), plain);
+}
+
+#[test]
+fn alignment_must_be_a_power_of_two() {
+    let opts = GrepOptions {
+        buffer_align: Some(48),
+        ..Default::default()
+    };
+    let err = GrepEngine::new_literal(b"x", opts.clone())
+        .search(&mut &b"x"[..], &mut VecSink::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(matches!(
+        GrepEngine::try_new_literal(b"x", opts),
+        Err(GrepError::Options(_))
+    ));
+}
@@ -0,0 +1,63 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use simd_grep::engine::{GrepEngine, GrepOptions};
+
+/// Counts allocations made by the current thread while `COUNTING` is set.
+struct CountingAlloc;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.with(Cell::get) {
+            ALLOCS.with(|n| n.set(n.get() + 1));
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    ALLOCS.with(|n| n.set(0));
+    COUNTING.with(|c| c.set(true));
+    let out = f();
+    COUNTING.with(|c| c.set(false));
+    (out, ALLOCS.with(Cell::get))
+}
+
+fn engine() -> GrepEngine<'static> {
+    GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            chunk_bytes: 64,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn reports_hit_and_miss() {
+    let eng = engine();
+    assert!(eng.any_match(&mut &b"hay needle hay"[..]).unwrap());
+    assert!(!eng.any_match(&mut &b"hay hay hay"[..]).unwrap());
+    assert!(!eng.any_match(&mut &b""[..]).unwrap());
+}
+
+#[test]
+fn only_the_chunk_buffer_is_allocated() {
+    let eng = engine();
+    let data = b"needle".repeat(1000);
+    let (hit, allocs) = allocations(|| eng.any_match(&mut &data[..]).unwrap());
+    assert!(hit);
+    assert_eq!(allocs, 1);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::io::{BufReader, Cursor};
+
+use common::Trickle;
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+const DATA: &[u8] = b"\xEF\xBB\xBFneedle one\nneedle two\n";
+
+fn opts(chunk_bytes: usize, strip_bom: bool) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes,
+        flags: GrepFlags::LINE_NUMBER,
+        strip_bom,
+        ..Default::default()
+    }
+}
+
+/// `(off, line_no, col)` from every way of running the search.
+fn reports(engine: &GrepEngine<'_>, data: &[u8]) -> Vec<(u64, u32, u32)> {
+    let collect = |s: VecSink| -> Vec<_> {
+        s.offs
+            .into_iter()
+            .zip(s.line_nos)
+            .zip(s.cols)
+            .map(|((o, l), c)| (o, l, c))
+            .collect()
+    };
+    let mut sliced = VecSink::default();
+    engine.search_slice(data, &mut sliced).unwrap();
+    let sliced = collect(sliced);
+
+    for step in [1, 2, 64] {
+        let mut streamed = VecSink::default();
+        engine
+            .search(&mut Trickle::new(data, step), &mut streamed)
+            .unwrap();
+        assert_eq!(collect(streamed), sliced, "step={step}");
+
+        let mut buffered = VecSink::default();
+        let mut reader = BufReader::with_capacity(4 + step, Cursor::new(data));
+        engine.search_buffered(&mut reader, &mut buffered).unwrap();
+        assert_eq!(collect(buffered), sliced, "step={step}");
+
+        let mut pushed = VecSink::default();
+        let mut push = engine.push_searcher().unwrap();
+        for part in data.chunks(step) {
+            push.push(part, &mut pushed);
+        }
+        push.finish(&mut pushed);
+        assert_eq!(collect(pushed), sliced, "step={step}");
+    }
+    sliced
+}
+
+#[test]
+fn anchored_match_on_the_first_line_after_a_bom() {
+    for chunk_bytes in [4, 8, 1 << 20] {
+        let engine = GrepEngine::new_regex("^needle", opts(chunk_bytes, true)).unwrap();
+        // Offsets count from the first byte after the BOM.
+        assert_eq!(
+            reports(&engine, DATA)[0],
+            (0, 1, 1),
+            "chunk_bytes={chunk_bytes}"
+        );
+
+        let engine = GrepEngine::new_literal(b"needle", opts(chunk_bytes, true));
+        assert_eq!(reports(&engine, DATA), vec![(0, 1, 1), (11, 2, 1)]);
+        assert_eq!(reports(&engine, &DATA[3..]), reports(&engine, DATA));
+    }
+}
+
+#[test]
+fn bom_is_kept_unless_stripping_is_requested() {
+    let engine = GrepEngine::new_regex("^needle", opts(1 << 20, false)).unwrap();
+    assert!(reports(&engine, DATA).iter().all(|&(off, ..)| off != 0));
+    let engine = GrepEngine::new_literal(b"needle", opts(1 << 20, false));
+    assert_eq!(reports(&engine, DATA), vec![(3, 1, 4), (14, 2, 1)]);
+}
+
+#[test]
+fn only_a_leading_bom_is_stripped() {
+    let engine = GrepEngine::new_literal(b"\xEF\xBB\xBFx", opts(8, true));
+    // A lone BOM prefix and a BOM later in the input are plain bytes.
+    assert_eq!(reports(&engine, b"\xEF\xBB"), vec![]);
+    assert_eq!(reports(&engine, b"ab\xEF\xBB\xBFx"), vec![(2, 1, 3)]);
+    assert_eq!(
+        reports(&engine, b"\xEF\xBB\xBF\xEF\xBB\xBFx"),
+        vec![(0, 1, 1)]
+    );
+}
@@ -0,0 +1,70 @@
+use std::io::{BufReader, Cursor};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::chunker::BufChunker;
+
+fn search_both(engine: &GrepEngine<'_>, data: &[u8], capacity: usize) -> (VecSink, VecSink) {
+    let mut generic = VecSink::default();
+    engine.search(&mut &data[..], &mut generic).unwrap();
+    let mut buffered = VecSink::default();
+    let mut reader = BufReader::with_capacity(capacity, Cursor::new(data));
+    engine.search_buffered(&mut reader, &mut buffered).unwrap();
+    (generic, buffered)
+}
+
+#[test]
+fn chunks_follow_the_stream() {
+    let data: Vec<u8> = (0..1000u32).map(|i| (i % 253) as u8).collect();
+    let mut reader = BufReader::with_capacity(64, Cursor::new(&data));
+    let mut chunker = BufChunker::new(&mut reader, 5);
+    let mut covered = 0;
+    while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+        let base = base as usize;
+        assert_eq!(chunk, &data[base..base + chunk.len()]);
+        // Each chunk starts within the carried tail of the previous one.
+        assert!(base <= covered && covered - base <= 5);
+        covered = covered.max(base + chunk.len());
+    }
+    assert_eq!(covered, data.len());
+}
+
+#[test]
+fn buffered_search_matches_generic_search() {
+    let mut rng = StdRng::seed_from_u64(0x352);
+    for _ in 0..100 {
+        let data: Vec<u8> = (0..rng.gen_range(0..600))
+            .map(|_| b"ab\n"[rng.gen_range(0..3)])
+            .collect();
+        let needle: Vec<u8> = (0..rng.gen_range(1..6))
+            .map(|_| b"ab"[rng.gen_range(0..2)])
+            .collect();
+        let flags = if rng.gen_bool(0.5) {
+            GrepFlags::LINE_NUMBER
+        } else {
+            GrepFlags::empty()
+        };
+        let opts = GrepOptions {
+            chunk_bytes: rng.gen_range(8..64),
+            flags,
+            ..Default::default()
+        };
+        let capacity = rng.gen_range(1..48);
+        let engine = GrepEngine::new_literal(&needle, opts);
+        let (generic, buffered) = search_both(&engine, &data, capacity);
+        assert_eq!(buffered.offs, generic.offs, "needle {:?}", needle);
+        assert_eq!(buffered.line_nos, generic.line_nos);
+        assert_eq!(buffered.cols, generic.cols);
+    }
+}
+
+#[test]
+fn buffered_regex_search() {
+    let data = b"key=1 key=22\nkey=333 nothing key=4444\n".repeat(20);
+    let engine = GrepEngine::new_regex(r"key=\d+", GrepOptions::default()).unwrap();
+    let (generic, buffered) = search_both(&engine, &data, 16);
+    assert_eq!(generic.offs.len(), 80);
+    assert_eq!(buffered.offs, generic.offs);
+    assert_eq!(buffered.lens, generic.lens);
+}
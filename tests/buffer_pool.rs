@@ -0,0 +1,79 @@
+mod common;
+
+use std::thread;
+
+use common::TempDir;
+use simd_grep::engine::{CountSink, GrepOptions, PreparedPattern};
+use simd_grep::io::pool::BufferPool;
+use simd_grep::io::walk::{WalkOptions, search_dir_pooled};
+
+fn many_files(tag: &str, n: usize) -> TempDir {
+    let dir = TempDir::new(tag);
+    for i in 0..n {
+        dir.write(&format!("d{}/f{:03}.txt", i % 4, i), b"a needle here\n");
+    }
+    dir
+}
+
+fn opts() -> GrepOptions {
+    GrepOptions {
+        chunk_bytes: 64 * 1024,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn walker_reuses_one_buffer() {
+    let dir = many_files("pool", 60);
+    let pool = BufferPool::new();
+    let pattern = PreparedPattern::new(b"needle");
+    let mut sink = CountSink::default();
+    let files = search_dir_pooled(
+        dir.path(),
+        &pattern,
+        &opts(),
+        &WalkOptions::default(),
+        &mut sink,
+        &pool,
+    )
+    .unwrap();
+    assert_eq!(files.len(), 60);
+    assert_eq!(sink.total, 60);
+    assert_eq!(pool.created(), 1);
+    assert_eq!(pool.reused(), 59);
+
+    let buf = pool.get();
+    assert!(buf.capacity() >= 64 * 1024);
+}
+
+#[test]
+fn threads_sharing_a_pool_allocate_at_most_one_buffer_each() {
+    let dir = many_files("pool_mt", 40);
+    let pool = BufferPool::new();
+    let pattern = PreparedPattern::new(b"needle");
+    let totals: Vec<u64> = thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let root = dir.path().join(format!("d{}", i));
+                let (pool, pattern) = (&pool, &pattern);
+                s.spawn(move || {
+                    let mut sink = CountSink::default();
+                    search_dir_pooled(
+                        &root,
+                        pattern,
+                        &opts(),
+                        &WalkOptions::default(),
+                        &mut sink,
+                        pool,
+                    )
+                    .unwrap();
+                    sink.total
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(totals, vec![10; 4]);
+    assert!(pool.created() <= 4);
+    assert_eq!(pool.created() + pool.reused(), 40);
+}
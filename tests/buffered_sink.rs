@@ -0,0 +1,76 @@
+use std::io::{self, Write};
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, MatchSink};
+use simd_grep::io::output::{BufferedSink, FormatSink, PathStyle, PrintSink};
+
+/// Records every `write` call.
+#[derive(Default)]
+struct Counting {
+    bytes: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for Counting {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn engine() -> GrepEngine<'static> {
+    GrepEngine::new_literal(
+        b"ab",
+        GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER),
+    )
+}
+
+const TEMPLATE: &str = "{line}:{col}:{text}\n";
+
+#[test]
+fn batches_writes_with_identical_output() {
+    let data = b"ab xab\nnothing\nabab\n".repeat(200);
+
+    let mut direct = FormatSink::new(Counting::default(), TEMPLATE, vec![]).unwrap();
+    engine().search(&mut &data[..], &mut direct).unwrap();
+    let direct = direct.finish().unwrap();
+
+    let inner = FormatSink::new(Vec::new(), TEMPLATE, vec![]).unwrap();
+    let mut sink = BufferedSink::new(inner, Counting::default()).with_block(4096);
+    engine().search(&mut &data[..], &mut sink).unwrap();
+    let batched = sink.finish().unwrap();
+
+    assert_eq!(batched.bytes, direct.bytes);
+    assert!(
+        batched.writes * 50 < direct.writes,
+        "{} batched writes vs {} direct",
+        batched.writes,
+        direct.writes
+    );
+}
+
+#[test]
+fn flushes_on_finish_and_drop() {
+    let mut out = Vec::new();
+    {
+        let inner = PrintSink::new(Vec::new(), vec![], PathStyle::Lossy);
+        let mut sink = BufferedSink::new(inner, &mut out);
+        engine().search(&mut &b"ab\nab"[..], &mut sink).unwrap();
+        // `on_finish` already wrote everything out.
+    }
+    assert_eq!(out, b"1:0\n2:3\n");
+
+    let mut out = Vec::new();
+    {
+        let inner = PrintSink::new(Vec::new(), vec![], PathStyle::Lossy);
+        let mut sink = BufferedSink::new(inner, &mut out);
+        // No `on_finish`: only dropping the sink writes the match out.
+        sink.on_match(1, 2, 1, 0);
+        drop(sink);
+    }
+    assert_eq!(out, b"1:1\n");
+}
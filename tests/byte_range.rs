@@ -0,0 +1,77 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::TempDir;
+use simd_grep::engine::{ByteRange, GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::search_path;
+
+// Needles start at 0, 8, 16 and 24.
+const DATA: &[u8] = b"needle\n\nneedle\n\nneedle\n\nneedle\n";
+
+fn opts(range: ByteRange) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes: 5,
+        byte_range: Some(range),
+        ..Default::default()
+    }
+}
+
+fn seekable(range: ByteRange) -> Vec<u64> {
+    let eng = GrepEngine::new_literal(b"needle", opts(range));
+    let mut sink = VecSink::default();
+    eng.search_seekable(&mut Cursor::new(DATA), &mut sink)
+        .unwrap();
+    sink.offs
+}
+
+#[test]
+fn head_limits_plain_search() {
+    let eng = GrepEngine::new_literal(b"needle", opts(ByteRange::Head(16)));
+    let mut sink = VecSink::default();
+    eng.search(&mut Cursor::new(DATA), &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![0, 8]);
+
+    // A match straddling the end of the window is not reported.
+    assert_eq!(seekable(ByteRange::Head(13)), vec![0]);
+}
+
+#[test]
+fn tail_and_span_report_absolute_offsets() {
+    assert_eq!(seekable(ByteRange::Tail(16)), vec![16, 24]);
+    assert_eq!(seekable(ByteRange::Tail(1000)), vec![0, 8, 16, 24]);
+    assert_eq!(seekable(ByteRange::Span(8, 24)), vec![8, 16]);
+    assert_eq!(seekable(ByteRange::Span(9, 29)), vec![16]);
+    assert!(seekable(ByteRange::Span(20, 10)).is_empty());
+}
+
+#[test]
+fn line_numbers_count_from_window_start() {
+    let mut o = opts(ByteRange::Span(8, 32));
+    o.flags = GrepFlags::LINE_NUMBER;
+    let eng = GrepEngine::new_literal(b"needle", o);
+    let mut sink = VecSink::default();
+    eng.search_seekable(&mut Cursor::new(DATA), &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![8, 16, 24]);
+    assert_eq!(sink.line_nos, vec![1, 3, 5]);
+}
+
+#[test]
+fn tail_needs_a_seekable_source() {
+    let eng = GrepEngine::new_literal(b"needle", opts(ByteRange::Tail(8)));
+    let err = eng
+        .search(&mut Cursor::new(DATA), &mut VecSink::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn path_search_supports_tail() {
+    let dir = TempDir::new("byte_range");
+    dir.write("f.txt", DATA);
+    let eng = GrepEngine::new_literal(b"needle", opts(ByteRange::Tail(9)));
+    let mut sink = VecSink::default();
+    search_path(&dir.path().join("f.txt"), &eng, &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![24]);
+}
@@ -0,0 +1,59 @@
+use std::io;
+
+use simd_grep::engine::{GrepEngine, GrepOptions, Match, MatchSink};
+
+#[derive(Default)]
+struct Full(Vec<Match>);
+
+impl MatchSink for Full {
+    fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.0.push(*m);
+    }
+}
+
+fn check(matches: &[Match], total: u64) {
+    let mut last = 0;
+    for m in matches {
+        assert!(m.bytes_read >= m.match_off + u64::from(m.len), "{m:?}");
+        assert!(m.bytes_read >= last, "{m:?} after {last}");
+        assert!(m.bytes_read <= total, "{m:?}");
+        last = m.bytes_read;
+    }
+}
+
+#[test]
+fn bytes_read_is_monotonic_and_covers_the_match() {
+    let data = b"needle xx needle\nyyyy needle needle zz".repeat(30);
+    for chunk_bytes in [6, 7, 64, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_literal(b"needle", opts);
+        let mut sink = Full::default();
+        engine.search(&mut &data[..], &mut sink).unwrap();
+        assert_eq!(sink.0.len(), 120);
+        check(&sink.0, data.len() as u64);
+        if chunk_bytes < data.len() {
+            // Progress is reported per chunk, not only at the end.
+            assert!(sink.0[0].bytes_read < data.len() as u64);
+        }
+
+        let yielded: Vec<Match> = engine
+            .matches(&mut &data[..])
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(yielded, sink.0, "chunk_bytes={chunk_bytes}");
+    }
+}
+
+#[test]
+fn slices_report_the_whole_haystack_as_read() {
+    let engine = GrepEngine::new_literal(b"ab", GrepOptions::default());
+    let mut sink = Full::default();
+    engine.search_slice(b"ab cd ab", &mut sink).unwrap();
+    let read: Vec<u64> = sink.0.iter().map(|m| m.bytes_read).collect();
+    assert_eq!(read, vec![8, 8]);
+}
@@ -0,0 +1,114 @@
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use simd_grep::engine::{GrepEngine, GrepOptions, MatchSink, VecSink};
+
+/// A reader over `len` bytes of `"needle "` repeated, counting what it hands out.
+struct Repeat {
+    pos: usize,
+    len: usize,
+}
+
+impl Read for Repeat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.len - self.pos);
+        for (i, b) in buf[..n].iter_mut().enumerate() {
+            *b = b"needle "[(self.pos + i) % 7];
+        }
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Asks the canceller thread to set the flag after the first chunk and waits
+/// until it has.
+struct Signal {
+    tx: mpsc::Sender<()>,
+    done: mpsc::Receiver<()>,
+    boundaries: usize,
+    matches: u64,
+    finished: Option<u64>,
+}
+
+impl MatchSink for Signal {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {
+        self.matches += 1;
+    }
+
+    fn on_chunk_boundary(&mut self, _global_off: u64) {
+        self.boundaries += 1;
+        if self.boundaries == 1 {
+            self.tx.send(()).unwrap();
+            self.done.recv().unwrap();
+        }
+    }
+
+    fn on_finish(&mut self, _file_id: u32, total_matches: u64) {
+        self.finished = Some(total_matches);
+    }
+}
+
+#[test]
+fn flag_set_from_another_thread_stops_the_search() {
+    const LEN: usize = 64 * 1024 * 1024;
+    let cancel = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    let (done_tx, done) = mpsc::channel();
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            chunk_bytes: 64 * 1024,
+            ..Default::default()
+        },
+    );
+    let mut reader = Repeat { pos: 0, len: LEN };
+    let mut sink = Signal {
+        tx,
+        done,
+        boundaries: 0,
+        matches: 0,
+        finished: None,
+    };
+    thread::scope(|s| {
+        let cancel = &cancel;
+        s.spawn(move || {
+            rx.recv().unwrap();
+            cancel.store(true, Ordering::Relaxed);
+            done_tx.send(()).unwrap();
+        });
+        eng.search_cancellable(&mut reader, &mut sink, cancel)
+            .unwrap();
+    });
+    assert_eq!(sink.boundaries, 1);
+    assert!(reader.pos <= 2 * 64 * 1024, "read {} bytes", reader.pos);
+    assert!(sink.matches > 0);
+    assert_eq!(sink.finished, Some(sink.matches));
+}
+
+#[test]
+fn unset_flag_searches_everything() {
+    let cancel = AtomicBool::new(false);
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            chunk_bytes: 16,
+            ..Default::default()
+        },
+    );
+    let mut sink = VecSink::default();
+    eng.search_cancellable(&mut Repeat { pos: 0, len: 70 }, &mut sink, &cancel)
+        .unwrap();
+    assert_eq!(sink.offs, (0..10).map(|i| i * 7).collect::<Vec<_>>());
+}
+
+#[test]
+fn flag_set_up_front_reports_nothing() {
+    let cancel = AtomicBool::new(true);
+    let eng = GrepEngine::new_literal(b"needle", GrepOptions::default());
+    let mut sink = VecSink::default();
+    eng.search_cancellable(&mut &b"needle"[..], &mut sink, &cancel)
+        .unwrap();
+    assert!(sink.offs.is_empty());
+}
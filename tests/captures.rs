@@ -0,0 +1,91 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink};
+
+/// Matches and the captures following each, in report order.
+#[derive(Debug, PartialEq)]
+enum Event {
+    Match(u64, u32),
+    Capture(u32, u64, u32),
+}
+
+#[derive(Default)]
+struct Events(Vec<Event>);
+
+impl MatchSink for Events {
+    fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.0.push(Event::Match(m.off, m.len));
+    }
+
+    fn on_capture(&mut self, group: u32, off: u64, len: u32) {
+        self.0.push(Event::Capture(group, off, len));
+    }
+}
+
+fn events(pattern: &str, data: &[u8], opts: GrepOptions) -> Vec<Event> {
+    let engine = GrepEngine::new_regex_captures(pattern, opts).unwrap();
+    let mut streamed = Events::default();
+    engine.search(&mut &data[..], &mut streamed).unwrap();
+    let mut sliced = Events::default();
+    engine.search_slice(data, &mut sliced).unwrap();
+    assert_eq!(streamed.0, sliced.0);
+    streamed.0
+}
+
+#[test]
+fn group_spans_follow_each_match() {
+    let data = b"user id=42 ok\nid=7 and id=1234\n";
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        overlap: Some(7),
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    let got = events(r"id=(\d+)", data, opts);
+    assert_eq!(
+        got,
+        vec![
+            Event::Match(5, 5),
+            Event::Capture(1, 8, 2),
+            Event::Match(14, 4),
+            Event::Capture(1, 17, 1),
+            Event::Match(23, 7),
+            Event::Capture(1, 26, 4),
+        ]
+    );
+    let digits: Vec<&[u8]> = got
+        .iter()
+        .filter_map(|e| match *e {
+            Event::Capture(_, off, len) => {
+                Some(&data[off as usize..(off + u64::from(len)) as usize])
+            }
+            Event::Match(..) => None,
+        })
+        .collect();
+    assert_eq!(digits, vec![&b"42"[..], b"7", b"1234"]);
+}
+
+#[test]
+fn groups_that_did_not_take_part_are_skipped() {
+    let got = events(r"(a)|(b)(c)?", b"xa bc b", GrepOptions::default());
+    assert_eq!(
+        got,
+        vec![
+            Event::Match(1, 1),
+            Event::Capture(1, 1, 1),
+            Event::Match(3, 2),
+            Event::Capture(2, 3, 1),
+            Event::Capture(3, 4, 1),
+            Event::Match(6, 1),
+            Event::Capture(2, 6, 1),
+        ]
+    );
+}
+
+#[test]
+fn plain_regex_reports_no_captures() {
+    let engine = GrepEngine::new_regex(r"id=(\d+)", GrepOptions::default()).unwrap();
+    let mut sink = Events::default();
+    engine.search_slice(b"id=1 id=2", &mut sink).unwrap();
+    assert_eq!(sink.0, vec![Event::Match(0, 4), Event::Match(5, 4)]);
+}
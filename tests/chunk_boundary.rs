@@ -0,0 +1,154 @@
+use simd_grep::engine::{
+    Control, ControlSink, GrepEngine, GrepFlags, GrepOptions, Match, MatchSink,
+};
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Match(u64),
+    Boundary(u64),
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Event>);
+
+impl MatchSink for Recorder {
+    fn on_match(&mut self, off: u64, _len: u32, _line_no: u32, _file_id: u32) {
+        self.0.push(Event::Match(off));
+    }
+
+    fn on_chunk_boundary(&mut self, global_off: u64) {
+        self.0.push(Event::Boundary(global_off));
+    }
+}
+
+impl Recorder {
+    fn boundaries(&self) -> Vec<u64> {
+        self.0
+            .iter()
+            .filter_map(|e| match e {
+                Event::Boundary(off) => Some(*off),
+                Event::Match(_) => None,
+            })
+            .collect()
+    }
+
+    /// Checks that every match was delivered before the first boundary past it.
+    fn assert_ordered(&self) {
+        let mut delivered_to = 0;
+        for event in &self.0 {
+            match *event {
+                Event::Match(off) => assert!(off >= delivered_to, "{off} after boundary"),
+                Event::Boundary(off) => {
+                    assert!(off > delivered_to, "boundary {off} did not advance");
+                    delivered_to = off;
+                }
+            }
+        }
+    }
+}
+
+// 30 bytes with "ab" at 0, 6 (straddling the first cut), 13 and 28.
+const DATA: &[u8] = b"ab....ab.....ab.............ab";
+
+fn opts(flags: GrepFlags) -> GrepOptions {
+    GrepOptions {
+        flags,
+        chunk_bytes: 8,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn called_once_per_chunk_with_increasing_offsets() {
+    let eng = GrepEngine::new_literal(b"ab", opts(GrepFlags::empty()));
+    let mut sink = Recorder::default();
+    eng.search(&mut &DATA[..], &mut sink).unwrap();
+    // Each chunk holds 8 fresh bytes after the byte carried over, and reports
+    // the matches starting before its own last byte, which is carried on. The
+    // last chunk is reported in full.
+    assert_eq!(sink.boundaries(), vec![7, 15, 23, 30]);
+    assert_eq!(
+        sink.0,
+        vec![
+            Event::Match(0),
+            Event::Match(6),
+            Event::Boundary(7),
+            Event::Match(13),
+            Event::Boundary(15),
+            Event::Boundary(23),
+            Event::Match(28),
+            Event::Boundary(30),
+        ]
+    );
+    sink.assert_ordered();
+}
+
+#[test]
+fn line_aware_boundaries_fall_on_line_starts() {
+    let data = b"ab\n..ab\n....\nab..\nab";
+    let eng = GrepEngine::new_literal(b"ab", opts(GrepFlags::LINE_NUMBER));
+    let mut sink = Recorder::default();
+    eng.search(&mut &data[..], &mut sink).unwrap();
+    let boundaries = sink.boundaries();
+    assert_eq!(boundaries.last(), Some(&(data.len() as u64)));
+    for &off in &boundaries[..boundaries.len() - 1] {
+        assert_eq!(data[off as usize - 1], b'\n', "boundary {off}");
+    }
+    sink.assert_ordered();
+}
+
+#[test]
+fn slice_and_kmp_searches_report_boundaries() {
+    let eng = GrepEngine::new_literal(b"ab", opts(GrepFlags::empty()));
+    let mut sink = Recorder::default();
+    eng.search_slice(DATA, &mut sink).unwrap();
+    assert_eq!(sink.boundaries(), vec![30]);
+
+    let mut sink = Recorder::default();
+    eng.search_kmp(&mut &DATA[..], &mut sink).unwrap();
+    assert_eq!(sink.boundaries(), vec![8, 16, 24, 30]);
+    sink.assert_ordered();
+}
+
+#[test]
+fn kmp_boundary_stops_before_a_pending_match() {
+    // "ab" straddles the first cut, and the "a" at the end holds the second
+    // boundary back until the input ends.
+    let eng = GrepEngine::new_literal(b"ab", opts(GrepFlags::empty()));
+    let mut sink = Recorder::default();
+    eng.search_kmp(&mut &b".......ab......a"[..], &mut sink)
+        .unwrap();
+    assert_eq!(
+        sink.0,
+        vec![
+            Event::Boundary(7),
+            Event::Match(7),
+            Event::Boundary(15),
+            Event::Boundary(16)
+        ]
+    );
+    sink.assert_ordered();
+}
+
+#[test]
+fn no_boundary_for_the_chunk_a_sink_stops_in() {
+    struct StopAt(u64, Vec<u64>);
+    impl ControlSink for StopAt {
+        fn on_match_control(&mut self, m: &Match, _: Option<&[u8]>) -> Control {
+            if m.off >= self.0 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        }
+
+        fn on_chunk_boundary(&mut self, global_off: u64) {
+            self.1.push(global_off);
+        }
+    }
+
+    let eng = GrepEngine::new_literal(b"ab", opts(GrepFlags::empty()));
+    let mut sink = StopAt(13, Vec::new());
+    eng.search_control(&mut &DATA[..], &mut sink).unwrap();
+    assert_eq!(sink.1, vec![7]);
+}
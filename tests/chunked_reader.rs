@@ -0,0 +1,47 @@
+use std::io::Read;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::testutil::ChunkedReader;
+
+#[test]
+fn reads_are_capped() {
+    let data = b"0123456789";
+    let mut reader = ChunkedReader::new(&data[..], 3);
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"6789");
+}
+
+#[test]
+fn fragmented_reads_give_identical_results() {
+    let mut rng = StdRng::seed_from_u64(0x361);
+    let data: Vec<u8> = (0..3000).map(|_| b"ab\nc"[rng.gen_range(0..4)]).collect();
+    let needles: [&[u8]; 5] = [b"a", b"ab", b"aba", b"b\nc", b"abcab"];
+    for needle in needles {
+        for chunk_bytes in [5, 17, 64, 4096] {
+            for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+                let opts = GrepOptions {
+                    chunk_bytes,
+                    flags,
+                    ..Default::default()
+                };
+                let engine = GrepEngine::new_literal(needle, opts);
+                let mut whole = VecSink::default();
+                engine.search(&mut &data[..], &mut whole).unwrap();
+                for max_per_read in [1, 2, 7] {
+                    let mut sink = VecSink::default();
+                    let mut reader = ChunkedReader::new(&data[..], max_per_read);
+                    engine.search(&mut reader, &mut sink).unwrap();
+                    assert_eq!(sink.offs, whole.offs, "{:?} {}", needle, chunk_bytes);
+                    assert_eq!(sink.line_nos, whole.line_nos);
+                    assert_eq!(sink.cols, whole.cols);
+                }
+            }
+        }
+    }
+}
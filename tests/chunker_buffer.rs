@@ -0,0 +1,108 @@
+use std::io::Cursor;
+
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::chunker::{Chunker, ChunkerError};
+
+fn collect(chunker: &mut Chunker<'_, Cursor<&[u8]>>) -> Vec<(u64, Vec<u8>)> {
+    let mut out = Vec::new();
+    while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+        out.push((base, chunk.to_vec()));
+    }
+    out
+}
+
+#[test]
+fn reused_buffer_yields_same_chunks_without_reallocating() {
+    let data: &[u8] = b"0123456789abcdefghij";
+    let mut r1 = Cursor::new(data);
+    let mut fresh = Chunker::new(&mut r1, 6, 2);
+    let expected = collect(&mut fresh);
+
+    let buf = Vec::with_capacity(64 * 1024);
+    let cap = buf.capacity();
+    let ptr = buf.as_ptr();
+
+    let mut r2 = Cursor::new(data);
+    let mut first = Chunker::with_buffer(&mut r2, 6, 2, buf);
+    assert_eq!(collect(&mut first), expected);
+    let buf = first.into_buffer();
+
+    let mut r3 = Cursor::new(data);
+    let mut second = Chunker::with_buffer(&mut r3, 6, 2, buf);
+    assert_eq!(collect(&mut second), expected);
+    let buf = second.into_buffer();
+
+    assert_eq!(buf.capacity(), cap);
+    assert_eq!(buf.as_ptr(), ptr);
+}
+
+#[test]
+fn engine_search_with_buffer_across_inputs() {
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            chunk_bytes: 8,
+            ..Default::default()
+        },
+    );
+    let mut buf = Vec::new();
+
+    let mut s1 = VecSink::default();
+    eng.search_with_buffer(&mut &b"a needle and a needle"[..], &mut s1, &mut buf)
+        .unwrap();
+    assert_eq!(s1.offs, vec![2, 15]);
+    let cap = buf.capacity();
+    assert!(cap > 0);
+
+    let mut s2 = VecSink::default();
+    eng.search_with_buffer(&mut &b"needle"[..], &mut s2, &mut buf)
+        .unwrap();
+    assert_eq!(s2.offs, vec![0]);
+    assert_eq!(buf.capacity(), cap);
+}
+
+#[test]
+fn fresh_chunks_split_carried_prefix_from_new_bytes() {
+    let data: &[u8] = b"0123456789abcdefghij";
+    let mut reader = Cursor::new(data);
+    let mut chunker = Chunker::new(&mut reader, 6, 2);
+
+    let mut fresh_total = 0u64;
+    let mut calls = 0;
+    while let Some((fresh_off, overlap_len, chunk)) = chunker.next_fresh_chunk().unwrap() {
+        assert_eq!(fresh_off, fresh_total);
+        assert_eq!(overlap_len, if calls == 0 { 0 } else { 2 });
+        let fresh = &chunk[overlap_len..];
+        assert_eq!(fresh, &data[fresh_off as usize..][..fresh.len()]);
+        assert_eq!(
+            &chunk[..overlap_len],
+            &data[fresh_off as usize - overlap_len..][..overlap_len]
+        );
+        fresh_total += fresh.len() as u64;
+        calls += 1;
+    }
+    assert_eq!(fresh_total, data.len() as u64);
+    assert_eq!(calls, 4);
+}
+
+#[test]
+fn overlap_must_be_smaller_than_chunk() {
+    let mut reader = Cursor::new(&b"0123456789"[..]);
+    let err = Chunker::try_new(&mut reader, 2, 10).err().unwrap();
+    assert_eq!(
+        err,
+        ChunkerError::OverlapTooLarge {
+            overlap: 10,
+            chunk_size: 2
+        }
+    );
+    assert!(Chunker::try_new(&mut reader, 0, 0).is_err());
+    assert!(Chunker::try_new(&mut reader, 3, 2).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "chunker overlap (10) must be smaller than chunk_size (2)")]
+fn new_panics_on_oversized_overlap() {
+    let mut reader = Cursor::new(&b"0123456789"[..]);
+    let _ = Chunker::new(&mut reader, 2, 10);
+}
@@ -0,0 +1,96 @@
+use simd_grep::io::chunker::Chunker;
+
+const SECRET: &[u8] = b"hunter2 is the password";
+
+#[test]
+fn shrink_releases_memory_and_reading_resumes() {
+    let data = vec![b'x'; 1 << 20];
+    let mut reader = &data[..];
+    let mut chunker = Chunker::new(&mut reader, 256 << 10, 3);
+    chunker.next_chunk().unwrap();
+    assert!(chunker.capacity() >= 256 << 10);
+
+    chunker.shrink_to(4096);
+    // The chunk just returned is still buffered.
+    assert_eq!(chunker.capacity(), 256 << 10);
+    chunker.clear();
+    chunker.shrink_to(4096);
+    assert_eq!(chunker.capacity(), 4096);
+
+    let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+    assert_eq!(base, 256 << 10);
+    assert_eq!(chunk.len(), 256 << 10);
+    assert!(chunker.into_buffer().capacity() >= 256 << 10);
+}
+
+#[test]
+fn clear_zeroes_the_buffer() {
+    let mut reader = SECRET;
+    let mut chunker = Chunker::new(&mut reader, 8, 2);
+    let (_, chunk) = chunker.next_chunk().unwrap().unwrap();
+    assert_eq!(chunk, b"hunter2 ");
+    chunker.clear();
+    let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+    // Nothing is carried over the clear.
+    assert_eq!((base, chunk), (8, &b"is the p"[..]));
+    chunker.clear();
+    assert!(chunker.into_buffer().iter().all(|&b| b == 0));
+}
+
+#[test]
+fn zero_on_drop_wipes_the_returned_buffer() {
+    let mut reader = SECRET;
+    let mut chunker = Chunker::new(&mut reader, 64, 2).zero_on_drop(true);
+    while chunker.next_chunk().unwrap().is_some() {}
+    let buf = chunker.into_buffer();
+    assert!(!buf.is_empty());
+    assert!(buf.iter().all(|&b| b == 0));
+
+    let mut reader = SECRET;
+    let mut chunker = Chunker::new(&mut reader, 64, 2);
+    while chunker.next_chunk().unwrap().is_some() {}
+    let buf = chunker.into_buffer();
+    assert!(memchr::memmem::find(&buf, b"the password").is_some());
+}
+
+#[test]
+fn aligned_buffers_shrink_too() {
+    let data = vec![b'x'; 100_000];
+    let mut reader = &data[..];
+    let mut chunker = Chunker::with_alignment(&mut reader, 64 << 10, 0, 64).zero_on_drop(true);
+    chunker.next_chunk().unwrap();
+    chunker.clear();
+    chunker.shrink_to(1024);
+    assert_eq!(chunker.capacity(), 1024);
+    let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+    assert_eq!((base, chunk.len()), (64 << 10, 100_000 - (64 << 10)));
+    assert_eq!(chunk.as_ptr() as usize % 64, 0);
+}
+
+#[test]
+fn zeroing_reallocations_keep_the_buffered_bytes() {
+    let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+    for align in [None, Some(64)] {
+        let mut reader = &data[..];
+        let chunker = match align {
+            Some(align) => Chunker::with_alignment(&mut reader, 4096, 0, align),
+            None => Chunker::new(&mut reader, 4096, 0),
+        };
+        let mut chunker = chunker.zero_on_drop(true);
+        chunker.next_chunk().unwrap();
+        // Retaining a whole chunk grows the buffer.
+        chunker.retain(4096);
+        let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+        assert_eq!((base, chunk), (0, &data[..8192]));
+        assert!(chunker.capacity() >= 8192);
+
+        chunker.retain(100);
+        let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+        assert_eq!((base, chunk), (8092, &data[8092..12288]));
+        chunker.shrink_to(1024);
+        assert_eq!(chunker.capacity(), 4196, "{align:?}");
+        chunker.retain(4196);
+        let (base, chunk) = chunker.next_chunk().unwrap().unwrap();
+        assert_eq!((base, chunk), (8092, &data[8092..16384]), "{align:?}");
+    }
+}
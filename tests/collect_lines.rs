@@ -0,0 +1,23 @@
+use simd_grep::engine::GrepOptions;
+use simd_grep::io::collect_matching_lines;
+
+#[test]
+fn joins_each_matching_line_once() {
+    let input: &[u8] = b"alpha needle\nbeta\nneedle needle gamma\ndelta\nlast needle";
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        ..Default::default()
+    };
+    let got = collect_matching_lines(input, b"needle", opts).unwrap();
+    assert_eq!(got, "alpha needle\nneedle needle gamma\nlast needle");
+
+    let none = collect_matching_lines(input, b"absent", GrepOptions::default()).unwrap();
+    assert_eq!(none, "");
+}
+
+#[test]
+fn invalid_utf8_becomes_replacement_chars() {
+    let input: &[u8] = b"ok\n\xff needle \xfe\nneedle\n";
+    let got = collect_matching_lines(input, b"needle", GrepOptions::default()).unwrap();
+    assert_eq!(got, "\u{FFFD} needle \u{FFFD}\nneedle");
+}
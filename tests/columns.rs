@@ -0,0 +1,94 @@
+mod common;
+
+use common::Trickle;
+use simd_grep::engine::{ColumnUnit, GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+fn search(data: &[u8], needle: &[u8], chunk_bytes: usize, unit: ColumnUnit) -> VecSink {
+    let opts = GrepOptions {
+        chunk_bytes,
+        flags: GrepFlags::LINE_NUMBER,
+        column_unit: unit,
+        ..Default::default()
+    };
+    let eng = GrepEngine::new_literal(needle, opts);
+    let mut sink = VecSink::default();
+    eng.search(&mut Trickle::new(data, 1), &mut sink).unwrap();
+    sink
+}
+
+#[test]
+fn byte_columns_for_ascii() {
+    let data = b"foo bar\nbaz foo\nfoo";
+    for chunk_bytes in [1, 2, 3, 5, 8, 1024] {
+        let sink = search(data, b"foo", chunk_bytes, ColumnUnit::Byte);
+        assert_eq!(sink.offs, vec![0, 12, 16], "chunk_bytes={chunk_bytes}");
+        assert_eq!(sink.line_nos, vec![1, 2, 3], "chunk_bytes={chunk_bytes}");
+        assert_eq!(sink.cols, vec![1, 5, 1], "chunk_bytes={chunk_bytes}");
+    }
+}
+
+#[test]
+fn char_columns_for_multibyte_lines() {
+    let data = "héllo wörld\n한국어 needle, needle".as_bytes();
+    for chunk_bytes in [1, 4, 7, 1024] {
+        let bytes = search(data, b"needle", chunk_bytes, ColumnUnit::Byte);
+        assert_eq!(bytes.line_nos, vec![2, 2]);
+        assert_eq!(bytes.cols, vec![11, 19]);
+
+        let chars = search(data, b"needle", chunk_bytes, ColumnUnit::Char);
+        assert_eq!(chars.offs, bytes.offs);
+        assert_eq!(chars.line_nos, vec![2, 2]);
+        assert_eq!(chars.cols, vec![5, 13]);
+    }
+}
+
+#[test]
+fn match_spanning_newline_uses_starting_line() {
+    let data = b"foo\nbar\n";
+    for chunk_bytes in [1, 2, 4, 1024] {
+        let sink = search(data, b"o\nb", chunk_bytes, ColumnUnit::Byte);
+        assert_eq!(sink.offs, vec![2]);
+        assert_eq!(sink.line_nos, vec![1]);
+        assert_eq!(sink.cols, vec![3]);
+    }
+}
+
+#[test]
+fn columns_are_zero_without_line_numbers() {
+    let eng = GrepEngine::new_literal(b"bc", GrepOptions::default());
+    let mut sink = VecSink::default();
+    eng.search(&mut &b"a\nbc"[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![2]);
+    assert_eq!(sink.line_nos, vec![0]);
+    assert_eq!(sink.cols, vec![0]);
+}
+
+#[test]
+fn char_columns_count_emoji_and_accents_as_one() {
+    // "👋" is 4 bytes, "é" 2 and "e\u{301}" (e + combining accent) 3 in 2 chars.
+    let data = "👋 café e\u{301} needle".as_bytes();
+    let bytes = search(data, b"needle", 5, ColumnUnit::Byte);
+    let chars = search(data, b"needle", 5, ColumnUnit::Char);
+    assert_eq!(bytes.cols, vec![16]);
+    assert_eq!(chars.cols, vec![11]);
+    let before = std::str::from_utf8(&data[..15]).unwrap();
+    assert_eq!(chars.cols[0] as usize, before.chars().count() + 1);
+}
+
+#[test]
+fn char_columns_count_invalid_utf8_like_the_lossy_decoder() {
+    let cases: [&[u8]; 4] = [
+        b"\xff\xfe needle",
+        // A truncated 3-byte sequence is one replacement character.
+        b"\xe2\x82 needle",
+        // A lone continuation byte after a valid character.
+        b"\xc3\xa9\xa9 needle",
+        b"ok\xf0\x9f\x98 \xed\xa0\x80 needle",
+    ];
+    for data in cases {
+        let chars = search(data, b"needle", 3, ColumnUnit::Char);
+        let pos = data.len() - b"needle".len();
+        let expected = String::from_utf8_lossy(&data[..pos]).chars().count() + 1;
+        assert_eq!(chars.cols, vec![expected as u32], "{data:?}");
+    }
+}
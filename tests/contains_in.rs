@@ -0,0 +1,61 @@
+use std::io::{self, Read};
+
+use simd_grep::engine::{GrepEngine, GrepOptions};
+
+/// Counts the bytes handed out by the inner reader.
+struct Counting<'a> {
+    data: &'a [u8],
+    read: usize,
+}
+
+impl Read for Counting<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.data.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        self.read += n;
+        Ok(n)
+    }
+}
+
+fn engine(needle: &[u8]) -> GrepEngine<'_> {
+    GrepEngine::new_literal(
+        needle,
+        GrepOptions {
+            chunk_bytes: 16,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn stops_reading_after_first_match() {
+    let mut data = b"....needle......".to_vec();
+    data.resize(1 << 20, b'.');
+    let mut reader = Counting {
+        data: &data,
+        read: 0,
+    };
+    assert!(engine(b"needle").contains_in(&mut reader).unwrap());
+    assert_eq!(reader.read, 16);
+}
+
+#[test]
+fn finds_match_across_chunk_boundary() {
+    let mut reader = Counting {
+        data: b"..............needle..",
+        read: 0,
+    };
+    assert!(engine(b"needle").contains_in(&mut reader).unwrap());
+}
+
+#[test]
+fn missing_needle_reads_everything() {
+    let data = vec![b'.'; 1000];
+    let mut reader = Counting {
+        data: &data,
+        read: 0,
+    };
+    assert!(!engine(b"needle").contains_in(&mut reader).unwrap());
+    assert_eq!(reader.read, 1000);
+}
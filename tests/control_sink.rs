@@ -0,0 +1,95 @@
+use std::io::{self, Read};
+
+use simd_grep::engine::{Control, ControlSink, GrepEngine, GrepOptions, Match, VecSink};
+
+/// Counts how many bytes were pulled from the inner reader.
+struct CountingReader<'a> {
+    data: &'a [u8],
+    read: usize,
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = (&self.data[self.read..]).read(buf)?;
+        self.read += n;
+        Ok(n)
+    }
+}
+
+/// Stops after collecting `limit` matches.
+struct FirstN {
+    limit: usize,
+    offs: Vec<u64>,
+}
+
+impl ControlSink for FirstN {
+    fn on_match_control(&mut self, m: &Match, _line: Option<&[u8]>) -> Control {
+        self.offs.push(m.off);
+        if self.offs.len() >= self.limit {
+            Control::Stop
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+#[test]
+fn stop_after_first_match_reads_no_further_chunks() {
+    let mut data = b"xxneedlexx".to_vec();
+    data.extend(std::iter::repeat_n(b'-', 10_000));
+    data.extend_from_slice(b"needle");
+
+    let opts = GrepOptions {
+        chunk_bytes: 16,
+        ..Default::default()
+    };
+    let eng = GrepEngine::new_literal(b"needle", opts);
+    let mut reader = CountingReader {
+        data: &data,
+        read: 0,
+    };
+    let mut sink = FirstN {
+        limit: 1,
+        offs: Vec::new(),
+    };
+    eng.search_control(&mut reader, &mut sink).unwrap();
+
+    assert_eq!(sink.offs, vec![2]);
+    assert_eq!(
+        reader.read, 16,
+        "only the first chunk should have been read"
+    );
+}
+
+#[test]
+fn plain_match_sinks_always_continue() {
+    let data = b"ab ab ab";
+    let eng = GrepEngine::new_literal(
+        b"ab",
+        GrepOptions {
+            chunk_bytes: 3,
+            ..Default::default()
+        },
+    );
+    let mut sink = VecSink::default();
+    eng.search_control(&mut &data[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![0, 3, 6]);
+}
+
+#[test]
+fn stop_midway_keeps_earlier_matches() {
+    let data = b"ab ab ab ab";
+    let eng = GrepEngine::new_literal(
+        b"ab",
+        GrepOptions {
+            chunk_bytes: 4,
+            ..Default::default()
+        },
+    );
+    let mut sink = FirstN {
+        limit: 2,
+        offs: Vec::new(),
+    };
+    eng.search_control(&mut &data[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![0, 3]);
+}
@@ -0,0 +1,36 @@
+use simd_grep::count_lines;
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+#[test]
+fn empty_input_has_no_lines() {
+    assert_eq!(count_lines(b""), 0);
+}
+
+#[test]
+fn trailing_newline_is_optional() {
+    assert_eq!(count_lines(b"one\ntwo\nthree\n"), 3);
+    assert_eq!(count_lines(b"one\ntwo\nthree"), 3);
+    assert_eq!(count_lines(b"one"), 1);
+    assert_eq!(count_lines(b"\r\n"), 1);
+}
+
+#[test]
+fn only_newlines_are_empty_lines() {
+    assert_eq!(count_lines(b"\n"), 1);
+    assert_eq!(count_lines(b"\n\n\n"), 3);
+}
+
+#[test]
+fn agrees_with_the_last_line_number() {
+    let data = b"a\n\nb a\nlast a".repeat(50);
+    let opts = GrepOptions {
+        chunk_bytes: 16,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"last", opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(*sink.line_nos.last().unwrap() as usize, count_lines(&data));
+}
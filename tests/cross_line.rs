@@ -0,0 +1,62 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::testutil::ChunkedReader;
+
+// With 16-byte chunks the needle "end\nstart" straddles the first boundary:
+// "...end\n|start...". The match starts on line 2 and ends on line 3.
+const DATA: &[u8] = b"first\nsecond end\nstart third\n";
+
+fn opts(flags: GrepFlags) -> GrepOptions {
+    GrepOptions {
+        flags,
+        chunk_bytes: 16,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn cross_line_match_reports_its_starting_line() {
+    assert_eq!(&DATA[..16], b"first\nsecond end");
+    let eng = GrepEngine::new_literal(b"end\nstart", opts(GrepFlags::LINE_NUMBER));
+    let mut sink = VecSink::default();
+    eng.search(&mut ChunkedReader::new(DATA, 3), &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![13]);
+    assert_eq!(sink.line_nos, vec![2]);
+    assert_eq!(sink.cols, vec![8]);
+}
+
+#[test]
+fn no_cross_line_drops_matches_spanning_newlines() {
+    let flags = GrepFlags::LINE_NUMBER | GrepFlags::NO_CROSS_LINE;
+    let eng = GrepEngine::new_literal(b"end\nstart", opts(flags));
+    let mut sink = VecSink::default();
+    eng.search(&mut &DATA[..], &mut sink).unwrap();
+    assert!(sink.offs.is_empty());
+    let mut sink = VecSink::default();
+    eng.search_slice(DATA, &mut sink).unwrap();
+    assert!(sink.offs.is_empty());
+
+    let eng = GrepEngine::new_literal(b"end\nstart", opts(GrepFlags::NO_CROSS_LINE));
+    let mut sink = VecSink::default();
+    eng.search_kmp(&mut &DATA[..], &mut sink).unwrap();
+    assert!(sink.offs.is_empty());
+
+    // Single-line matches are unaffected.
+    let eng = GrepEngine::new_literal(b"start", opts(flags));
+    let mut sink = VecSink::default();
+    eng.search(&mut &DATA[..], &mut sink).unwrap();
+    assert_eq!(sink.line_nos, vec![3]);
+}
+
+#[test]
+fn no_cross_line_filters_regex_matches() {
+    for (flags, expected) in [
+        (GrepFlags::empty(), vec![13]),
+        (GrepFlags::NO_CROSS_LINE, vec![]),
+    ] {
+        let eng = GrepEngine::new_regex(r"end\s+start", opts(flags)).unwrap();
+        let mut sink = VecSink::default();
+        eng.search_slice(DATA, &mut sink).unwrap();
+        assert_eq!(sink.offs, expected);
+    }
+}
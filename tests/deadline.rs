@@ -0,0 +1,65 @@
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+
+/// Hands out `data` a few bytes at a time, sleeping before each read.
+struct Slow<'a> {
+    data: &'a [u8],
+    delay: Duration,
+}
+
+impl Read for Slow<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        thread::sleep(self.delay);
+        let n = buf.len().min(self.data.len()).min(8);
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn slow_search_times_out_with_partial_results() {
+    let data = b"needle..".repeat(1000);
+    let mut reader = Slow {
+        data: &data,
+        delay: Duration::from_millis(5),
+    };
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        deadline: Some(Instant::now() + Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    let err = GrepEngine::new_literal(b"needle", opts)
+        .search(&mut reader, &mut sink)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    let found = sink.offs.len() as u64;
+    assert!(found > 0 && found < 1000, "found {}", found);
+    match GrepError::from(err) {
+        GrepError::Timeout { matches, path } => {
+            assert_eq!(matches, found);
+            assert!(path.is_none());
+        }
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[test]
+fn deadline_in_the_future_is_harmless() {
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        deadline: Some(Instant::now() + Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"needle", opts)
+        .search(&mut &b"a needle and a needle"[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![2, 15]);
+}
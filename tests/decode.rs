@@ -0,0 +1,103 @@
+mod common;
+
+use std::io::{self, Read};
+
+use common::Trickle;
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::decode::{Base64Source, HexSource};
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Padded base64, wrapped at 76 columns like MIME.
+fn base64(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for group in data.chunks(3) {
+        let b = [
+            group[0],
+            *group.get(1).unwrap_or(&0),
+            *group.get(2).unwrap_or(&0),
+        ];
+        let sextets = [
+            b[0] >> 2,
+            (b[0] << 4 | b[1] >> 4) & 63,
+            (b[1] << 2 | b[2] >> 6) & 63,
+            b[2] & 63,
+        ];
+        for (i, s) in sextets.iter().enumerate() {
+            out.push(if i <= group.len() {
+                ALPHABET[*s as usize]
+            } else {
+                b'='
+            });
+        }
+        if out.len() % 77 == 76 {
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+fn plain() -> Vec<u8> {
+    let mut data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    data.splice(613..613, b"needle".iter().copied());
+    data
+}
+
+#[test]
+fn base64_needle_found_at_decoded_offset() {
+    let data = plain();
+    let encoded = base64(&data);
+    for step in [1, 3, 5, 4096] {
+        let mut source = Base64Source::new(Trickle::new(&encoded, step));
+        let mut sink = VecSink::default();
+        GrepEngine::new_literal(b"needle", GrepOptions::default())
+            .search(&mut source, &mut sink)
+            .unwrap();
+        assert_eq!(sink.offs, vec![613], "step {}", step);
+    }
+}
+
+#[test]
+fn base64_round_trips_with_and_without_padding() {
+    for len in 0..10 {
+        let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37)).collect();
+        let padded = base64(&data);
+        let unpadded: Vec<u8> = padded.iter().copied().filter(|&b| b != b'=').collect();
+        for encoded in [padded, unpadded] {
+            let mut out = Vec::new();
+            Base64Source::new(&encoded[..])
+                .read_to_end(&mut out)
+                .unwrap();
+            assert_eq!(out, data);
+        }
+    }
+}
+
+#[test]
+fn hex_skips_whitespace_and_carries_split_pairs() {
+    let encoded = b"6e 65\n65 64\r\n6C65\t\n";
+    let mut out = Vec::new();
+    HexSource::new(Trickle::new(encoded, 1))
+        .read_to_end(&mut out)
+        .unwrap();
+    assert_eq!(out, b"needle");
+}
+
+#[test]
+fn malformed_input_is_invalid_data() {
+    let mut out = Vec::new();
+    let err = HexSource::new(&b"abc"[..])
+        .read_to_end(&mut out)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let err = Base64Source::new(&b"ab$d"[..])
+        .read_to_end(&mut out)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let err = Base64Source::new(&b"abcde"[..])
+        .read_to_end(&mut out)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
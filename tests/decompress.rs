@@ -0,0 +1,102 @@
+//! Run the codec tests with `cargo test --all-features --test decompress`.
+
+mod common;
+
+use std::io::Read;
+
+use common::TempDir;
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::decompress::{self, Codec};
+
+fn plain() -> Vec<u8> {
+    b"some text with a needle in it\n".repeat(500)
+}
+
+/// Decompresses `path` through `open`, checks the bytes round-tripped and
+/// returns the needle offsets found in them.
+fn search_decompressed(path: &std::path::Path) -> Vec<u64> {
+    let expected = plain();
+    let mut out = Vec::new();
+    decompress::open(path)
+        .unwrap()
+        .read_to_end(&mut out)
+        .unwrap();
+    assert_eq!(out, expected);
+
+    let opts = GrepOptions {
+        chunk_bytes: 1000,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"needle", opts)
+        .search(&mut decompress::open(path).unwrap(), &mut sink)
+        .unwrap();
+    sink.offs
+}
+
+fn expected_offsets() -> Vec<u64> {
+    (0..500).map(|i| i * 30 + 17).collect()
+}
+
+#[test]
+fn codecs_are_picked_by_extension() {
+    let codec = |p: &str| Codec::from_path(std::path::Path::new(p));
+    assert_eq!(codec("a.txt.gz"), Some(Codec::Gzip));
+    assert_eq!(codec("a.zst"), Some(Codec::Zstd));
+    assert_eq!(codec("a.bz2"), Some(Codec::Bzip2));
+    assert_eq!(codec("a.txt"), None);
+    assert_eq!(codec("gz"), None);
+}
+
+#[test]
+fn unknown_extensions_are_read_raw() {
+    let dir = TempDir::new("decompress-raw");
+    let path = dir.write("plain.txt", &plain());
+    assert_eq!(search_decompressed(&path), expected_offsets());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_round_trip() {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(&plain()).unwrap();
+    let dir = TempDir::new("decompress-gz");
+    let path = dir.write("data.txt.gz", &enc.finish().unwrap());
+    assert_eq!(search_decompressed(&path), expected_offsets());
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_round_trip() {
+    let packed = zstd::encode_all(&plain()[..], 3).unwrap();
+    let dir = TempDir::new("decompress-zst");
+    let path = dir.write("data.zst", &packed);
+    assert_eq!(search_decompressed(&path), expected_offsets());
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn bzip2_round_trip() {
+    use bzip2::Compression;
+    use bzip2::write::BzEncoder;
+    use std::io::Write;
+
+    let mut enc = BzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(&plain()).unwrap();
+    let dir = TempDir::new("decompress-bz2");
+    let path = dir.write("data.bz2", &enc.finish().unwrap());
+    assert_eq!(search_decompressed(&path), expected_offsets());
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn disabled_codec_is_unsupported() {
+    let dir = TempDir::new("decompress-off");
+    let path = dir.write("data.gz", b"not really gzip");
+    let err = decompress::open(&path).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
@@ -0,0 +1,50 @@
+use std::io::Read;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepOptions};
+
+fn small_chunks() -> GrepOptions {
+    GrepOptions {
+        chunk_bytes: 4096,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn estimate_is_close_to_the_known_density() {
+    // About one "ab" in 16 random bytes over {a, b, c, d}.
+    let mut rng = StdRng::seed_from_u64(0xde75);
+    let data: Vec<u8> = (0..1 << 20).map(|_| b"abcd"[rng.gen_range(0..4)]).collect();
+    let engine = GrepEngine::new_literal(b"ab", small_chunks());
+    let mut reader = &data[..];
+    let density = engine.estimate_density(&mut reader, 64 * 1024).unwrap();
+    assert!((density - 1.0 / 16.0).abs() < 0.005, "density {density}");
+    // Only the sample was read.
+    assert_eq!(reader.len(), data.len() - 64 * 1024);
+}
+
+#[test]
+fn estimate_counts_every_match_in_the_sample() {
+    let data = [b"needle".as_slice(), &[b'x'; 94]].concat().repeat(100);
+    let engine = GrepEngine::new_literal(b"needle", small_chunks());
+    let density = engine.estimate_density(&mut &data[..], 5000).unwrap();
+    assert_eq!(density, 50.0 / 5000.0);
+
+    // A sample larger than the input is divided by the bytes actually read.
+    let density = engine.estimate_density(&mut &data[..], 1 << 20).unwrap();
+    assert_eq!(density, 0.01);
+}
+
+#[test]
+fn empty_sample_has_zero_density() {
+    let engine = GrepEngine::new_literal(b"x", GrepOptions::default());
+    assert_eq!(engine.estimate_density(&mut &b"xxx"[..], 0).unwrap(), 0.0);
+    assert_eq!(
+        engine.estimate_density(&mut std::io::empty(), 100).unwrap(),
+        0.0
+    );
+    let mut rest = &b"xxx"[..];
+    engine.estimate_density(&mut rest, 0).unwrap();
+    assert_eq!(rest.bytes().count(), 3);
+}
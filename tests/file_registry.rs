@@ -0,0 +1,83 @@
+mod common;
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use common::TempDir;
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::output::{PathStyle, PrintSink};
+use simd_grep::io::walk::WalkOptions;
+use simd_grep::io::{FileRegistry, search_dir, search_paths};
+
+#[test]
+fn registered_ids_resolve_to_their_paths() {
+    let dir = TempDir::new("registry");
+    let mut registry = FileRegistry::new();
+    let a = registry.register(dir.write("a.txt", b"needle"));
+    let b = registry.register(dir.write("b.txt", b"none"));
+    let c = registry.register(dir.write("c.txt", b"x needle"));
+    assert_eq!((a, b, c), (0, 1, 2));
+    assert_eq!(registry.len(), 3);
+
+    let mut sink = VecSink::default();
+    for (id, path) in registry.iter() {
+        let engine = GrepEngine::new_literal(b"needle", GrepOptions::default().with_file_id(id));
+        engine
+            .search(&mut File::open(path).unwrap(), &mut sink)
+            .unwrap();
+    }
+    let found: Vec<&Path> = sink
+        .file_ids
+        .iter()
+        .map(|&id| registry.path(id).unwrap())
+        .collect();
+    assert_eq!(
+        found,
+        vec![dir.path().join("a.txt"), dir.path().join("c.txt")]
+    );
+    assert_eq!(registry.path(3), None);
+}
+
+#[test]
+fn default_id_is_the_first_registered_path() {
+    let registry: FileRegistry = ["only.txt"].into_iter().collect();
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"ab", GrepOptions::default())
+        .search(&mut &b"xab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(registry.path(sink.file_ids[0]), Some(Path::new("only.txt")));
+
+    let mut print = PrintSink::new(Vec::new(), registry.into(), PathStyle::Lossy);
+    GrepEngine::new_literal(b"ab", GrepOptions::default())
+        .search(&mut &b"xab"[..], &mut print)
+        .unwrap();
+    assert_eq!(print.finish().unwrap(), b"only.txt:1\n");
+}
+
+#[test]
+fn multi_file_helpers_number_files_like_a_registry() {
+    let dir = TempDir::new("registry_helpers");
+    let paths = vec![dir.write("one", b"-- needle"), dir.write("two", b"needle")];
+    let mut sink = VecSink::default();
+    assert!(search_paths(&paths, b"needle", &GrepOptions::default(), &mut sink).is_empty());
+    let registry = FileRegistry::from(paths.clone());
+    let resolved: Vec<PathBuf> = sink
+        .file_ids
+        .iter()
+        .map(|&id| registry.path(id).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(resolved, paths);
+
+    let pattern = simd_grep::engine::PreparedPattern::new(b"needle");
+    let mut sink = VecSink::default();
+    let files = search_dir(
+        dir.path(),
+        &pattern,
+        &GrepOptions::default(),
+        &WalkOptions::default(),
+        &mut sink,
+    )
+    .unwrap();
+    let registry = FileRegistry::from(files);
+    assert_eq!(registry.path(sink.file_ids[1]), Some(paths[1].as_path()));
+}
@@ -0,0 +1,70 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink, grep_bytes};
+use simd_grep::io::collect_matching_lines;
+
+const DATA: &[u8] = b"ab\nnone\nlast ab";
+
+fn lines(require_final_newline: bool) -> Vec<(u64, u32)> {
+    let mut results = Vec::new();
+    for chunk_bytes in [4, 8, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            flags: GrepFlags::LINE_NUMBER,
+            require_final_newline,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_literal(b"ab", opts);
+        let mut streamed = VecSink::default();
+        engine.search(&mut &DATA[..], &mut streamed).unwrap();
+        let mut sliced = VecSink::default();
+        engine.search_slice(DATA, &mut sliced).unwrap();
+        assert_eq!(streamed.offs, sliced.offs);
+        assert_eq!(streamed.line_nos, sliced.line_nos);
+        let got: Vec<_> = streamed.offs.into_iter().zip(streamed.line_nos).collect();
+        if chunk_bytes == 4 {
+            results = got;
+        } else {
+            assert_eq!(got, results, "chunk_bytes={chunk_bytes}");
+        }
+    }
+    results
+}
+
+#[test]
+fn unterminated_last_line_is_a_line_by_default() {
+    assert_eq!(lines(false), vec![(0, 1), (13, 3)]);
+    let hits = grep_bytes(DATA, b"ab", &GrepOptions::default()).unwrap();
+    assert_eq!(hits.last().unwrap().line_no, 3);
+    assert_eq!(hits.last().unwrap().line, b"last ab");
+    let collected = collect_matching_lines(DATA, b"ab", GrepOptions::default()).unwrap();
+    assert_eq!(collected, "ab\nlast ab");
+}
+
+#[test]
+fn require_final_newline_drops_the_unterminated_tail() {
+    assert_eq!(lines(true), vec![(0, 1)]);
+    let opts = GrepOptions {
+        require_final_newline: true,
+        ..Default::default()
+    };
+    let collected = collect_matching_lines(DATA, b"ab", opts.clone()).unwrap();
+    assert_eq!(collected, "ab");
+
+    // A terminated last line is reported either way.
+    let terminated = b"ab\nlast ab\n";
+    let hits = grep_bytes(terminated, b"ab", &opts).unwrap();
+    assert_eq!(
+        hits.iter().map(|h| h.line_no).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    // Without line awareness there are no lines to drop.
+    let plain = GrepOptions {
+        require_final_newline: true,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"ab", plain)
+        .search_slice(DATA, &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![0, 13]);
+}
@@ -0,0 +1,109 @@
+use std::io::{self, Cursor};
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::chunker::{Chunker, ChunkerError};
+use simd_grep::io::testutil::ChunkedReader;
+
+fn chunks(mut chunker: Chunker<'_, impl io::Read>) -> Vec<(u64, Vec<u8>)> {
+    let mut out = Vec::new();
+    while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+        out.push((base, chunk.to_vec()));
+    }
+    out
+}
+
+#[test]
+fn slice_chunker_matches_heap_chunker() {
+    let data: Vec<u8> = (0..200u8).collect();
+    let mut buf = [0u8; 12];
+    let fixed = chunks(Chunker::with_slice(&mut &data[..], 9, 3, &mut buf));
+    let heap = chunks(Chunker::new(&mut &data[..], 9, 3));
+    assert_eq!(fixed, heap);
+}
+
+#[test]
+fn slice_must_hold_chunk_and_overlap() {
+    let mut reader = Cursor::new(&b"0123456789"[..]);
+    let mut buf = [0u8; 11];
+    let err = Chunker::try_with_slice(&mut reader, 9, 3, &mut buf)
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        ChunkerError::BufferTooSmall {
+            len: 11,
+            needed: 12
+        }
+    );
+    assert!(Chunker::try_with_slice(&mut reader, 2, 10, &mut buf).is_err());
+    assert!(Chunker::try_with_slice(&mut reader, 8, 3, &mut buf).is_ok());
+}
+
+#[test]
+fn retaining_past_the_slice_fails() {
+    let mut reader = Cursor::new(&b"0123456789abcdef"[..]);
+    let mut buf = [0u8; 6];
+    let mut chunker = Chunker::with_slice(&mut reader, 4, 1, &mut buf);
+    assert_eq!(chunker.next_chunk().unwrap(), Some((0, &b"0123"[..])));
+    chunker.retain(3);
+    let err = chunker.next_chunk().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+}
+
+#[test]
+fn search_with_stack_buffer_matches_heap_search() {
+    let data = b"alpha needle\nbeta\nneedle needle gamma\nneedlneedle\n".repeat(5);
+    for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+        let eng = GrepEngine::new_literal(
+            b"needle",
+            GrepOptions {
+                flags,
+                chunk_bytes: 16,
+                ..Default::default()
+            },
+        );
+        let mut heap = VecSink::default();
+        eng.search(&mut &data[..], &mut heap).unwrap();
+
+        let mut buf = [0u8; 64];
+        let mut fixed = VecSink::default();
+        eng.search_with_slice(&mut ChunkedReader::new(&data[..], 5), &mut fixed, &mut buf)
+            .unwrap();
+        assert_eq!(fixed.offs, heap.offs);
+        assert_eq!(fixed.line_nos, heap.line_nos);
+        assert_eq!(fixed.cols, heap.cols);
+        assert_eq!(fixed.offs.len(), 20);
+    }
+}
+
+#[test]
+fn search_with_too_small_slice() {
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            chunk_bytes: 16,
+            ..Default::default()
+        },
+    );
+    let mut buf = [0u8; 20];
+    let err = eng
+        .search_with_slice(&mut &b"needle"[..], &mut VecSink::default(), &mut buf)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // A line longer than the buffer can carry doesn't fit in line-aware mode.
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            flags: GrepFlags::LINE_NUMBER,
+            chunk_bytes: 16,
+            ..Default::default()
+        },
+    );
+    let data = [b'x'; 100];
+    let mut buf = [0u8; 32];
+    let err = eng
+        .search_with_slice(&mut &data[..], &mut VecSink::default(), &mut buf)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+}
@@ -0,0 +1,37 @@
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink, escape_literal};
+
+fn offs(engine: &GrepEngine<'_>, data: &[u8]) -> Vec<u64> {
+    let mut sink = VecSink::default();
+    engine.search(&mut &data[..], &mut sink).unwrap();
+    sink.offs
+}
+
+#[test]
+fn fixed_string_ignores_regex_syntax() {
+    let data = b"abc a.c";
+    let fixed = GrepEngine::new_fixed_string(b"a.c", GrepOptions::default());
+    assert_eq!(offs(&fixed, data), vec![4]);
+
+    let regex = GrepEngine::new_regex("a.c", GrepOptions::default()).unwrap();
+    assert_eq!(offs(&regex, data), vec![0, 4]);
+}
+
+#[test]
+fn escaped_literal_runs_as_regex() {
+    let needles: [&[u8]; 4] = [
+        b"a.c",
+        b"(x|y)*[z]{2}^$\\",
+        b"#&-~ +?",
+        b"caf\xc3\xa9 \xff\x00",
+    ];
+    for needle in needles {
+        let mut data = b"a-c xyz ".to_vec();
+        data.extend_from_slice(needle);
+        data.extend_from_slice(b" abc");
+        let pattern = String::from_utf8(escape_literal(needle)).unwrap();
+        let regex = GrepEngine::new_regex(&pattern, GrepOptions::default()).unwrap();
+        let fixed = GrepEngine::new_fixed_string(needle, GrepOptions::default());
+        assert_eq!(offs(&regex, &data), vec![8], "pattern {pattern}");
+        assert_eq!(offs(&regex, &data), offs(&fixed, &data));
+    }
+}
@@ -0,0 +1,232 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+
+fn rejected(opts: GrepOptions) -> String {
+    match GrepEngine::try_new_literal(b"needle", opts) {
+        Err(GrepError::Options(msg)) => msg,
+        Err(e) => panic!("unexpected error {e}"),
+        Ok(_) => panic!("options were accepted"),
+    }
+}
+
+#[test]
+fn helpers_build_the_expected_bits() {
+    assert_eq!(GrepFlags::count_only(), GrepFlags::COUNT_ONLY);
+    assert_eq!(GrepFlags::line_numbers(), GrepFlags::LINE_NUMBER);
+    assert_eq!(
+        GrepFlags::line_offsets(),
+        GrepFlags::LINE_NUMBER | GrepFlags::REPORT_LINE_OFFSET
+    );
+    assert_eq!(
+        GrepFlags::count_only().with(GrepFlags::line_numbers()),
+        GrepFlags::COUNT_ONLY | GrepFlags::LINE_NUMBER
+    );
+    assert_eq!(
+        GrepFlags::line_offsets().without(GrepFlags::REPORT_LINE_OFFSET),
+        GrepFlags::LINE_NUMBER
+    );
+    let opts = GrepOptions::default().with_flags(GrepFlags::line_numbers());
+    assert_eq!(opts.flags, GrepFlags::LINE_NUMBER);
+}
+
+#[test]
+fn conflicting_combinations_are_rejected() {
+    let line_offset_alone = GrepOptions::default().with_flags(GrepFlags::REPORT_LINE_OFFSET);
+    assert!(rejected(line_offset_alone).contains("requires LINE_NUMBER"));
+
+    let count_and_offsets =
+        GrepOptions::default().with_flags(GrepFlags::line_offsets().with(GrepFlags::COUNT_ONLY));
+    assert!(rejected(count_and_offsets).contains("COUNT_ONLY"));
+
+    let per_line = GrepOptions {
+        max_matches_per_line: Some(1),
+        ..Default::default()
+    };
+    assert!(rejected(per_line).contains("max_matches_per_line"));
+
+    let short_overlap = GrepOptions {
+        overlap: Some(2),
+        ..Default::default()
+    };
+    assert!(rejected(short_overlap).contains("overlap"));
+}
+
+#[test]
+fn consistent_combinations_are_accepted() {
+    for flags in [
+        GrepFlags::empty(),
+        GrepFlags::count_only(),
+        GrepFlags::line_numbers(),
+        GrepFlags::line_offsets(),
+        GrepFlags::count_only().with(GrepFlags::line_numbers()),
+    ] {
+        assert!(
+            GrepEngine::try_new_literal(b"needle", GrepOptions::default().with_flags(flags))
+                .is_ok()
+        );
+    }
+}
+
+#[test]
+fn new_literal_applies_precedence_instead() {
+    let data: &[u8] = b"ab\nxab";
+    let run = |flags| {
+        let mut sink = VecSink::default();
+        GrepEngine::new_literal(b"ab", GrepOptions::default().with_flags(flags))
+            .search(&mut &data[..], &mut sink)
+            .unwrap();
+        sink.offs
+    };
+    // REPORT_LINE_OFFSET without LINE_NUMBER is ignored.
+    assert_eq!(run(GrepFlags::REPORT_LINE_OFFSET), vec![0, 4]);
+    // COUNT_ONLY does not suppress reports; REPORT_LINE_OFFSET still applies.
+    assert_eq!(
+        run(GrepFlags::line_offsets().with(GrepFlags::COUNT_ONLY)),
+        vec![0, 3]
+    );
+}
+
+fn invalid(opts: GrepOptions) -> String {
+    match opts.validate(6) {
+        Err(GrepError::Options(msg)) => msg,
+        Err(e) => panic!("unexpected error {e}"),
+        Ok(()) => panic!("options were accepted"),
+    }
+}
+
+#[test]
+fn validate_rejects_each_nonsensical_configuration() {
+    let zero_chunk = GrepOptions {
+        chunk_bytes: 0,
+        ..Default::default()
+    };
+    assert_eq!(invalid(zero_chunk), "chunk_bytes must be at least 1");
+
+    let wide_overlap = GrepOptions {
+        chunk_bytes: 8,
+        overlap: Some(9),
+        ..Default::default()
+    };
+    assert_eq!(invalid(wide_overlap), "overlap 9 exceeds chunk_bytes 8");
+
+    let short_overlap = GrepOptions {
+        overlap: Some(4),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid(short_overlap),
+        "overlap 4 is shorter than the 5 bytes the pattern needs"
+    );
+
+    let conflict = GrepOptions::default().with_flags(GrepFlags::COUNT_LINES);
+    assert_eq!(invalid(conflict), "COUNT_LINES requires LINE_NUMBER");
+
+    let per_line = GrepOptions {
+        max_matches_per_line: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid(per_line),
+        "max_matches_per_line requires LINE_NUMBER"
+    );
+
+    let no_matches = GrepOptions {
+        max_matches: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        invalid(no_matches),
+        "max_matches of 0 would never report a match"
+    );
+
+    let stride = GrepOptions {
+        match_stride: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(invalid(stride), "match_stride must be at least 1");
+
+    let separator = GrepOptions {
+        record_separator: Some(Vec::new()),
+        ..Default::default()
+    };
+    assert_eq!(invalid(separator), "record_separator must not be empty");
+
+    let align = GrepOptions {
+        buffer_align: Some(48),
+        ..Default::default()
+    };
+    assert_eq!(invalid(align), "buffer alignment 48 is not a power of two");
+}
+
+#[test]
+fn validate_accepts_defaults_and_tight_settings() {
+    assert!(GrepOptions::default().validate(6).is_ok());
+    assert!(GrepOptions::default().validate(0).is_ok());
+    let tight = GrepOptions {
+        chunk_bytes: 5,
+        overlap: Some(5),
+        flags: GrepFlags::line_offsets(),
+        max_matches: Some(1),
+        max_matches_per_line: Some(1),
+        buffer_align: Some(64),
+        ..Default::default()
+    };
+    assert!(tight.validate(6).is_ok());
+}
+
+#[test]
+fn search_rejects_zero_chunk_bytes() {
+    let opts = GrepOptions {
+        chunk_bytes: 0,
+        ..Default::default()
+    };
+    let err = GrepEngine::new_literal(b"ab", opts)
+        .search(&mut &b"abab"[..], &mut VecSink::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("chunk_bytes"));
+}
+
+#[test]
+fn search_rejects_values_validate_rejects() {
+    for opts in [
+        GrepOptions {
+            record_separator: Some(vec![]),
+            ..Default::default()
+        },
+        GrepOptions {
+            match_stride: Some(0),
+            ..Default::default()
+        },
+        GrepOptions {
+            line_range: Some((3, 2)),
+            flags: GrepFlags::LINE_NUMBER,
+            ..Default::default()
+        },
+        GrepOptions {
+            buffer_align: Some(3),
+            ..Default::default()
+        },
+        GrepOptions {
+            chunk_bytes: 0,
+            ..Default::default()
+        },
+    ] {
+        assert!(opts.validate(2).is_err());
+        let engine = GrepEngine::new_literal(b"ab", opts.clone());
+        let results = [
+            engine.search(&mut &b"abab"[..], &mut VecSink::default()),
+            engine.search_slice(b"abab", &mut VecSink::default()),
+            engine.search_buffered(&mut &b"abab"[..], &mut VecSink::default()),
+            engine.push_searcher().map(|_| ()),
+        ];
+        for (i, res) in results.into_iter().enumerate() {
+            let err = res.unwrap_err();
+            assert_eq!(
+                err.kind(),
+                std::io::ErrorKind::InvalidInput,
+                "{i}: {opts:?}"
+            );
+        }
+    }
+}
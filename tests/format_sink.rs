@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, MatchSink};
+use simd_grep::error::GrepError;
+use simd_grep::io::output::FormatSink;
+
+fn run(template: &str, data: &[u8], flags: GrepFlags) -> String {
+    let opts = GrepOptions {
+        chunk_bytes: 5,
+        flags,
+        ..Default::default()
+    };
+    let mut sink = FormatSink::new(Vec::new(), template, vec![PathBuf::from("notes.txt")]).unwrap();
+    GrepEngine::new_literal(b"needle", opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    String::from_utf8(sink.finish().unwrap()).unwrap()
+}
+
+const DATA: &[u8] = b"first needle\nnothing\nneedle and needle\n";
+
+#[test]
+fn file_line_offset_template() {
+    let out = run("{file}:{line}:{offset}\n", DATA, GrepFlags::LINE_NUMBER);
+    assert_eq!(out, "notes.txt:1:6\nnotes.txt:3:21\nnotes.txt:3:32\n");
+}
+
+#[test]
+fn text_len_and_col_tokens() {
+    let out = run("{line}:{col}:{len}:{text}\n", DATA, GrepFlags::LINE_NUMBER);
+    assert_eq!(
+        out,
+        "1:7:6:first needle\n3:1:6:needle and needle\n3:12:6:needle and needle\n"
+    );
+}
+
+#[test]
+fn line_tokens_without_line_awareness() {
+    let out = run("{line}|{text}|{offset};", DATA, GrepFlags::empty());
+    assert_eq!(out, "0||6;0||21;0||32;");
+}
+
+#[test]
+fn escaped_braces_are_literal() {
+    let out = run("{{{offset}}} ", b"needle", GrepFlags::empty());
+    assert_eq!(out, "{0} ");
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    for bad in ["{nope}", "{offset", "offset}", "{"] {
+        let err = FormatSink::new(Vec::new(), bad, Vec::new()).err().unwrap();
+        assert!(matches!(err, GrepError::Format(_)), "{bad}");
+    }
+}
+
+#[test]
+fn direct_on_match_calls_are_formatted() {
+    let mut sink = FormatSink::new(Vec::new(), "{file}@{offset}+{len}\n", Vec::new()).unwrap();
+    sink.on_match(10, 2, 0, 7);
+    assert_eq!(sink.finish().unwrap(), b"@10+2\n".to_vec());
+}
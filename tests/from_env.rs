@@ -0,0 +1,100 @@
+use std::env;
+use std::sync::Mutex;
+
+use simd_grep::engine::GrepOptions;
+use simd_grep::io::output::ColorChoice;
+
+const VARS: [&str; 3] = ["SIMD_GREP_CHUNK_BYTES", "SIMD_GREP_COLOR", "NO_COLOR"];
+
+// Tests in this file run on parallel threads of one process and share its
+// environment, so each holds this lock while it sets variables.
+static ENV: Mutex<()> = Mutex::new(());
+
+/// Runs `f` with exactly `vars` set among the variables `from_env` reads.
+fn with_env<T>(vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+    let _guard = ENV.lock().unwrap_or_else(|e| e.into_inner());
+    // SAFETY: every test touching the environment holds `ENV`, and nothing
+    // else in this test binary reads it.
+    unsafe {
+        for name in VARS {
+            env::remove_var(name);
+        }
+        for (name, value) in vars {
+            env::set_var(name, value);
+        }
+    }
+    let out = f();
+    unsafe {
+        for name in VARS {
+            env::remove_var(name);
+        }
+    }
+    out
+}
+
+#[test]
+fn unset_environment_gives_defaults() {
+    let opts = with_env(&[], GrepOptions::from_env);
+    let default = GrepOptions::default();
+    assert_eq!(opts.chunk_bytes, default.chunk_bytes);
+    assert_eq!(opts.color, ColorChoice::Auto);
+}
+
+#[test]
+fn chunk_bytes_with_suffixes() {
+    for (value, expected) in [
+        ("4096", 4096),
+        ("64K", 64 * 1024),
+        (" 2m ", 2 * 1024 * 1024),
+        ("1 G", 1 << 30),
+    ] {
+        let opts = with_env(&[("SIMD_GREP_CHUNK_BYTES", value)], GrepOptions::from_env);
+        assert_eq!(opts.chunk_bytes, expected, "{value:?}");
+    }
+}
+
+#[test]
+fn invalid_values_fall_back() {
+    let default = GrepOptions::default().chunk_bytes;
+    for value in ["", "0", "-5", "12X", "K", "lots", "99999999999999999999G"] {
+        let opts = with_env(
+            &[("SIMD_GREP_CHUNK_BYTES", value), ("SIMD_GREP_COLOR", value)],
+            GrepOptions::from_env,
+        );
+        assert_eq!(opts.chunk_bytes, default, "{value:?}");
+        assert_eq!(opts.color, ColorChoice::Auto, "{value:?}");
+    }
+}
+
+#[test]
+fn color_and_no_color() {
+    let color = |vars: &[(&str, &str)]| with_env(vars, GrepOptions::from_env).color;
+    assert_eq!(color(&[("SIMD_GREP_COLOR", "Always")]), ColorChoice::Always);
+    assert_eq!(color(&[("SIMD_GREP_COLOR", "never")]), ColorChoice::Never);
+    assert_eq!(color(&[("NO_COLOR", "1")]), ColorChoice::Never);
+    assert_eq!(color(&[("NO_COLOR", "")]), ColorChoice::Auto);
+    assert_eq!(
+        color(&[("NO_COLOR", "1"), ("SIMD_GREP_COLOR", "auto")]),
+        ColorChoice::Never
+    );
+    assert_eq!(
+        color(&[("NO_COLOR", "1"), ("SIMD_GREP_COLOR", "always")]),
+        ColorChoice::Always
+    );
+}
+
+#[test]
+fn with_env_keeps_unset_options() {
+    let base = GrepOptions {
+        chunk_bytes: 123,
+        color: ColorChoice::Always,
+        ..Default::default()
+    };
+    let opts = with_env(&[("NO_COLOR", "yes")], || base.clone().with_env());
+    assert_eq!(opts.chunk_bytes, 123);
+    assert_eq!(opts.color, ColorChoice::Never);
+
+    let opts = with_env(&[("SIMD_GREP_CHUNK_BYTES", "bad")], || base.with_env());
+    assert_eq!(opts.chunk_bytes, 123);
+    assert_eq!(opts.color, ColorChoice::Always);
+}
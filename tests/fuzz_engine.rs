@@ -0,0 +1,139 @@
+//! Randomized differential tests: the streaming engine against a naive reference.
+
+mod common;
+
+use std::io::Cursor;
+
+use common::Trickle;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+/// Ground truth: every offset where `needle` occurs in `haystack`.
+///
+/// With `overlapping`, the scan resumes one byte after each hit (the engine's
+/// convention); otherwise it resumes after the end of the hit.
+fn reference_matches(haystack: &[u8], needle: &[u8], overlapping: bool) -> Vec<u64> {
+    if needle.is_empty() {
+        return vec![0];
+    }
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        if &haystack[i..i + needle.len()] == needle {
+            out.push(i as u64);
+            i += if overlapping { 1 } else { needle.len() };
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+fn reference_line_no(haystack: &[u8], off: u64) -> u32 {
+    haystack[..off as usize]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u32
+        + 1
+}
+
+fn engine_matches(haystack: &[u8], needle: &[u8], opts: GrepOptions, step: usize) -> VecSink {
+    let eng = GrepEngine::new_literal(needle, opts);
+    let mut sink = VecSink::default();
+    eng.search(&mut Trickle::new(haystack, step), &mut sink)
+        .unwrap();
+    sink
+}
+
+fn random_bytes(rng: &mut StdRng, len: usize, alphabet: &[u8]) -> Vec<u8> {
+    (0..len)
+        .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+        .collect()
+}
+
+#[test]
+fn reference_helper_sanity() {
+    assert_eq!(reference_matches(b"aaaaa", b"aa", true), vec![0, 1, 2, 3]);
+    assert_eq!(reference_matches(b"aaaaa", b"aa", false), vec![0, 2]);
+    assert_eq!(reference_matches(b"abc", b"abcd", true), Vec::<u64>::new());
+}
+
+#[test]
+fn random_haystacks_match_reference() {
+    let mut rng = StdRng::seed_from_u64(0x5EED_0004);
+    let alphabets: [&[u8]; 3] = [b"ab", b"ab\n", b"abcdefgh\n"];
+    for _ in 0..400 {
+        let alphabet = alphabets[rng.gen_range(0..alphabets.len())];
+        let hay_len = rng.gen_range(0..200);
+        let haystack = random_bytes(&mut rng, hay_len, alphabet);
+        let needle_len = rng.gen_range(1..6);
+        let needle = random_bytes(&mut rng, needle_len, alphabet);
+        let chunk_bytes = rng.gen_range(1..24);
+        let step = rng.gen_range(1..32);
+
+        let expected = reference_matches(&haystack, &needle, true);
+        let ctx = format!(
+            "hay={:?} needle={:?} chunk_bytes={chunk_bytes} step={step}",
+            String::from_utf8_lossy(&haystack),
+            String::from_utf8_lossy(&needle),
+        );
+
+        let opts = GrepOptions {
+            chunk_bytes,
+            ..Default::default()
+        };
+        let plain = engine_matches(&haystack, &needle, opts, step);
+        assert_eq!(plain.offs, expected, "{ctx}");
+
+        let opts = GrepOptions {
+            chunk_bytes,
+            flags: GrepFlags::LINE_NUMBER,
+            ..Default::default()
+        };
+        let lined = engine_matches(&haystack, &needle, opts, step);
+        assert_eq!(lined.offs, expected, "line-aware {ctx}");
+        let lines: Vec<u32> = expected
+            .iter()
+            .map(|&o| reference_line_no(&haystack, o))
+            .collect();
+        assert_eq!(lined.line_nos, lines, "line-aware {ctx}");
+    }
+}
+
+#[test]
+fn every_chunk_size_matches_reference() {
+    let mut rng = StdRng::seed_from_u64(0xC4_0C4E);
+    let haystack = random_bytes(&mut rng, 300, b"xy\n");
+    for needle in [&b"x"[..], b"xy", b"yx\n", b"xyxy"] {
+        let expected = reference_matches(&haystack, needle, true);
+        for chunk_bytes in 1..=64 {
+            let opts = GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            };
+            let mut sink = VecSink::default();
+            GrepEngine::new_literal(needle, opts)
+                .search(&mut Cursor::new(&haystack), &mut sink)
+                .unwrap();
+            assert_eq!(
+                sink.offs, expected,
+                "needle={needle:?} chunk_bytes={chunk_bytes}"
+            );
+        }
+    }
+}
+
+/// Found by `random_haystacks_match_reference`: with a single-byte needle the
+/// overlap is zero, and the chunker never consumed the previous chunk, so the
+/// search re-reported it and never terminated.
+#[test]
+fn single_byte_needle_across_chunks() {
+    let haystack = b"xaxaxaxaxa";
+    let opts = GrepOptions {
+        chunk_bytes: 3,
+        ..Default::default()
+    };
+    let sink = engine_matches(haystack, b"a", opts, 1);
+    assert_eq!(sink.offs, vec![1, 3, 5, 7, 9]);
+}
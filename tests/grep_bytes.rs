@@ -0,0 +1,88 @@
+use std::io;
+
+use simd_grep::engine::{ByteRange, GrepFlags, GrepOptions, LineMatch, grep_bytes};
+
+fn lm(line_no: u32, byte_offset: u64, line: &[u8]) -> LineMatch {
+    LineMatch {
+        line_no,
+        byte_offset,
+        line: line.to_vec(),
+    }
+}
+
+#[test]
+fn returns_line_numbers_offsets_and_contents() {
+    let data = b"alpha needle\nbeta\nneedle gamma needle\nlast needle";
+    for chunk_bytes in [2, 7, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            ..Default::default()
+        };
+        let hits = grep_bytes(data, b"needle", &opts).unwrap();
+        assert_eq!(
+            hits,
+            vec![
+                lm(1, 6, b"alpha needle"),
+                lm(3, 18, b"needle gamma needle"),
+                lm(3, 31, b"needle gamma needle"),
+                lm(4, 43, b"last needle"),
+            ],
+            "chunk_bytes={chunk_bytes}"
+        );
+    }
+}
+
+#[test]
+fn no_matches_and_empty_lines() {
+    let hits = grep_bytes(b"a\n\nb\n", b"zzz", &GrepOptions::default()).unwrap();
+    assert!(hits.is_empty());
+    let hits = grep_bytes(b"\n\nx\n", b"x", &GrepOptions::default()).unwrap();
+    assert_eq!(hits, vec![lm(3, 2, b"x")]);
+}
+
+#[test]
+fn lines_follow_a_stripped_bom() {
+    let opts = GrepOptions {
+        strip_bom: true,
+        ..Default::default()
+    };
+    let hits = grep_bytes(b"\xEF\xBB\xBFab needle\nx needle", b"needle", &opts).unwrap();
+    assert_eq!(hits, vec![lm(1, 3, b"ab needle"), lm(2, 12, b"x needle")]);
+}
+
+#[test]
+fn offsets_are_the_match_even_with_line_offsets() {
+    let opts = GrepOptions {
+        flags: GrepFlags::REPORT_LINE_OFFSET,
+        ..Default::default()
+    };
+    let hits = grep_bytes(b"one\ntwo needle", b"needle", &opts).unwrap();
+    assert_eq!(hits, vec![lm(2, 8, b"two needle")]);
+}
+
+#[test]
+fn every_byte_range_is_searched() {
+    let data = b"needle one\ntwo needle\n";
+    let tail = GrepOptions {
+        byte_range: Some(ByteRange::Tail(11)),
+        ..Default::default()
+    };
+    let hits = grep_bytes(data, b"needle", &tail).unwrap();
+    assert_eq!(hits, vec![lm(1, 15, b"two needle")]);
+    let span = GrepOptions {
+        byte_range: Some(ByteRange::Span(0, 8)),
+        ..Default::default()
+    };
+    let hits = grep_bytes(data, b"needle", &span).unwrap();
+    assert_eq!(hits, vec![lm(1, 0, b"needle o")]);
+}
+
+#[test]
+fn invalid_options_are_an_error() {
+    let opts = GrepOptions {
+        chunk_bytes: 0,
+        ..Default::default()
+    };
+    let err = grep_bytes(b"needle", b"needle", &opts).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use std::error::Error;
+use std::io;
+
+use common::TempDir;
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+use simd_grep::io::search_path;
+
+#[test]
+fn missing_path_reports_io_error_with_path() {
+    let dir = TempDir::new("grep-error");
+    let missing = dir.path().join("does-not-exist.txt");
+    let eng = GrepEngine::new_literal(b"x", GrepOptions::default());
+
+    let err = search_path(&missing, &eng, &mut VecSink::default()).unwrap_err();
+    match &err {
+        GrepError::Io(e, Some(p)) => {
+            assert_eq!(e.kind(), io::ErrorKind::NotFound);
+            assert_eq!(p, &missing);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert_eq!(err.path(), Some(missing.as_path()));
+    assert!(err.to_string().contains("does-not-exist.txt"));
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn existing_path_is_searched() {
+    let dir = TempDir::new("grep-error");
+    let file = dir.write("a.txt", b"one needle two needle");
+    let eng = GrepEngine::new_literal(b"needle", GrepOptions::default());
+
+    let mut sink = VecSink::default();
+    search_path(&file, &eng, &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![4, 15]);
+}
+
+#[test]
+fn io_error_converts_without_path() {
+    let err: GrepError = io::Error::other("boom").into();
+    assert!(err.path().is_none());
+    assert_eq!(err.to_string(), "boom");
+}
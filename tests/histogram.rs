@@ -0,0 +1,35 @@
+use simd_grep::engine::{GrepEngine, GrepOptions, MatchSink};
+use simd_grep::io::output::HistogramSink;
+
+#[test]
+fn offsets_are_binned_with_boundaries_opening_a_bin() {
+    let mut sink = HistogramSink::new(10);
+    for off in [0, 9, 10, 19, 20, 45, 50] {
+        sink.on_match(off, 1, 0, 0);
+    }
+    assert_eq!(sink.finish(), vec![2, 2, 1, 0, 1, 1]);
+}
+
+#[test]
+fn empty_when_nothing_matched() {
+    assert!(HistogramSink::new(1 << 20).finish().is_empty());
+}
+
+#[test]
+fn engine_matches_feed_the_histogram() {
+    let mut data = [b'.'; 64];
+    for at in [1, 4, 16, 40] {
+        data[at..at + 2].copy_from_slice(b"ab");
+    }
+    let mut sink = HistogramSink::new(16);
+    GrepEngine::new_literal(b"ab", GrepOptions::default())
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.finish(), vec![2, 1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "non-zero")]
+fn zero_bin_size_panics() {
+    HistogramSink::new(0);
+}
@@ -0,0 +1,86 @@
+use simd_grep::engine::{Endian, GrepEngine, GrepOptions, IntWidth, VecSink, int_pattern};
+
+#[test]
+fn encodes_each_width_and_order() {
+    assert_eq!(
+        int_pattern(0x0102, IntWidth::U16, Endian::Big),
+        Some(vec![0x01, 0x02])
+    );
+    assert_eq!(
+        int_pattern(0x0102, IntWidth::U16, Endian::Little),
+        Some(vec![0x02, 0x01])
+    );
+    assert_eq!(
+        int_pattern(0xAB, IntWidth::U8, Endian::Big),
+        Some(vec![0xAB])
+    );
+    assert_eq!(
+        int_pattern(0xAB, IntWidth::U8, Endian::Little),
+        Some(vec![0xAB])
+    );
+    assert_eq!(
+        int_pattern(0xDEAD_BEEF, IntWidth::U32, Endian::Big),
+        Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+    );
+    assert_eq!(
+        int_pattern(0xDEAD_BEEF, IntWidth::U32, Endian::Little),
+        Some(vec![0xEF, 0xBE, 0xAD, 0xDE])
+    );
+    assert_eq!(
+        int_pattern(0x0102_0304_0506_0708, IntWidth::U64, Endian::Big),
+        Some(vec![1, 2, 3, 4, 5, 6, 7, 8])
+    );
+    assert_eq!(
+        int_pattern(0x0102_0304_0506_0708, IntWidth::U64, Endian::Little),
+        Some(vec![8, 7, 6, 5, 4, 3, 2, 1])
+    );
+}
+
+#[test]
+fn negative_values_encode_as_twos_complement() {
+    assert_eq!(
+        int_pattern(-2i16 as u64, IntWidth::U16, Endian::Little),
+        Some(vec![0xFE, 0xFF])
+    );
+    assert_eq!(
+        int_pattern(-1i8 as u64, IntWidth::U8, Endian::Big),
+        Some(vec![0xFF])
+    );
+    assert_eq!(
+        int_pattern(-1i64 as u64, IntWidth::U64, Endian::Big),
+        Some(vec![0xFF; 8])
+    );
+}
+
+#[test]
+fn too_wide_values_are_rejected() {
+    assert_eq!(int_pattern(0x1_0000, IntWidth::U16, Endian::Big), None);
+    assert_eq!(int_pattern(0x100, IntWidth::U8, Endian::Little), None);
+    // Sign-extended only if every bit above the width is set.
+    assert_eq!(int_pattern(-129i64 as u64, IntWidth::U8, Endian::Big), None);
+    assert_eq!(
+        int_pattern(-128i64 as u64, IntWidth::U8, Endian::Big),
+        Some(vec![0x80])
+    );
+}
+
+#[test]
+fn finds_integers_in_a_binary_buffer() {
+    // Records of a u16 tag followed by a little-endian u32 value.
+    let mut data = Vec::new();
+    for (tag, value) in [(1u16, 7u32), (2, 0x1234_5678), (3, 9), (4, 0x1234_5678)] {
+        data.extend_from_slice(&tag.to_le_bytes());
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let needle = int_pattern(0x1234_5678, IntWidth::U32, Endian::Little).unwrap();
+    let eng = GrepEngine::new_literal(&needle, GrepOptions::default());
+    let mut sink = VecSink::default();
+    eng.search(&mut &data[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![8, 20]);
+
+    let needle = int_pattern(0x1234_5678, IntWidth::U32, Endian::Big).unwrap();
+    let eng = GrepEngine::new_literal(&needle, GrepOptions::default());
+    let mut sink = VecSink::default();
+    eng.search(&mut &data[..], &mut sink).unwrap();
+    assert!(sink.offs.is_empty());
+}
@@ -0,0 +1,131 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{Control, GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, VecSink};
+use simd_grep::kmp::KmpSearcher;
+
+/// Overlapping occurrences, as the engine reports them.
+fn reference(haystack: &[u8], needle: &[u8]) -> Vec<u64> {
+    (0..haystack.len())
+        .filter(|&i| haystack[i..].starts_with(needle))
+        .map(|i| i as u64)
+        .collect()
+}
+
+fn fed_in_pieces(data: &[u8], needle: &[u8], piece: usize) -> Vec<u64> {
+    let mut kmp = KmpSearcher::new(needle);
+    let mut offs = Vec::new();
+    for part in data.chunks(piece) {
+        kmp.feed(part, |off| {
+            offs.push(off);
+            Control::Continue
+        });
+    }
+    assert_eq!(kmp.position(), data.len() as u64);
+    offs
+}
+
+#[test]
+fn straddling_matches_agree_with_the_reference() {
+    let mut rng = StdRng::seed_from_u64(0x4b3b);
+    for _ in 0..200 {
+        let data: Vec<u8> = (0..rng.gen_range(0..300))
+            .map(|_| b"ab"[rng.gen_range(0..2)])
+            .collect();
+        let needle: Vec<u8> = (0..rng.gen_range(1..7))
+            .map(|_| b"ab"[rng.gen_range(0..2)])
+            .collect();
+        let expected = reference(&data, &needle);
+        for piece in [1, 2, 3, 5, 8, 64, 1024] {
+            assert_eq!(
+                fed_in_pieces(&data, &needle, piece),
+                expected,
+                "needle {needle:?} piece {piece}"
+            );
+        }
+        for chunk_bytes in [1, 2, 5, 17] {
+            let opts = GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            };
+            let mut sink = VecSink::default();
+            GrepEngine::new_literal(&needle, opts)
+                .search_kmp(&mut &data[..], &mut sink)
+                .unwrap();
+            assert_eq!(sink.offs, expected, "chunk_bytes={chunk_bytes}");
+        }
+    }
+}
+
+#[test]
+fn matches_search_and_honors_limits() {
+    let data = b"xxneedlexneedleneedlexx".repeat(10);
+    for (max_matches, match_stride) in [(None, None), (Some(7), None), (None, Some(3))] {
+        let opts = GrepOptions {
+            chunk_bytes: 4,
+            max_matches,
+            match_stride,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_literal(b"needle", opts);
+        let mut expected = VecSink::default();
+        engine.search(&mut &data[..], &mut expected).unwrap();
+        let mut got = VecSink::default();
+        engine.search_kmp(&mut &data[..], &mut got).unwrap();
+        assert_eq!(got.offs, expected.offs, "{max_matches:?} {match_stride:?}");
+    }
+}
+
+#[test]
+fn stop_skips_the_rest_of_the_feed() {
+    let mut kmp = KmpSearcher::new(b"ab");
+    let mut seen = Vec::new();
+    let control = kmp.feed(b"ab ab ab", |off| {
+        seen.push(off);
+        if seen.len() == 2 {
+            Control::Stop
+        } else {
+            Control::Continue
+        }
+    });
+    assert_eq!(control, Control::Stop);
+    assert_eq!(seen, vec![0, 3]);
+    assert_eq!(kmp.position(), 8);
+}
+
+#[test]
+fn every_byte_is_read_once() {
+    struct Bytes(u64);
+    impl MatchSink for Bytes {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_match_info(&mut self, m: &Match) {
+            self.0 = m.bytes_read;
+        }
+    }
+    let mut data = vec![b'.'; 10_000];
+    data[9_990..9_996].copy_from_slice(b"needle");
+    let opts = GrepOptions {
+        chunk_bytes: 7,
+        ..Default::default()
+    };
+    let mut sink = Bytes(0);
+    GrepEngine::new_literal(b"needle", opts)
+        .search_kmp(&mut &data[..], &mut sink)
+        .unwrap();
+    // Chunks are consecutive: the one holding the match ends at a multiple of 7.
+    assert_eq!(sink.0, 9_996_u64.div_ceil(7) * 7);
+}
+
+#[test]
+fn rejects_what_needs_overlapping_chunks() {
+    let regex = GrepEngine::new_regex("a+", GrepOptions::default()).unwrap();
+    let lines = GrepEngine::new_literal(
+        b"a",
+        GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER),
+    );
+    for engine in [regex, lines] {
+        let err = engine
+            .search_kmp(&mut &b"aaa"[..], &mut VecSink::default())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,165 @@
+use simd_grep::count_lines;
+use simd_grep::engine::{ByteRange, ColumnUnit, GrepEngine, GrepFlags, GrepOptions};
+use simd_grep::io::chunker::UTF8_BOM;
+use simd_grep::lines::LineIndex;
+
+const TEXT: &[u8] = b"fn main() {\n    let x = 1;\n\n    let y = x;\n}";
+
+#[test]
+fn index_agrees_with_reported_matches() {
+    let opts = GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER);
+    let (matches, index) = GrepEngine::new_literal(b"let", opts)
+        .search_all_in_memory(TEXT)
+        .unwrap();
+    assert_eq!(matches.len(), 2);
+    for m in &matches {
+        assert_eq!(index.line_col(m.match_off), Some((m.line_no, m.col)));
+    }
+    assert_eq!(
+        matches
+            .iter()
+            .map(|m| (m.line_no, m.col))
+            .collect::<Vec<_>>(),
+        vec![(2, 5), (4, 5)]
+    );
+}
+
+#[test]
+fn offsets_between_matches() {
+    let (matches, index) = GrepEngine::new_literal(b"let", GrepOptions::default())
+        .search_all_in_memory(TEXT)
+        .unwrap();
+    assert_eq!(
+        matches.iter().map(|m| m.off).collect::<Vec<_>>(),
+        vec![16, 32]
+    );
+    assert_eq!(index.line_count(), 5);
+    assert_eq!(index.line_col(0), Some((1, 1)));
+    assert_eq!(index.line_col(11), Some((1, 12)));
+    assert_eq!(index.line_col(12), Some((2, 1)));
+    assert_eq!(index.line_col(27), Some((3, 1)));
+    assert_eq!(index.line_col(40), Some((4, 13)));
+    assert_eq!(index.line_col(TEXT.len() as u64), Some((5, 2)));
+    assert_eq!(index.line_col(TEXT.len() as u64 + 1), None);
+    assert_eq!(index.line_start(3), Some(27));
+    assert_eq!(index.line_start(0), None);
+    assert_eq!(index.line_start(6), None);
+}
+
+#[test]
+fn edge_buffers() {
+    let empty = LineIndex::new(b"");
+    assert_eq!(empty.line_count(), 0);
+    assert_eq!(empty.line_col(0), Some((1, 1)));
+
+    let trailing = LineIndex::new(b"a\n");
+    assert_eq!(trailing.line_count(), 1);
+    assert_eq!(trailing.line_col(1), Some((1, 2)));
+    assert_eq!(trailing.line_col(2), Some((2, 1)));
+    assert_eq!(trailing.line_start(2), Some(2));
+}
+
+#[test]
+fn line_count_matches_count_lines() {
+    for data in [&b""[..], b"\n", b"a", b"a\n", b"a\nb", b"a\n\n", b"\n\nb\n"] {
+        assert_eq!(
+            LineIndex::new(data).line_count(),
+            count_lines(data),
+            "{data:?}"
+        );
+        let (_, index) = GrepEngine::new_literal(b"a", GrepOptions::default())
+            .search_all_in_memory(data)
+            .unwrap();
+        assert_eq!(index.line_count(), count_lines(data), "{data:?}");
+    }
+}
+
+#[test]
+fn columns_count_bytes_under_any_unit() {
+    let opts = GrepOptions {
+        flags: GrepFlags::LINE_NUMBER,
+        column_unit: ColumnUnit::Char,
+        ..Default::default()
+    };
+    let (matches, index) = GrepEngine::new_literal(b"x", opts)
+        .search_all_in_memory("é\nñx".as_bytes())
+        .unwrap();
+    assert_eq!((matches[0].line_no, matches[0].col), (2, 2));
+    assert_eq!(index.line_col(matches[0].match_off), Some((2, 3)));
+}
+
+#[test]
+fn early_stops_still_index_every_line() {
+    let opts = GrepOptions {
+        flags: GrepFlags::LINE_NUMBER,
+        max_matches: Some(1),
+        record_separator: Some(b"--".to_vec()),
+        ..Default::default()
+    };
+    let (matches, index) = GrepEngine::new_literal(b"a", opts)
+        .search_all_in_memory(b"a--a---b--a")
+        .unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_start(3), Some(6));
+    assert_eq!(index.line_col(10), Some((4, 1)));
+}
+
+#[test]
+fn index_follows_the_searched_window_and_separator() {
+    let bom = [UTF8_BOM, &b"a\nx a\nb a"[..]].concat();
+    let cases = [
+        GrepOptions {
+            strip_bom: true,
+            ..Default::default()
+        },
+        GrepOptions {
+            record_separator: Some(b"\0".to_vec()),
+            ..Default::default()
+        },
+        GrepOptions {
+            record_separator: Some(b"\n---\n".to_vec()),
+            ..Default::default()
+        },
+        GrepOptions {
+            byte_range: Some(ByteRange::Span(4, 14)),
+            ..Default::default()
+        },
+    ];
+    let data: [&[u8]; 4] = [
+        &bom,
+        b"a\0x a\nb\0a",
+        b"a\n---\nx a\n---\nab",
+        b"a\nx a\nb a\nyy a",
+    ];
+    for (opts, data) in cases.into_iter().zip(data) {
+        let opts = opts.with_flags(GrepFlags::LINE_NUMBER);
+        let (matches, index) = GrepEngine::new_literal(b"a", opts.clone())
+            .search_all_in_memory(data)
+            .unwrap();
+        assert!(matches.len() >= 2, "{opts:?}");
+        for m in &matches {
+            assert_eq!(
+                index.line_col(m.match_off),
+                Some((m.line_no, m.col)),
+                "{opts:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn offsets_outside_the_window_are_not_indexed() {
+    let opts = GrepOptions {
+        byte_range: Some(ByteRange::Span(2, 6)),
+        ..Default::default()
+    };
+    let (_, index) = GrepEngine::new_literal(b"a", opts)
+        .search_all_in_memory(b"a\nx a\nb a")
+        .unwrap();
+    assert_eq!(index.line_col(1), None);
+    assert_eq!(index.line_col(2), Some((1, 1)));
+    assert_eq!(index.line_col(6), Some((2, 1)));
+    assert_eq!(index.line_col(7), None);
+    assert_eq!(index.line_start(1), Some(2));
+}
@@ -0,0 +1,64 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, VecSink};
+
+#[derive(Default)]
+struct Collect(Vec<Match>);
+
+impl MatchSink for Collect {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.0.push(*m);
+    }
+}
+
+// Lines start at 0, 8 and 20.
+const DATA: &[u8] = b"abc abc\nxxxxxxxxabc\nabc";
+
+fn run(flags: GrepFlags) -> Vec<Match> {
+    let opts = GrepOptions {
+        chunk_bytes: 6,
+        flags,
+        ..Default::default()
+    };
+    let mut sink = Collect::default();
+    GrepEngine::new_literal(b"abc", opts)
+        .search(&mut &DATA[..], &mut sink)
+        .unwrap();
+    sink.0
+}
+
+#[test]
+fn off_is_line_start_and_match_off_is_match_start() {
+    let got = run(GrepFlags::LINE_NUMBER | GrepFlags::REPORT_LINE_OFFSET);
+    let offs: Vec<_> = got.iter().map(|m| m.off).collect();
+    let match_offs: Vec<_> = got.iter().map(|m| m.match_off).collect();
+    let cols: Vec<_> = got.iter().map(|m| m.col).collect();
+    assert_eq!(offs, vec![0, 0, 8, 20]);
+    assert_eq!(match_offs, vec![0, 4, 16, 20]);
+    assert_eq!(cols, vec![1, 5, 9, 1]);
+}
+
+#[test]
+fn on_match_receives_line_start() {
+    let opts = GrepOptions {
+        flags: GrepFlags::LINE_NUMBER | GrepFlags::REPORT_LINE_OFFSET,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"abc", opts)
+        .search(&mut &DATA[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![0, 0, 8, 20]);
+}
+
+#[test]
+fn ignored_without_line_awareness() {
+    let got = run(GrepFlags::REPORT_LINE_OFFSET);
+    for m in &got {
+        assert_eq!(m.off, m.match_off);
+    }
+    assert_eq!(
+        got.iter().map(|m| m.off).collect::<Vec<_>>(),
+        vec![0, 4, 16, 20]
+    );
+}
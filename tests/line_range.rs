@@ -0,0 +1,66 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+
+/// Ten lines, each holding one "hit".
+fn ten_lines() -> Vec<u8> {
+    (1..=10)
+        .flat_map(|n| format!("{n:02} hit\n").into_bytes())
+        .collect()
+}
+
+fn opts(chunk_bytes: usize, line_range: Option<(u32, u32)>) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes,
+        flags: GrepFlags::LINE_NUMBER,
+        line_range,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn only_lines_in_range_are_reported() {
+    let data = ten_lines();
+    for chunk_bytes in [4, 7, 16, 1 << 20] {
+        let engine = GrepEngine::new_literal(b"hit", opts(chunk_bytes, Some((3, 5))));
+        let mut rest = &data[..];
+        let mut streamed = VecSink::default();
+        engine.search(&mut rest, &mut streamed).unwrap();
+        assert_eq!(
+            streamed.line_nos,
+            vec![3, 4, 5],
+            "chunk_bytes={chunk_bytes}"
+        );
+        assert_eq!(streamed.offs, vec![17, 24, 31]);
+        if chunk_bytes < 16 {
+            // Lines 6..=10 start at byte 35; the search stopped well before EOF.
+            assert!(
+                !rest.is_empty(),
+                "chunk_bytes={chunk_bytes} read everything"
+            );
+        }
+
+        let mut sliced = VecSink::default();
+        engine.search_slice(&data, &mut sliced).unwrap();
+        assert_eq!(sliced.line_nos, streamed.line_nos);
+        assert_eq!(sliced.offs, streamed.offs);
+    }
+}
+
+#[test]
+fn several_matches_on_a_line_and_single_line_ranges() {
+    let data = b"hit\nhit hit\nhit\n";
+    let engine = GrepEngine::new_literal(b"hit", opts(5, Some((2, 2))));
+    let mut sink = VecSink::default();
+    engine.search(&mut &data[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![4, 8]);
+    assert_eq!(sink.line_nos, vec![2, 2]);
+}
+
+#[test]
+fn empty_ranges_are_rejected() {
+    for range in [(0, 4), (5, 3)] {
+        let err = opts(1024, Some(range)).validate(3).unwrap_err();
+        assert!(matches!(err, GrepError::Options(_)), "{range:?}");
+    }
+    assert!(opts(1024, Some((1, 1))).validate(3).is_ok());
+}
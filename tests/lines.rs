@@ -0,0 +1,67 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::lines::count_newlines;
+
+fn naive_count(haystack: &[u8]) -> usize {
+    haystack.iter().filter(|&&b| b == b'\n').count()
+}
+
+#[test]
+fn matches_scalar_count_on_edge_buffers() {
+    let cases: [&[u8]; 7] = [
+        b"",
+        b"no newline at all",
+        b"\n",
+        b"\n\n\n",
+        b"trailing\n",
+        b"a\nb\nc",
+        b"\nleading and trailing\n",
+    ];
+    for buf in cases {
+        assert_eq!(count_newlines(buf), naive_count(buf), "{buf:?}");
+    }
+}
+
+#[test]
+fn matches_scalar_count_on_random_buffers() {
+    let mut rng = StdRng::seed_from_u64(0x11E5);
+    for len in [1, 15, 16, 31, 32, 33, 63, 64, 65, 1000, 100_000] {
+        let buf: Vec<u8> = (0..len)
+            .map(|_| {
+                if rng.gen_ratio(1, 8) {
+                    b'\n'
+                } else {
+                    rng.r#gen()
+                }
+            })
+            .collect();
+        assert_eq!(count_newlines(&buf), naive_count(&buf), "len={len}");
+    }
+}
+
+#[test]
+fn crlf_split_across_chunks_keeps_line_numbers() {
+    use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+    let text = "ab\r\nxx ab\r\n\r\nab ab\r\nnone\r\nab";
+    let data = text.as_bytes();
+    // Reference: line number of every "ab" from an in-memory split.
+    let mut want = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        want.extend(line.match_indices("ab").map(|_| i as u32 + 1));
+    }
+
+    // Every chunk size puts a boundary between some "\r" and its "\n".
+    for chunk_bytes in 2..data.len() + 1 {
+        let opts = GrepOptions {
+            chunk_bytes,
+            flags: GrepFlags::LINE_NUMBER,
+            ..Default::default()
+        };
+        let mut sink = VecSink::default();
+        GrepEngine::new_literal(b"ab", opts)
+            .search(&mut &data[..], &mut sink)
+            .unwrap();
+        assert_eq!(sink.line_nos, want, "chunk_bytes {}", chunk_bytes);
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions};
+use simd_grep::io::output::{ColorChoice, FormatSink, TtySink};
+
+const DATA: &[u8] = b"ok\nbad \xff\xfe key \xe2\x82\n";
+
+fn engine() -> GrepEngine<'static> {
+    let opts = GrepOptions {
+        chunk_bytes: 5,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    GrepEngine::new_literal(b"key", opts)
+}
+
+fn tty(lossy: bool, color: ColorChoice) -> Vec<u8> {
+    let mut sink = TtySink::new(Vec::new(), vec![PathBuf::from("f")], color, false).lossy(lossy);
+    engine().search(&mut &DATA[..], &mut sink).unwrap();
+    sink.finish().unwrap()
+}
+
+fn format(lossy: bool) -> Vec<u8> {
+    let mut sink = FormatSink::new(Vec::new(), "{line}:{text}\n", Vec::new())
+        .unwrap()
+        .lossy(lossy);
+    engine().search(&mut &DATA[..], &mut sink).unwrap();
+    sink.finish().unwrap()
+}
+
+#[test]
+fn lossy_lines_replace_invalid_utf8() {
+    assert_eq!(
+        tty(true, ColorChoice::Never),
+        "f:2:bad \u{fffd}\u{fffd} key \u{fffd}\n".as_bytes()
+    );
+    assert_eq!(
+        format(true),
+        "2:bad \u{fffd}\u{fffd} key \u{fffd}\n".as_bytes()
+    );
+    // Highlighting still lands on the match.
+    let colored = String::from_utf8(tty(true, ColorChoice::Always)).unwrap();
+    assert!(colored.ends_with("bad \u{fffd}\u{fffd} \x1b[1;31mkey\x1b[0m \u{fffd}\n"));
+}
+
+#[test]
+fn raw_lines_are_written_verbatim() {
+    assert_eq!(
+        tty(false, ColorChoice::Never),
+        b"f:2:bad \xff\xfe key \xe2\x82\n"
+    );
+    assert_eq!(format(false), b"2:bad \xff\xfe key \xe2\x82\n");
+    // Raw is the default.
+    let mut sink = FormatSink::new(Vec::new(), "{text}", Vec::new()).unwrap();
+    engine().search(&mut &DATA[..], &mut sink).unwrap();
+    assert_eq!(sink.finish().unwrap(), b"bad \xff\xfe key \xe2\x82");
+}
@@ -0,0 +1,39 @@
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::testutil::ChunkedReader;
+use simd_grep::io::transform::MapSource;
+
+const KEY: &[u8] = b"k3y!";
+
+/// XORs `buf` with `KEY`, starting `*pos` bytes into the key stream.
+fn xor(buf: &mut [u8], pos: &mut usize) {
+    for b in buf {
+        *b ^= KEY[*pos % KEY.len()];
+        *pos += 1;
+    }
+}
+
+#[test]
+fn xor_obfuscated_input_is_searched_as_plaintext() {
+    let mut plain = b"header bytes ".repeat(50);
+    let at = plain.len() - 17;
+    plain[at..at + 6].copy_from_slice(b"secret");
+    let mut cipher = plain.clone();
+    xor(&mut cipher, &mut 0);
+    assert!(memchr::memmem::find(&cipher, b"secret").is_none());
+
+    let opts = GrepOptions {
+        chunk_bytes: 16,
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"secret", opts);
+    for per_read in [1, 3, 7, 4096] {
+        let mut pos = 0;
+        let mut source = MapSource::new(
+            ChunkedReader::new(&cipher[..], per_read),
+            |buf: &mut [u8]| xor(buf, &mut pos),
+        );
+        let mut sink = VecSink::default();
+        engine.search(&mut source, &mut sink).unwrap();
+        assert_eq!(sink.offs, vec![at as u64], "per_read={per_read}");
+    }
+}
@@ -0,0 +1,38 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match};
+use simd_grep::io::output::DebugSink;
+
+#[test]
+fn display_names_file_line_offset_and_len() {
+    let m = Match {
+        off: 42,
+        match_off: 42,
+        len: 6,
+        line_no: 3,
+        col: 5,
+        file_id: 0,
+        ..Match::default()
+    };
+    assert_eq!(m.to_string(), "file 0, line 3, offset 42, len 6");
+    assert_eq!(
+        format!("{}", Match { file_id: 7, ..m }),
+        "file 7, line 3, offset 42, len 6"
+    );
+}
+
+#[test]
+fn debug_sink_prints_one_line_per_match() {
+    let opts = GrepOptions::default().with_flags(GrepFlags::line_numbers());
+    let mut sink = DebugSink::with_writer(Vec::new());
+    GrepEngine::new_literal(b"ab", opts)
+        .search(&mut &b"ab\nx ab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(sink.into_inner()).unwrap(),
+        "file 0, line 1, offset 0, len 2\nfile 0, line 2, offset 5, len 2\n"
+    );
+
+    // The default writer is stderr.
+    GrepEngine::new_literal(b"ab", GrepOptions::default())
+        .search(&mut &b"ab"[..], &mut DebugSink::new())
+        .unwrap();
+}
@@ -0,0 +1,150 @@
+use simd_grep::engine::{CountSink, GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, VecSink};
+
+fn search(data: &[u8], needle: &[u8], opts: GrepOptions) -> VecSink {
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(needle, opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    sink
+}
+
+fn dense_data() -> Vec<u8> {
+    let mut data = vec![b'a'; 100];
+    data.extend_from_slice(b"\nxaxax\n");
+    data
+}
+
+#[test]
+fn per_line_cap_limits_each_line() {
+    for chunk_bytes in [3, 16, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            flags: GrepFlags::LINE_NUMBER,
+            max_matches_per_line: Some(3),
+            ..Default::default()
+        };
+        let sink = search(&dense_data(), b"a", opts);
+        assert_eq!(
+            sink.offs,
+            vec![0, 1, 2, 102, 104],
+            "chunk_bytes={chunk_bytes}"
+        );
+        assert_eq!(sink.line_nos, vec![1, 1, 1, 2, 2]);
+    }
+}
+
+#[test]
+fn zero_per_line_cap_reports_nothing() {
+    let opts = GrepOptions {
+        flags: GrepFlags::LINE_NUMBER,
+        max_matches_per_line: Some(0),
+        ..Default::default()
+    };
+    assert!(search(&dense_data(), b"a", opts).offs.is_empty());
+}
+
+#[test]
+fn per_line_cap_is_ignored_without_line_awareness() {
+    let opts = GrepOptions {
+        max_matches_per_line: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(search(&dense_data(), b"a", opts).offs.len(), 102);
+}
+
+#[test]
+fn global_limit_applies_on_top_of_per_line_cap() {
+    let opts = GrepOptions {
+        flags: GrepFlags::LINE_NUMBER,
+        max_matches: Some(4),
+        max_matches_per_line: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(search(&dense_data(), b"a", opts).offs, vec![0, 1, 2, 102]);
+}
+
+#[test]
+fn global_limit_without_line_awareness() {
+    let opts = GrepOptions {
+        chunk_bytes: 4,
+        max_matches: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(search(b"ab ab ab", b"ab", opts.clone()).offs, vec![0, 3]);
+
+    let none = GrepOptions {
+        max_matches: Some(0),
+        ..opts
+    };
+    assert!(search(b"ab ab ab", b"ab", none).offs.is_empty());
+}
+
+#[test]
+fn count_lines_counts_each_matching_line_once() {
+    let data = b"needle needle needle\nnothing\nneedle\n";
+    let count = |flags| {
+        let mut sink = CountSink::default();
+        let opts = GrepOptions {
+            chunk_bytes: 8,
+            flags,
+            ..Default::default()
+        };
+        GrepEngine::new_literal(b"needle", opts)
+            .search(&mut &data[..], &mut sink)
+            .unwrap();
+        sink.total
+    };
+    let lines = GrepFlags::LINE_NUMBER;
+    assert_eq!(count(lines | GrepFlags::COUNT_ONLY), 4);
+    assert_eq!(count(lines | GrepFlags::COUNT_LINES), 2);
+    // Only the first line, which holds three needles, counted once.
+    let first_line = GrepOptions {
+        flags: lines | GrepFlags::COUNT_LINES,
+        ..Default::default()
+    };
+    let sink = search(&data[..20], b"needle", first_line);
+    assert_eq!(sink.offs, vec![0]);
+    assert_eq!(
+        GrepFlags::COUNT_LINES.conflict(),
+        Some("COUNT_LINES requires LINE_NUMBER")
+    );
+}
+
+#[test]
+fn unique_lines_reports_each_matching_line_once() {
+    #[derive(Default)]
+    struct Lines(Vec<(u32, Vec<u8>)>);
+    impl MatchSink for Lines {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {
+            panic!("line-aware searches report through on_match_line");
+        }
+        fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+            self.0.push((m.line_no, line.to_vec()));
+        }
+    }
+    let run = |data: &[u8]| {
+        let opts = GrepOptions {
+            chunk_bytes: 8,
+            flags: GrepFlags::LINE_NUMBER | GrepFlags::UNIQUE_LINES,
+            ..Default::default()
+        };
+        let mut sink = Lines::default();
+        GrepEngine::new_literal(b"ab", opts)
+            .search(&mut &data[..], &mut sink)
+            .unwrap();
+        sink.0
+    };
+    assert_eq!(run(b"xab ab"), vec![(1, b"xab ab".to_vec())]);
+    assert_eq!(
+        run(b"ab ab ab\nnone\nabab\n\nx ab"),
+        vec![
+            (1, b"ab ab ab".to_vec()),
+            (3, b"abab".to_vec()),
+            (5, b"x ab".to_vec())
+        ]
+    );
+    assert_eq!(
+        GrepFlags::UNIQUE_LINES.conflict(),
+        Some("UNIQUE_LINES requires LINE_NUMBER")
+    );
+}
@@ -0,0 +1,41 @@
+use simd_grep::engine::{GrepEngine, GrepOptions, Match, MatchRegion, MatchSink};
+
+#[derive(Default)]
+struct Regions(Vec<(u64, MatchRegion)>);
+
+impl MatchSink for Regions {
+    fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+
+    fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+        self.0.push((m.off, region));
+    }
+}
+
+const DATA: &[u8] = b"xxneedlexxxxxneedlexxxxx";
+
+fn regions(debug_stitch: bool) -> Vec<(u64, MatchRegion)> {
+    let opts = GrepOptions {
+        chunk_bytes: 16,
+        debug_stitch,
+        ..Default::default()
+    };
+    let mut sink = Regions::default();
+    GrepEngine::new_literal(b"needle", opts)
+        .search(&mut &DATA[..], &mut sink)
+        .unwrap();
+    sink.0
+}
+
+#[test]
+fn boundary_match_is_found_in_the_stitch() {
+    // The second needle straddles the end of the first 16-byte chunk.
+    assert_eq!(
+        regions(true),
+        vec![(2, MatchRegion::Fresh), (13, MatchRegion::Stitch)]
+    );
+}
+
+#[test]
+fn regions_are_only_reported_on_request() {
+    assert!(regions(false).is_empty());
+}
@@ -0,0 +1,40 @@
+use simd_grep::engine::{ByteRange, GrepEngine, GrepOptions, VecSink};
+
+// "REC" at offsets 4, 7 and 12.
+const DATA: &[u8] = b"....RECREC..REC....";
+
+fn offs(opts: GrepOptions) -> Vec<u64> {
+    let engine = GrepEngine::new_literal(b"REC", opts);
+    let mut streamed = VecSink::default();
+    engine.search(&mut &DATA[..], &mut streamed).unwrap();
+    let mut sliced = VecSink::default();
+    engine.search_slice(DATA, &mut sliced).unwrap();
+    assert_eq!(streamed.offs, sliced.offs);
+    streamed.offs
+}
+
+#[test]
+fn stride_keeps_only_aligned_matches() {
+    assert_eq!(offs(GrepOptions::default()), vec![4, 7, 12]);
+    for chunk_bytes in [3, 5, 8, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            match_stride: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(offs(opts), vec![4, 12], "chunk_bytes={chunk_bytes}");
+    }
+}
+
+#[test]
+fn stride_uses_stream_offsets_within_a_range() {
+    let opts = GrepOptions {
+        byte_range: Some(ByteRange::Span(5, 19)),
+        match_stride: Some(4),
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"REC", opts);
+    let mut sink = VecSink::default();
+    engine.search_slice(DATA, &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![12]);
+}
@@ -0,0 +1,235 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{
+    GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, PerPatternCountSink, VecSink,
+};
+use simd_grep::error::GrepError;
+
+fn small_chunks(chunk_bytes: usize) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes,
+        ..Default::default()
+    }
+}
+
+fn offs_and_lens(engine: &GrepEngine<'_>, data: &[u8]) -> Vec<(u64, u32)> {
+    let mut sink = VecSink::default();
+    engine.search(&mut &data[..], &mut sink).unwrap();
+    sink.offs.into_iter().zip(sink.lens).collect()
+}
+
+#[test]
+fn regex_matches_across_chunk_boundaries() {
+    let data = b"id=12 x id=3456789 y id=7";
+    let engine = GrepEngine::new_regex(r"id=[0-9]+", small_chunks(6)).unwrap();
+    assert_eq!(offs_and_lens(&engine, data), vec![(0, 5), (8, 10), (21, 4)]);
+
+    let mut whole = VecSink::default();
+    engine.search_slice(data, &mut whole).unwrap();
+    assert_eq!(whole.offs, vec![0, 8, 21]);
+}
+
+#[test]
+fn streamed_regex_matches_equal_a_single_pass() {
+    // A run longer than a chunk: the search must resume where the last match
+    // before the cut ended, not realign at the next chunk's start.
+    let run = vec![b'a'; 20001];
+    for pattern in ["aa", "aaa"] {
+        let engine = GrepEngine::new_regex(pattern, small_chunks(8192)).unwrap();
+        let mut whole = VecSink::default();
+        engine.search_slice(&run, &mut whole).unwrap();
+        assert_eq!(whole.offs.len(), 20001 / pattern.len());
+        let streamed = offs_and_lens(&engine, &run);
+        let streamed: Vec<u64> = streamed.into_iter().map(|(off, _)| off).collect();
+        assert_eq!(streamed, whole.offs, "{pattern}");
+    }
+}
+
+#[test]
+fn regex_anchors_see_the_bytes_before_a_chunk() {
+    // "foo" right at offset 8192, where a chunk starts with no overlap.
+    let mut data = vec![b'x'; 8192];
+    data.extend_from_slice(b"foo ");
+    for pattern in [r"^foo", r"\bfoo", r"\Afoo"] {
+        for overlap in [Some(0), None] {
+            let opts = GrepOptions {
+                overlap,
+                ..small_chunks(8192)
+            };
+            let engine = GrepEngine::new_regex(pattern, opts).unwrap();
+            assert_eq!(
+                offs_and_lens(&engine, &data),
+                vec![],
+                "{pattern} {overlap:?}"
+            );
+        }
+    }
+    data[8191] = b' ';
+    let engine = GrepEngine::new_regex(
+        r"\bfoo",
+        GrepOptions {
+            overlap: Some(0),
+            ..small_chunks(8192)
+        },
+    )
+    .unwrap();
+    assert_eq!(offs_and_lens(&engine, &data), vec![(8192, 3)]);
+}
+
+#[test]
+fn regex_reports_lines_and_columns() {
+    let data = b"alpha\nbeta 42\n7 gamma\n";
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_regex(r"\d+", opts)
+        .unwrap()
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![11, 14]);
+    assert_eq!(sink.line_nos, vec![2, 3]);
+    assert_eq!(sink.cols, vec![6, 1]);
+}
+
+#[test]
+fn invalid_regex_is_reported() {
+    let err = GrepEngine::new_regex("(unclosed", GrepOptions::default()).err();
+    assert!(matches!(err, Some(GrepError::Regex(_))));
+}
+
+#[test]
+fn multi_reports_every_needle() {
+    let data = b"cat dog bird cat";
+    let engine = GrepEngine::new_multi(&["dog", "cat", "bird"], small_chunks(5));
+    assert_eq!(
+        offs_and_lens(&engine, data),
+        vec![(0, 3), (4, 3), (8, 4), (13, 3)]
+    );
+}
+
+#[test]
+fn multi_prefers_the_first_listed_needle_at_an_offset() {
+    let engine = GrepEngine::new_multi(&["ab", "abc"], GrepOptions::default());
+    assert_eq!(offs_and_lens(&engine, b"xabc"), vec![(1, 2)]);
+    let engine = GrepEngine::new_multi(&["abc", "ab"], GrepOptions::default());
+    assert_eq!(offs_and_lens(&engine, b"xabc"), vec![(1, 3)]);
+}
+
+#[test]
+fn multi_agrees_with_single_literals() {
+    let mut rng = StdRng::seed_from_u64(0x3481);
+    for _ in 0..100 {
+        let data: Vec<u8> = (0..rng.gen_range(0..200))
+            .map(|_| b"ab"[rng.gen_range(0..2)])
+            .collect();
+        let needles: Vec<Vec<u8>> = (0..3)
+            .map(|_| {
+                (0..rng.gen_range(1..5))
+                    .map(|_| b"ab"[rng.gen_range(0..2)])
+                    .collect()
+            })
+            .collect();
+        let opts = small_chunks(rng.gen_range(8..40));
+
+        // Every offset where some needle starts, reported once.
+        let mut expected: Vec<u64> = needles
+            .iter()
+            .flat_map(|n| {
+                offs_and_lens(&GrepEngine::new_literal(n, opts.clone()), &data)
+                    .into_iter()
+                    .map(|(off, _)| off)
+            })
+            .collect();
+        expected.sort_unstable();
+        expected.dedup();
+
+        let got: Vec<u64> = offs_and_lens(&GrepEngine::new_multi(&needles, opts), &data)
+            .into_iter()
+            .map(|(off, _)| off)
+            .collect();
+        assert_eq!(got, expected, "needles {:?}", needles);
+    }
+}
+
+#[test]
+fn owned_engine_keeps_the_backend() {
+    let engine = GrepEngine::new_regex(r"b+", small_chunks(4)).unwrap();
+    let owned = engine.into_owned_engine();
+    let mut sink = VecSink::default();
+    owned.search(&mut &b"abbbabb"[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![1, 5]);
+}
+
+#[test]
+fn per_pattern_counts() {
+    let data = b"foo bar baz foo qux bar foo\nfoobar bazbaz";
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        ..Default::default()
+    };
+    let mut sink = PerPatternCountSink::new(3);
+    GrepEngine::new_multi(&["foo", "bar", "baz"], opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.counts, vec![4, 3, 3]);
+
+    let mut patterns = Vec::new();
+    struct Ids<'a>(&'a mut Vec<u32>);
+    impl MatchSink for Ids<'_> {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_match_info(&mut self, m: &Match) {
+            self.0.push(m.pattern);
+        }
+    }
+    let engine = GrepEngine::new_multi(&["b", "a"], GrepOptions::default());
+    engine
+        .search_slice(b"abba", &mut Ids(&mut patterns))
+        .unwrap();
+    assert_eq!(patterns, vec![1, 0, 0, 1]);
+}
+
+/// `(off, len, pattern)` of every report, from a stream and from a slice.
+fn reports(engine: &GrepEngine<'_>, data: &[u8]) -> Vec<(u64, u32, u32)> {
+    #[derive(Default)]
+    struct Reports(Vec<(u64, u32, u32)>);
+    impl MatchSink for Reports {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_match_info(&mut self, m: &Match) {
+            self.0.push((m.off, m.len, m.pattern));
+        }
+    }
+    let mut streamed = Reports::default();
+    engine.search(&mut &data[..], &mut streamed).unwrap();
+    let mut sliced = Reports::default();
+    engine.search_slice(data, &mut sliced).unwrap();
+    assert_eq!(streamed.0, sliced.0);
+    streamed.0
+}
+
+#[test]
+fn multi_all_reports_ties_in_pattern_order() {
+    let data = b"xfoobar foo";
+    for chunk_bytes in [6, 7, 9, 1 << 20] {
+        let engine =
+            GrepEngine::new_multi_all(&["foobar", "bar", "foo"], small_chunks(chunk_bytes));
+        assert_eq!(
+            reports(&engine, data),
+            vec![(1, 6, 0), (1, 3, 2), (4, 3, 1), (8, 3, 2)],
+            "chunk_bytes={chunk_bytes}"
+        );
+        let engine = GrepEngine::new_multi_all(&["foo", "foobar"], small_chunks(chunk_bytes));
+        assert_eq!(
+            reports(&engine, data),
+            vec![(1, 3, 0), (1, 6, 1), (8, 3, 0)]
+        );
+    }
+    // Duplicates and single bytes at the very end are reported too.
+    let engine = GrepEngine::new_multi_all(&["a", "ba", "a"], GrepOptions::default());
+    assert_eq!(
+        reports(&engine, b"ba"),
+        vec![(0, 2, 1), (1, 1, 0), (1, 1, 2)]
+    );
+}
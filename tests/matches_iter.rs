@@ -0,0 +1,96 @@
+use std::io::{self, Read};
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink};
+
+/// Collects full matches, like the iterator yields them.
+#[derive(Default)]
+struct Full(Vec<Match>);
+
+impl MatchSink for Full {
+    fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+
+    fn on_match_info(&mut self, m: &Match) {
+        self.0.push(*m);
+    }
+}
+
+/// Counts the bytes handed out.
+struct Counting<'a> {
+    data: &'a [u8],
+    read: usize,
+}
+
+impl Read for Counting<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.data.read(buf)?;
+        self.read += n;
+        Ok(n)
+    }
+}
+
+#[test]
+fn yields_what_search_reports() {
+    let data = b"ab\nxab ab\nabab\n\nab".repeat(20);
+    for chunk_bytes in [3, 7, 64, 1 << 20] {
+        for flags in [GrepFlags::empty(), GrepFlags::line_offsets()] {
+            let opts = GrepOptions {
+                chunk_bytes,
+                flags,
+                ..Default::default()
+            };
+            let engine = GrepEngine::new_literal(b"ab", opts);
+            let mut sink = Full::default();
+            engine.search(&mut &data[..], &mut sink).unwrap();
+            let yielded: Vec<Match> = engine
+                .matches(&mut &data[..])
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(yielded, sink.0, "chunk_bytes={chunk_bytes} {flags:?}");
+        }
+    }
+}
+
+#[test]
+fn reads_only_as_far_as_needed() {
+    let mut data = vec![b'x'; 1 << 16];
+    data[10..16].copy_from_slice(b"needle");
+    data[60_000..60_006].copy_from_slice(b"needle");
+    let opts = GrepOptions {
+        chunk_bytes: 1024,
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"needle", opts);
+    let mut reader = Counting {
+        data: &data,
+        read: 0,
+    };
+    let mut iter = engine.matches(&mut reader);
+    assert_eq!(iter.next().unwrap().unwrap().off, 10);
+    drop(iter);
+    assert!(reader.read < 8192, "read {} bytes", reader.read);
+
+    let mut reader = Counting {
+        data: &data,
+        read: 0,
+    };
+    let offs: Vec<u64> = engine
+        .matches(&mut reader)
+        .map(|m| m.unwrap().off)
+        .collect();
+    assert_eq!(offs, vec![10, 60_000]);
+    assert_eq!(reader.read, data.len());
+}
+
+#[test]
+fn errors_are_yielded() {
+    let opts = GrepOptions {
+        overlap: Some(1),
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"needle", opts);
+    let mut reader: &[u8] = b"needle";
+    let mut iter = engine.matches(&mut reader);
+    let err = iter.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(iter.next().is_none());
+}
@@ -0,0 +1,45 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, TRUNCATION_MARKER};
+use simd_grep::io::output::FormatSink;
+
+fn render(data: &[u8], needle: &[u8], max_columns: Option<usize>) -> String {
+    let opts = GrepOptions {
+        chunk_bytes: 64,
+        flags: GrepFlags::LINE_NUMBER,
+        max_columns,
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(needle, opts);
+    let mut sink = FormatSink::new(Vec::new(), "{line}:{col}:{offset}:{text}\n", vec![]).unwrap();
+    engine.search(&mut &data[..], &mut sink).unwrap();
+    String::from_utf8(sink.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn long_line_is_truncated_but_match_is_intact() {
+    let mut line = vec![b'x'; 1000];
+    line[900..906].copy_from_slice(b"needle");
+    let mut data = b"short needle\n".to_vec();
+    data.extend_from_slice(&line);
+    data.push(b'\n');
+
+    let out = render(&data, b"needle", Some(20));
+    let marker = std::str::from_utf8(TRUNCATION_MARKER).unwrap();
+    let expected = format!(
+        "1:7:6:short needle\n2:901:913:{}{}\n",
+        "x".repeat(20),
+        marker
+    );
+    assert_eq!(out, expected);
+
+    let full = render(&data, b"needle", None);
+    assert!(full.contains(&String::from_utf8(line).unwrap()));
+}
+
+#[test]
+fn truncation_keeps_utf8_characters_whole() {
+    // "é" is two bytes; a cut after 5 bytes would fall inside the third one.
+    let data = "ééé needle\n".as_bytes();
+    let out = render(data, b"needle", Some(5));
+    let marker = std::str::from_utf8(TRUNCATION_MARKER).unwrap();
+    assert_eq!(out, format!("1:8:7:éé{}\n", marker));
+}
@@ -0,0 +1,163 @@
+use std::io;
+use std::path::PathBuf;
+
+use simd_grep::engine::{GrepOptions, PreparedPattern, VecSink};
+use simd_grep::error::GrepError;
+use simd_grep::io::pool::BufferPool;
+use simd_grep::io::testutil::MemFs;
+use simd_grep::io::walk::{
+    WalkOptions, collect_files_in, count_dir_in, files_with_matches_in, search_dir_in,
+};
+
+fn tree() -> MemFs {
+    MemFs::new()
+        .file("/r/b.txt", b"needle\nneedle\n")
+        .file("/r/a.txt", b"one needle")
+        .file("/r/sub/deeper/d.txt", b"needle")
+        .file("/r/sub/c.txt", b"nothing here")
+        .dir("/r/empty")
+}
+
+fn paths(list: &[&str]) -> Vec<PathBuf> {
+    list.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn walks_nested_tree_in_sorted_order() {
+    let files = collect_files_in(&tree(), "/r".as_ref(), &WalkOptions::default()).unwrap();
+    assert_eq!(
+        files,
+        paths(&[
+            "/r/a.txt",
+            "/r/b.txt",
+            "/r/sub/c.txt",
+            "/r/sub/deeper/d.txt"
+        ])
+    );
+
+    let shallow = WalkOptions {
+        max_depth: Some(0),
+        ..WalkOptions::default()
+    };
+    let files = collect_files_in(&tree(), "/r".as_ref(), &shallow).unwrap();
+    assert_eq!(files, paths(&["/r/a.txt", "/r/b.txt"]));
+}
+
+#[test]
+fn searches_files_from_memory() {
+    let pattern = PreparedPattern::new(b"needle");
+    let mut sink = VecSink::default();
+    let files = search_dir_in(
+        &tree(),
+        "/r".as_ref(),
+        &pattern,
+        &GrepOptions::default(),
+        &WalkOptions::default(),
+        &mut sink,
+        &BufferPool::new(),
+    )
+    .unwrap();
+    assert_eq!(files.len(), 4);
+    assert_eq!(sink.file_ids, vec![0, 1, 1, 3]);
+    assert_eq!(sink.offs, vec![4, 0, 7, 0]);
+}
+
+#[test]
+fn counts_and_lists_files_from_memory() {
+    let pattern = PreparedPattern::new(b"needle");
+    let (opts, walk) = (GrepOptions::default(), WalkOptions::default());
+    let summary = count_dir_in(&tree(), "/r".as_ref(), &pattern, &opts, &walk, false).unwrap();
+    assert_eq!(summary.total, 4);
+    assert_eq!(
+        summary.files,
+        vec![
+            (PathBuf::from("/r/a.txt"), 1),
+            (PathBuf::from("/r/b.txt"), 2),
+            (PathBuf::from("/r/sub/deeper/d.txt"), 1),
+        ]
+    );
+
+    let with = files_with_matches_in(&tree(), "/r".as_ref(), &pattern, &opts, &walk, false);
+    assert_eq!(
+        with.unwrap(),
+        paths(&["/r/a.txt", "/r/b.txt", "/r/sub/deeper/d.txt"])
+    );
+    let without = files_with_matches_in(&tree(), "/r".as_ref(), &pattern, &opts, &walk, true);
+    assert_eq!(without.unwrap(), paths(&["/r/sub/c.txt"]));
+}
+
+#[test]
+fn symlink_loop_is_walked_once() {
+    let fs = tree()
+        .symlink("/r/sub/back", "..")
+        .symlink("/r/self", "/r/self")
+        .symlink("/r/link.txt", "a.txt");
+
+    let plain = collect_files_in(&fs, "/r".as_ref(), &WalkOptions::default()).unwrap();
+    assert_eq!(
+        plain,
+        paths(&[
+            "/r/a.txt",
+            "/r/b.txt",
+            "/r/sub/c.txt",
+            "/r/sub/deeper/d.txt"
+        ])
+    );
+
+    let follow = WalkOptions {
+        follow_symlinks: true,
+        ..WalkOptions::default()
+    };
+    let err = collect_files_in(&fs, "/r".as_ref(), &follow).unwrap_err();
+    match err {
+        GrepError::Io(_, Some(path)) => assert_eq!(path, PathBuf::from("/r/self")),
+        other => panic!("unexpected error: {other:?}"),
+    }
+
+    // `sub` sorts before `zz`, so the real directory is the one listed.
+    let fs = tree()
+        .symlink("/r/sub/back", "..")
+        .symlink("/r/link.txt", "a.txt")
+        .symlink("/r/zz", "sub");
+    let files = collect_files_in(&fs, "/r".as_ref(), &follow).unwrap();
+    assert_eq!(
+        files,
+        paths(&[
+            "/r/a.txt",
+            "/r/b.txt",
+            "/r/link.txt",
+            "/r/sub/c.txt",
+            "/r/sub/deeper/d.txt",
+        ])
+    );
+}
+
+#[test]
+fn permission_denied_names_the_file() {
+    let fs = tree().deny("/r/sub/c.txt");
+    let pattern = PreparedPattern::new(b"needle");
+    let mut sink = VecSink::default();
+    let err = search_dir_in(
+        &fs,
+        "/r".as_ref(),
+        &pattern,
+        &GrepOptions::default(),
+        &WalkOptions::default(),
+        &mut sink,
+        &BufferPool::new(),
+    )
+    .unwrap_err();
+    match err {
+        GrepError::Io(e, Some(path)) => {
+            assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+            assert_eq!(path, PathBuf::from("/r/sub/c.txt"));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert_eq!(sink.file_ids, vec![0, 1, 1]);
+
+    let fs = tree().deny("/r/sub");
+    let err = collect_files_in(&fs, "/r".as_ref(), &WalkOptions::default()).unwrap_err();
+    assert!(matches!(err, GrepError::Io(ref e, Some(ref p))
+        if e.kind() == io::ErrorKind::PermissionDenied && p == &PathBuf::from("/r/sub")));
+}
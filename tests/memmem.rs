@@ -1,6 +1,6 @@
-use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
-use simd_grep::{find, contains};
+use rand::{Rng, SeedableRng};
+use simd_grep::{contains, ends_with, find, starts_with};
 
 #[test]
 fn empty_needle_is_zero() {
@@ -73,3 +73,22 @@ fn long_pattern_edge() {
     // First match should be at 0
     assert_eq!(find(&h, &n), Some(0));
 }
+
+#[test]
+fn anchored_prefix_and_suffix() {
+    assert!(starts_with(b"abc", b""));
+    assert!(ends_with(b"abc", b""));
+    assert!(starts_with(b"", b""));
+    assert!(ends_with(b"", b""));
+
+    assert!(!starts_with(b"ab", b"abc"));
+    assert!(!ends_with(b"bc", b"abc"));
+
+    assert!(starts_with(b"abc", b"abc"));
+    assert!(ends_with(b"abc", b"abc"));
+
+    assert!(starts_with(b"abcdef", b"abc"));
+    assert!(!starts_with(b"xabcdef", b"abc"));
+    assert!(ends_with(b"abcdef", b"def"));
+    assert!(!ends_with(b"abcdefx", b"def"));
+}
@@ -0,0 +1,61 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+// "ab" at 0, 3, 10 and 12.
+const DATA: &[u8] = b"ab.ab.....abab";
+
+fn offs(data: &[u8], min_gap: Option<u64>, flags: GrepFlags) -> Vec<u64> {
+    let mut results: Option<Vec<u64>> = None;
+    for chunk_bytes in [2, 3, 5, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            flags,
+            min_gap,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_literal(b"a", opts);
+        let mut streamed = VecSink::default();
+        engine.search(&mut &data[..], &mut streamed).unwrap();
+        let mut sliced = VecSink::default();
+        engine.search_slice(data, &mut sliced).unwrap();
+        assert_eq!(streamed.offs, sliced.offs, "chunk_bytes={chunk_bytes}");
+        if !flags.contains(GrepFlags::LINE_NUMBER) {
+            let mut kmp = VecSink::default();
+            engine.search_kmp(&mut &data[..], &mut kmp).unwrap();
+            assert_eq!(kmp.offs, sliced.offs, "chunk_bytes={chunk_bytes}");
+        }
+        match &results {
+            Some(r) => assert_eq!(&streamed.offs, r, "chunk_bytes={chunk_bytes}"),
+            None => results = Some(streamed.offs),
+        }
+    }
+    results.unwrap()
+}
+
+#[test]
+fn matches_within_the_gap_are_suppressed() {
+    let data = b"a..a......aa";
+    assert_eq!(offs(data, None, GrepFlags::empty()), vec![0, 3, 10, 11]);
+    assert_eq!(offs(data, Some(5), GrepFlags::empty()), vec![0, 10]);
+    assert_eq!(offs(data, Some(5), GrepFlags::LINE_NUMBER), vec![0, 10]);
+}
+
+#[test]
+fn gap_counts_from_the_last_reported_match() {
+    // Suppressed matches don't move the window: 4 is within 5 of 0, 8 is not.
+    assert_eq!(offs(b"a...a...a", Some(5), GrepFlags::empty()), vec![0, 8]);
+    // A gap of 1 changes nothing.
+    assert_eq!(offs(b"aaa", Some(1), GrepFlags::empty()), vec![0, 1, 2]);
+}
+
+#[test]
+fn gap_is_independent_of_the_needle_length() {
+    let opts = GrepOptions {
+        min_gap: Some(3),
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"ab", opts)
+        .search_slice(DATA, &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![0, 3, 10]);
+}
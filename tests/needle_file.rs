@@ -0,0 +1,40 @@
+mod common;
+
+use common::TempDir;
+use simd_grep::engine::{GrepEngine, GrepOptions, load_needles_delimited, load_needles_from_file};
+
+#[test]
+fn loads_one_needle_per_line() {
+    let dir = TempDir::new("needles");
+    dir.write("sigs.txt", b"alpha\n\nbe ta\r\n\xffgamma\nlast");
+    let needles = load_needles_from_file(&dir.path().join("sigs.txt")).unwrap();
+    assert_eq!(
+        needles,
+        vec![
+            b"alpha".to_vec(),
+            b"be ta\r".to_vec(),
+            b"\xffgamma".to_vec(),
+            b"last".to_vec()
+        ]
+    );
+
+    let haystack: &[u8] = b"alpha, be ta\r\n, \xffgamma and last";
+    for needle in &needles {
+        let eng = GrepEngine::new_literal(needle, GrepOptions::default());
+        assert!(eng.any_match(&mut &haystack[..]).unwrap(), "{:?}", needle);
+    }
+}
+
+#[test]
+fn nul_delimited_keeps_newlines() {
+    let dir = TempDir::new("needles_nul");
+    dir.write("sigs.bin", b"two\nlines\0\0plain\0");
+    let needles = load_needles_delimited(&dir.path().join("sigs.bin"), b'\0').unwrap();
+    assert_eq!(needles, vec![b"two\nlines".to_vec(), b"plain".to_vec()]);
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    let dir = TempDir::new("needles_missing");
+    assert!(load_needles_from_file(&dir.path().join("nope")).is_err());
+}
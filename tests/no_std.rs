@@ -0,0 +1,20 @@
+//! The `find`/`contains` helpers without the `std` feature.
+//!
+//! Run on its own with `cargo test --no-default-features --test no_std`, since
+//! the other test files need the engine.
+
+use simd_grep::lines::count_newlines;
+use simd_grep::{contains, ends_with, find, find_overlapping_iter, starts_with};
+
+#[test]
+fn byte_search_helpers() {
+    let hay = b"boot: loading stage2\nboot: ok\n";
+    assert_eq!(find(hay, b"stage2"), Some(14));
+    assert_eq!(find(hay, b"ok"), Some(27));
+    assert_eq!(find(hay, b"panic"), None);
+    assert!(contains(hay, b":"));
+    assert!(starts_with(hay, b"boot"));
+    assert!(ends_with(hay, b"ok\n"));
+    assert_eq!(find_overlapping_iter(hay, b"boot").count(), 2);
+    assert_eq!(count_newlines(hay), 2);
+}
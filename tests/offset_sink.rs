@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use simd_grep::engine::{GrepEngine, GrepOptions, MatchSink};
+use simd_grep::io::output::OffsetSink;
+
+#[test]
+fn writes_one_offset_per_line() {
+    let mut sink = OffsetSink::new(Vec::new());
+    GrepEngine::new_literal(b"ab", GrepOptions::default())
+        .search(&mut &b"ab xab abab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.finish().unwrap(), b"0\n4\n7\n9\n");
+}
+
+#[test]
+fn optional_columns_are_tab_separated() {
+    let mut sink = OffsetSink::new(Vec::new()).with_len().with_file_id();
+    sink.on_match(12, 3, 0, 0);
+    sink.on_match(4096, 10, 7, 2);
+    assert_eq!(sink.finish().unwrap(), b"12\t3\t0\n4096\t10\t2\n");
+
+    let mut sink = OffsetSink::new(Vec::new()).with_file_id();
+    sink.on_match(5, 3, 0, 9);
+    assert_eq!(sink.finish().unwrap(), b"5\t9\n");
+}
+
+/// A writer whose bytes stay visible after the sink is dropped.
+#[derive(Clone, Default)]
+struct Shared(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn buffered_output_is_flushed_on_drop() {
+    let out = Shared::default();
+    {
+        let mut sink = OffsetSink::new(out.clone());
+        sink.on_match(1, 1, 0, 0);
+        sink.on_match(2, 1, 0, 0);
+        assert!(out.0.borrow().is_empty());
+    }
+    assert_eq!(&*out.0.borrow(), b"1\n2\n");
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use simd_grep::engine::MatchSink;
+use simd_grep::io::output::{PathStyle, PrintSink};
+
+fn print(paths: Vec<PathBuf>, style: PathStyle) -> Vec<u8> {
+    let mut sink = PrintSink::new(Vec::new(), paths, style);
+    sink.on_match(7, 3, 2, 0);
+    sink.on_match(9, 3, 0, 0);
+    sink.finish().unwrap()
+}
+
+#[test]
+fn utf8_paths_print_identically_in_both_styles() {
+    let paths = vec![PathBuf::from("dir/file.txt")];
+    let expected = b"dir/file.txt:2:7\ndir/file.txt:9\n".to_vec();
+    assert_eq!(print(paths.clone(), PathStyle::Lossy), expected);
+    assert_eq!(print(paths, PathStyle::Raw), expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn invalid_utf8_path_is_raw_or_replaced() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = PathBuf::from(OsStr::from_bytes(b"bad-\xff\xfe.log"));
+
+    let raw = print(vec![path.clone()], PathStyle::Raw);
+    assert_eq!(raw, b"bad-\xff\xfe.log:2:7\nbad-\xff\xfe.log:9\n".to_vec());
+
+    let lossy = print(vec![path], PathStyle::Lossy);
+    assert_eq!(
+        String::from_utf8(lossy).unwrap(),
+        "bad-\u{FFFD}\u{FFFD}.log:2:7\nbad-\u{FFFD}\u{FFFD}.log:9\n"
+    );
+}
+
+#[test]
+fn unknown_file_id_omits_path() {
+    let mut sink = PrintSink::new(Vec::new(), Vec::new(), PathStyle::Lossy);
+    sink.on_match(4, 1, 0, 3);
+    assert_eq!(sink.finish().unwrap(), b"4\n".to_vec());
+}
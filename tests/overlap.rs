@@ -0,0 +1,79 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::chunker::Chunker;
+
+// With 8-byte chunks the needle straddles the first boundary: "NEEDL|E".
+const DATA: &[u8] = b"xxxNEEDLExxNEEDLExxxxxxxNEEDLE";
+
+fn opts(overlap: Option<usize>) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes: 8,
+        overlap,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn short_overlap_would_miss_boundary_match() {
+    let mut reader = DATA;
+    let mut chunker = Chunker::new(&mut reader, 8, 2);
+    let mut found = 0;
+    while let Some((_, chunk)) = chunker.next_chunk().unwrap() {
+        found += memchr::memmem::find_iter(chunk, b"NEEDLE").count();
+    }
+    assert!(found < 3);
+
+    let eng = GrepEngine::new_literal(b"NEEDLE", opts(Some(2)));
+    let err = eng
+        .search(&mut &DATA[..], &mut VecSink::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(eng.contains_in(&mut &DATA[..]).is_err());
+}
+
+#[test]
+fn exact_and_larger_overlaps_find_each_match_once() {
+    for overlap in [None, Some(5), Some(6), Some(20)] {
+        for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+            let eng = GrepEngine::new_literal(
+                b"NEEDLE",
+                GrepOptions {
+                    flags,
+                    ..opts(overlap)
+                },
+            );
+            let mut sink = VecSink::default();
+            eng.search(&mut &DATA[..], &mut sink).unwrap();
+            assert_eq!(
+                sink.offs,
+                vec![3, 11, 24],
+                "overlap {:?} {:?}",
+                overlap,
+                flags
+            );
+        }
+    }
+}
+
+#[test]
+fn needle_longer_than_chunk_is_found() {
+    let data: &[u8] = b"xxxxx0123456789xxxxx";
+    let opts = GrepOptions {
+        chunk_bytes: 4,
+        ..Default::default()
+    };
+    for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+        let eng = GrepEngine::new_literal(
+            b"0123456789",
+            GrepOptions {
+                flags,
+                ..opts.clone()
+            },
+        );
+        let mut sink = VecSink::default();
+        eng.search(&mut &data[..], &mut sink).unwrap();
+        assert_eq!(sink.offs, vec![5]);
+        assert!(eng.contains_in(&mut &data[..]).unwrap());
+    }
+    let eng = GrepEngine::new_literal(b"0123456789", opts);
+    assert!(!eng.contains_in(&mut &data[..9]).unwrap());
+}
@@ -0,0 +1,36 @@
+use memchr::memmem;
+use simd_grep::find_overlapping_iter;
+
+fn both(haystack: &[u8], needle: &[u8]) -> (Vec<usize>, Vec<usize>) {
+    (
+        find_overlapping_iter(haystack, needle).collect(),
+        memmem::find_iter(haystack, needle).collect(),
+    )
+}
+
+#[test]
+fn overlapping_versus_non_overlapping() {
+    assert_eq!(both(b"aaaaa", b"aa"), (vec![0, 1, 2, 3], vec![0, 2]));
+    assert_eq!(both(b"abababa", b"aba"), (vec![0, 2, 4], vec![0, 4]));
+    // Without self-overlap in the needle the two agree.
+    assert_eq!(both(b"abcxabc", b"abc"), (vec![0, 4], vec![0, 4]));
+    assert_eq!(both(b"xyz", b"abc"), (vec![], vec![]));
+}
+
+#[test]
+fn edge_cases() {
+    assert_eq!(
+        find_overlapping_iter(b"abc", b"").collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert_eq!(find_overlapping_iter(b"", b"").collect::<Vec<_>>(), vec![0]);
+    assert_eq!(find_overlapping_iter(b"ab", b"abc").count(), 0);
+    assert_eq!(
+        find_overlapping_iter(b"aaa", b"aaa").collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert_eq!(
+        find_overlapping_iter(b"aaa", b"a").collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+}
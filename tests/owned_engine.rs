@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::thread;
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, OwnedGrepEngine, VecSink};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn engines_are_send_and_sync() {
+    assert_send_sync::<GrepEngine<'static>>();
+    assert_send_sync::<OwnedGrepEngine>();
+}
+
+#[test]
+fn shared_engine_searches_from_two_threads() {
+    let opts = GrepOptions {
+        chunk_bytes: 7,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    let engine = Arc::new(OwnedGrepEngine::new_literal(b"needle", opts));
+
+    let inputs: [&'static [u8]; 2] = [b"needle\nhay\nneedle needle\n", b"hay\nhay\nneedle"];
+    let handles: Vec<_> = inputs
+        .into_iter()
+        .map(|input| {
+            let engine = Arc::clone(&engine);
+            thread::spawn(move || {
+                let mut results = Vec::new();
+                for _ in 0..50 {
+                    let mut sink = VecSink::default();
+                    engine.search(&mut &input[..], &mut sink).unwrap();
+                    results.push((sink.offs, sink.line_nos));
+                }
+                results
+            })
+        })
+        .collect();
+
+    let expected = [(vec![0, 11, 18], vec![1, 3, 3]), (vec![8], vec![3])];
+    for (handle, want) in handles.into_iter().zip(expected) {
+        for got in handle.join().unwrap() {
+            assert_eq!(got, want);
+        }
+    }
+}
+
+#[test]
+fn into_owned_engine_outlives_the_borrowed_needle() {
+    let owned = {
+        let needle = b"abc".to_vec();
+        GrepEngine::new_literal(&needle, GrepOptions::default()).into_owned_engine()
+    };
+    let mut sink = VecSink::default();
+    owned.search(&mut &b"xabcabc"[..], &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![1, 4]);
+}
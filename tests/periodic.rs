@@ -0,0 +1,53 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{CountSink, GrepEngine, GrepOptions, VecSink};
+
+/// Overlapping occurrences, as the engine reports them.
+fn reference(haystack: &[u8], needle: &[u8]) -> Vec<u64> {
+    (0..haystack.len())
+        .filter(|&i| haystack[i..].starts_with(needle))
+        .map(|i| i as u64)
+        .collect()
+}
+
+#[test]
+fn run_of_a_counts_every_overlap() {
+    let data = vec![b'a'; 1 << 20];
+    let mut sink = CountSink::default();
+    GrepEngine::new_literal(b"aaa", GrepOptions::default())
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.total, (1 << 20) - 2);
+
+    let opts = GrepOptions {
+        chunk_bytes: 4096,
+        ..Default::default()
+    };
+    let mut sink = CountSink::default();
+    GrepEngine::new_literal(b"aaa", opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.total, (1 << 20) - 2);
+}
+
+#[test]
+fn periodic_needles_agree_with_reference() {
+    let mut rng = StdRng::seed_from_u64(0x351);
+    let needles: [&[u8]; 6] = [b"aa", b"abab", b"abaab", b"aabaa", b"abcab", b"aaab"];
+    for _ in 0..200 {
+        let data: Vec<u8> = (0..rng.gen_range(0..400))
+            .map(|_| b"aabc"[rng.gen_range(0..4)])
+            .collect();
+        for needle in needles {
+            let opts = GrepOptions {
+                chunk_bytes: rng.gen_range(8..64),
+                ..Default::default()
+            };
+            let mut sink = VecSink::default();
+            GrepEngine::new_literal(needle, opts)
+                .search(&mut &data[..], &mut sink)
+                .unwrap();
+            assert_eq!(sink.offs, reference(&data, needle), "needle {:?}", needle);
+        }
+    }
+}
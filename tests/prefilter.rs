@@ -0,0 +1,41 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+fn offsets(data: &[u8], needle: &[u8], flags: GrepFlags) -> Vec<u64> {
+    let opts = GrepOptions {
+        chunk_bytes: 64,
+        flags,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(needle, opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    sink.offs
+}
+
+#[test]
+fn prefilter_does_not_change_results() {
+    let mut rng = StdRng::seed_from_u64(0xf117e2);
+    for round in 0..40 {
+        // Alternate between sparse (wide alphabet) and dense (two letters) inputs.
+        let alphabet: &[u8] = if round % 2 == 0 {
+            b"abcdefghijklmnopqrstuvwxyz"
+        } else {
+            b"zq"
+        };
+        let data: Vec<u8> = (0..rng.gen_range(0..2000))
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let needle_len = rng.gen_range(1..6);
+        let needle: Vec<u8> = (0..needle_len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+            let on = offsets(&data, &needle, flags);
+            let off = offsets(&data, &needle, flags | GrepFlags::NO_PREFILTER);
+            assert_eq!(on, off);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::io::Cursor;
+use std::sync::Arc;
+use std::thread;
+
+use simd_grep::engine::{GrepEngine, GrepOptions, PreparedPattern, VecSink};
+
+fn run(eng: &GrepEngine<'_>, data: &[u8]) -> VecSink {
+    let mut sink = VecSink::default();
+    eng.search(&mut Cursor::new(data.to_vec()), &mut sink)
+        .unwrap();
+    sink
+}
+
+#[test]
+fn prepared_pattern_matches_new_literal() {
+    let pattern = PreparedPattern::new(b"NEEDLE");
+    assert_eq!(pattern.needle(), b"NEEDLE");
+
+    let inputs: [&[u8]; 4] = [
+        b"xxx-NEEDLE-yyy-NEEDLE-zzz",
+        b"no match here",
+        b"NEEDLENEEDLE",
+        b"",
+    ];
+    for chunk_bytes in [4, 8, 1024] {
+        for data in inputs {
+            let opts = GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            };
+            let prepared = run(&GrepEngine::from_prepared(&pattern, opts.clone()), data);
+            let literal = run(&GrepEngine::new_literal(b"NEEDLE", opts), data);
+            assert_eq!(prepared.offs, literal.offs);
+            assert_eq!(prepared.lens, literal.lens);
+        }
+    }
+}
+
+#[test]
+fn prepared_pattern_is_shareable_across_threads() {
+    let pattern = Arc::new(PreparedPattern::new(b"ab"));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let pattern = pattern.clone();
+            thread::spawn(move || {
+                let eng = GrepEngine::from_prepared(&pattern, GrepOptions::default());
+                run(&eng, b"ab-ab-ab").offs
+            })
+        })
+        .collect();
+    for h in handles {
+        assert_eq!(h.join().unwrap(), vec![0, 3, 6]);
+    }
+}
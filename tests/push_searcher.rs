@@ -0,0 +1,63 @@
+use simd_grep::engine::{ByteRange, GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+fn pushed(engine: &GrepEngine<'_>, parts: &[&[u8]]) -> VecSink {
+    let mut sink = VecSink::default();
+    let mut searcher = engine.push_searcher().unwrap();
+    for part in parts {
+        searcher.push(part, &mut sink);
+    }
+    searcher.finish(&mut sink);
+    sink
+}
+
+#[test]
+fn needle_split_across_two_pushes() {
+    let engine = GrepEngine::new_literal(b"needle", GrepOptions::default());
+    let sink = pushed(&engine, &[b"xxxnee", b"dlexx"]);
+    assert_eq!(sink.offs, vec![3]);
+}
+
+#[test]
+fn needle_split_across_three_pushes() {
+    let engine = GrepEngine::new_literal(b"needle", GrepOptions::default());
+    let sink = pushed(&engine, &[b"packet one ne", b"e", b"dle tail"]);
+    assert_eq!(sink.offs, vec![11]);
+}
+
+#[test]
+fn pushes_match_a_streamed_search_at_every_split() {
+    let data: &[u8] = b"ab\nxab ab\nabab\n\nab";
+    for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+        let opts = GrepOptions::default().with_flags(flags);
+        let engine = GrepEngine::new_literal(b"ab", opts);
+        let mut expected = VecSink::default();
+        engine.search(&mut &data[..], &mut expected).unwrap();
+        for i in 0..=data.len() {
+            for j in i..=data.len() {
+                let sink = pushed(&engine, &[&data[..i], &data[i..j], &data[j..]]);
+                assert_eq!(sink.offs, expected.offs, "{flags:?} split at {i}, {j}");
+                assert_eq!(sink.line_nos, expected.line_nos);
+            }
+        }
+    }
+}
+
+#[test]
+fn limit_stops_reporting_and_byte_range_is_rejected() {
+    let opts = GrepOptions {
+        max_matches: Some(1),
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"ab", opts);
+    assert_eq!(pushed(&engine, &[b"abab", b"ab"]).offs, vec![0]);
+
+    let opts = GrepOptions {
+        byte_range: Some(ByteRange::Head(4)),
+        ..Default::default()
+    };
+    let err = GrepEngine::new_literal(b"ab", opts)
+        .push_searcher()
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,82 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, grep_bytes};
+use simd_grep::io::testutil::ChunkedReader;
+
+const DOCS: &[u8] = b"name: a\nkey: x\n---\nname: b\n---\nkey: y\nname: c\n---\nkey: z";
+
+#[derive(Default)]
+struct Records(Vec<(u64, u32, u32, Vec<u8>)>);
+
+impl MatchSink for Records {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+    fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+        self.0.push((m.off, m.line_no, m.col, line.to_vec()));
+    }
+}
+
+fn opts(sep: &[u8], chunk_bytes: usize) -> GrepOptions {
+    GrepOptions {
+        flags: GrepFlags::LINE_NUMBER,
+        record_separator: Some(sep.to_vec()),
+        chunk_bytes,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn multi_byte_separator_numbers_records() {
+    let expected = vec![
+        (3, 1, 4, b"name: a\nkey: x".to_vec()),
+        (9, 1, 10, b"name: a\nkey: x".to_vec()),
+        (22, 2, 4, b"name: b".to_vec()),
+        (32, 3, 2, b"key: y\nname: c".to_vec()),
+        (41, 3, 11, b"key: y\nname: c".to_vec()),
+        (51, 4, 2, b"key: z".to_vec()),
+    ];
+    for chunk_bytes in [4, 5, 7, 16, 64 * 1024] {
+        for step in [1, 3, 64] {
+            let eng = GrepEngine::new_literal(b"e", opts(b"\n---\n", chunk_bytes));
+            let mut sink = Records::default();
+            eng.search(&mut ChunkedReader::new(DOCS, step), &mut sink)
+                .unwrap();
+            assert_eq!(sink.0, expected, "chunk_bytes={chunk_bytes} step={step}");
+        }
+    }
+}
+
+#[test]
+fn separator_straddling_chunks() {
+    let eng = GrepEngine::new_literal(b"y", opts(b"\n---\n", 5));
+    let mut sink = Records::default();
+    eng.search(&mut ChunkedReader::new(DOCS, 2), &mut sink)
+        .unwrap();
+    let found: Vec<_> = sink.0.iter().map(|m| (m.0, m.1)).collect();
+    assert_eq!(found, vec![(10, 1), (33, 3), (36, 3), (52, 4)]);
+}
+
+#[test]
+fn nul_separated_records() {
+    let data: &[u8] = b"one\ntwo\0three\0four two\0";
+    let eng = GrepEngine::new_literal(b"two", opts(b"\0", 6));
+    let mut sink = Records::default();
+    eng.search(&mut ChunkedReader::new(data, 4), &mut sink)
+        .unwrap();
+    assert_eq!(
+        sink.0,
+        vec![
+            (4, 1, 5, b"one\ntwo".to_vec()),
+            (19, 3, 6, b"four two".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn no_cross_line_uses_the_separator() {
+    let mut o = opts(b"\0", 64);
+    o.flags |= GrepFlags::NO_CROSS_LINE;
+    let hits = grep_bytes(b"a\nb\0a\0b", b"a\n", &o).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].line, b"a\nb");
+    let hits = grep_bytes(b"a\nb\0a\0b", b"a\0", &o).unwrap();
+    assert!(hits.is_empty());
+}
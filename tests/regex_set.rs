@@ -0,0 +1,116 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, VecSink};
+use simd_grep::error::GrepError;
+
+/// `(off, len, pattern, line_no)` of every report, from a stream and from a slice.
+fn reports(patterns: &[&str], data: &[u8]) -> Vec<(u64, u32, u32, u32)> {
+    #[derive(Default)]
+    struct Reports(Vec<(u64, u32, u32, u32)>);
+    impl MatchSink for Reports {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_match_info(&mut self, m: &Match) {
+            self.0.push((m.off, m.len, m.pattern, m.line_no));
+        }
+    }
+
+    let mut expected = None;
+    for chunk_bytes in [20, 33, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            overlap: Some(16),
+            flags: GrepFlags::LINE_NUMBER,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_regex_set(patterns, opts).unwrap();
+        let mut streamed = Reports::default();
+        engine.search(&mut &data[..], &mut streamed).unwrap();
+        let mut sliced = Reports::default();
+        engine.search_slice(data, &mut sliced).unwrap();
+        assert_eq!(streamed.0, sliced.0, "chunk_bytes={chunk_bytes}");
+        let expected = expected.get_or_insert(sliced.0);
+        assert_eq!(&streamed.0, expected, "chunk_bytes={chunk_bytes}");
+    }
+    expected.unwrap()
+}
+
+#[test]
+fn reports_which_patterns_match_each_line() {
+    let data = b"error: disk full\nwarn: cpu 91%\nok\nerror 42 at cpu\n";
+    assert_eq!(
+        reports(&["error", r"\d+", "cpu"], data),
+        vec![
+            (0, 5, 0, 1),
+            (23, 3, 2, 2),
+            (27, 2, 1, 2),
+            (34, 5, 0, 4),
+            (40, 2, 1, 4),
+            (46, 3, 2, 4),
+        ]
+    );
+}
+
+#[test]
+fn patterns_may_overlap_each_other() {
+    let data = b"xabbc abc";
+    assert_eq!(
+        reports(&["ab+", "a", "b+c"], data),
+        vec![
+            (1, 3, 0, 1),
+            (1, 1, 1, 1),
+            (2, 3, 2, 1),
+            (6, 2, 0, 1),
+            (6, 1, 1, 1),
+            (7, 2, 2, 1),
+        ]
+    );
+    // Each pattern's own matches don't overlap.
+    assert_eq!(reports(&["aa"], b"aaaaa"), vec![(0, 2, 0, 1), (2, 2, 0, 1)]);
+}
+
+#[test]
+fn patterns_see_one_line_at_a_time() {
+    let data = b"disk full\nwarn\nok\n";
+    assert_eq!(reports(&[r"full\swarn"], data), vec![]);
+    assert_eq!(
+        reports(&["^ok$", "^w", r"l$"], data),
+        vec![(8, 1, 2, 1), (10, 1, 1, 2), (15, 2, 0, 3)]
+    );
+}
+
+#[test]
+fn lines_longer_than_a_chunk_are_searched_whole() {
+    let mut run = vec![b'a'; 20001];
+    let engine = GrepEngine::new_regex_set(&["a{1000}"], GrepOptions::default()).unwrap();
+    let mut sliced = VecSink::default();
+    engine.search_slice(&run, &mut sliced).unwrap();
+    assert_eq!(sliced.offs.len(), 20);
+    for overlap in [None, Some(0)] {
+        let opts = GrepOptions {
+            chunk_bytes: 8192,
+            overlap,
+            ..Default::default()
+        };
+        let engine = GrepEngine::new_regex_set(&["a{1000}", "^y"], opts).unwrap();
+        let mut streamed = VecSink::default();
+        engine.search(&mut &run[..], &mut streamed).unwrap();
+        assert_eq!(streamed.offs, sliced.offs, "overlap={overlap:?}");
+    }
+
+    // A `y` at a chunk start is not at a line start.
+    run[8192] = b'y';
+    run.extend_from_slice(b"\ny");
+    let opts = GrepOptions {
+        chunk_bytes: 8192,
+        overlap: Some(0),
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_regex_set(&["^y"], opts).unwrap();
+    let mut streamed = VecSink::default();
+    engine.search(&mut &run[..], &mut streamed).unwrap();
+    assert_eq!(streamed.offs, vec![20002]);
+}
+
+#[test]
+fn invalid_pattern_is_reported() {
+    let err = GrepEngine::new_regex_set(&["ok", "(unclosed"], GrepOptions::default()).err();
+    assert!(matches!(err, Some(GrepError::Regex(_))));
+}
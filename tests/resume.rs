@@ -0,0 +1,238 @@
+use std::io::{self, Cursor};
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, SearchState, VecSink};
+
+const DATA: &[u8] = b"one needle\ntwo\nneedle three needle\n\nfour neeneedle\nneedle";
+
+fn engine() -> GrepEngine<'static> {
+    GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            flags: GrepFlags::LINE_NUMBER,
+            chunk_bytes: 8,
+            ..Default::default()
+        },
+    )
+}
+
+fn single_pass() -> VecSink {
+    let mut sink = VecSink::default();
+    engine().search(&mut &DATA[..], &mut sink).unwrap();
+    sink
+}
+
+#[test]
+fn two_resumed_halves_equal_one_pass() {
+    let whole = single_pass();
+    assert_eq!(whole.offs, vec![4, 15, 28, 44, 51]);
+    for split in 0..=DATA.len() as u64 + 2 {
+        let mut sink = VecSink::default();
+        let state = engine()
+            .search_until(&mut Cursor::new(DATA), &mut sink, split)
+            .unwrap();
+        assert_eq!(state.global_off, split.min(DATA.len() as u64));
+
+        // Round-trip through the checkpoint encoding.
+        let state = SearchState::from_bytes(&state.to_bytes()).unwrap();
+        engine()
+            .resume_from(&mut Cursor::new(DATA), &mut sink, state)
+            .unwrap();
+        assert_eq!(sink.offs, whole.offs, "split at {split}");
+        assert_eq!(sink.line_nos, whole.line_nos, "split at {split}");
+    }
+}
+
+#[test]
+fn checkpoint_counts_lines_before_it() {
+    let mut sink = VecSink::default();
+    let state = engine()
+        .search_until(&mut Cursor::new(DATA), &mut sink, 15)
+        .unwrap();
+    assert_eq!(
+        state,
+        SearchState {
+            global_off: 15,
+            line_no: 3,
+            line_start: 15,
+            match_end: 10,
+        }
+    );
+    assert_eq!(sink.offs, vec![4]);
+
+    // A resumed search at a line start reports the same columns.
+    engine()
+        .resume_from(&mut Cursor::new(DATA), &mut sink, state)
+        .unwrap();
+    assert_eq!(sink.cols, single_pass().cols);
+}
+
+#[test]
+fn checkpoint_inside_a_line_keeps_its_start() {
+    let mut sink = VecSink::default();
+    let state = engine()
+        .search_until(&mut Cursor::new(DATA), &mut sink, 20)
+        .unwrap();
+    assert_eq!(
+        state,
+        SearchState {
+            global_off: 20,
+            line_no: 3,
+            line_start: 15,
+            match_end: 21,
+        }
+    );
+    engine()
+        .resume_from(&mut Cursor::new(DATA), &mut sink, state)
+        .unwrap();
+    let whole = single_pass();
+    assert_eq!(sink.offs, whole.offs);
+    assert_eq!(sink.cols, whole.cols);
+}
+
+/// Splits a search of `data` at every offset and returns what each split
+/// reports, next to what one pass reports.
+fn splits(eng: &GrepEngine<'_>, data: &[u8]) -> (Vec<u64>, Vec<(u64, Vec<u64>)>) {
+    let mut whole = VecSink::default();
+    eng.search(&mut &data[..], &mut whole).unwrap();
+    let halves = (0..=data.len() as u64)
+        .map(|split| {
+            let mut sink = VecSink::default();
+            let state = eng
+                .search_until(&mut Cursor::new(data), &mut sink, split)
+                .unwrap();
+            eng.resume_from(&mut Cursor::new(data), &mut sink, state)
+                .unwrap();
+            (split, sink.offs)
+        })
+        .collect();
+    (whole.offs, halves)
+}
+
+#[test]
+fn resumed_regex_sees_the_bytes_before_the_checkpoint() {
+    let data = b"xneedle\nab needle\n";
+    for (pattern, expected) in [
+        (r"\bneedle", vec![11]),
+        (r"^needle", vec![]),
+        (r"\Aab", vec![]),
+        (r"(?m)^ab", vec![8]),
+    ] {
+        let opts = GrepOptions {
+            chunk_bytes: 8,
+            ..Default::default()
+        };
+        let eng = GrepEngine::new_regex(pattern, opts).unwrap();
+        let (whole, halves) = splits(&eng, data);
+        assert_eq!(whole, expected, "{pattern}");
+        for (split, offs) in halves {
+            assert_eq!(offs, expected, "{pattern} split at {split}");
+        }
+    }
+}
+
+#[test]
+fn resumed_regex_splits_equal_one_pass() {
+    let data = b"aab xab\naaab ab aaaab\nb aab";
+    for pattern in ["a+b", "a*b", r"\ba+b", "(?m)^a+b|b$", "ab|aab"] {
+        let opts = GrepOptions {
+            chunk_bytes: 8,
+            ..Default::default()
+        };
+        let eng = GrepEngine::new_regex(pattern, opts).unwrap();
+        let (whole, halves) = splits(&eng, data);
+        assert!(!whole.is_empty(), "{pattern}");
+        for (split, offs) in halves {
+            assert_eq!(offs, whole, "{pattern} split at {split}");
+        }
+    }
+}
+
+#[test]
+fn resumed_line_offsets_point_at_the_line_start() {
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            flags: GrepFlags::line_offsets(),
+            chunk_bytes: 8,
+            ..Default::default()
+        },
+    );
+    let (whole, halves) = splits(&eng, b"xneedle\nab needle\n");
+    assert_eq!(whole, vec![0, 8]);
+    for (split, offs) in halves {
+        assert_eq!(offs, whole, "split at {split}");
+    }
+}
+
+#[test]
+fn chained_checkpoints() {
+    let whole = single_pass();
+    let mut sink = VecSink::default();
+    let mut reader = Cursor::new(DATA);
+    let mut state = engine().search_until(&mut reader, &mut sink, 20).unwrap();
+    state = engine()
+        .resume_until(&mut reader, &mut sink, state, 47)
+        .unwrap();
+    assert_eq!(state.line_no, 5);
+    engine().resume_from(&mut reader, &mut sink, state).unwrap();
+    assert_eq!(sink.offs, whole.offs);
+    assert_eq!(sink.line_nos, whole.line_nos);
+}
+
+#[test]
+fn early_stop_still_checkpoints_at_end() {
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            flags: GrepFlags::LINE_NUMBER,
+            max_matches: Some(1),
+            ..Default::default()
+        },
+    );
+    let mut sink = VecSink::default();
+    let state = eng
+        .search_until(&mut Cursor::new(DATA), &mut sink, 36)
+        .unwrap();
+    assert_eq!(sink.offs, vec![4]);
+    assert_eq!(
+        state,
+        SearchState {
+            global_off: 36,
+            line_no: 5,
+            line_start: 36,
+            match_end: 10,
+        }
+    );
+}
+
+#[test]
+fn rejects_bad_encodings_and_options() {
+    let err = SearchState::from_bytes(&[0; 11]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = SearchState::from_bytes(&[0; 28]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let past = SearchState {
+        global_off: 5,
+        line_no: 1,
+        line_start: 6,
+        match_end: 0,
+    };
+    let err = SearchState::from_bytes(&past.to_bytes()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(
+        SearchState::from_bytes(&SearchState::default().to_bytes()).unwrap(),
+        SearchState::default()
+    );
+
+    let eng = GrepEngine::new_literal(
+        b"needle",
+        GrepOptions {
+            strip_bom: true,
+            ..Default::default()
+        },
+    );
+    let err = eng
+        .search_until(&mut Cursor::new(DATA), &mut VecSink::default(), 5)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
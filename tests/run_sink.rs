@@ -0,0 +1,34 @@
+use simd_grep::engine::{GrepEngine, GrepOptions};
+use simd_grep::io::output::{Run, RunSink};
+
+fn runs(needle: &[u8], data: &[u8]) -> Vec<(u64, u64, u64)> {
+    let mut sink = RunSink::new();
+    let opts = GrepOptions {
+        chunk_bytes: 4,
+        ..Default::default()
+    };
+    GrepEngine::new_literal(needle, opts)
+        .search(&mut &data[..], &mut sink)
+        .unwrap();
+    sink.finish()
+        .into_iter()
+        .map(
+            |Run {
+                 start, len, count, ..
+             }| (start, len, count),
+        )
+        .collect()
+}
+
+#[test]
+fn overlapping_matches_form_one_run() {
+    assert_eq!(runs(b"aa", b"aaaaa"), vec![(0, 5, 4)]);
+    assert_eq!(runs(b"aa", b"xaaaaax"), vec![(1, 5, 4)]);
+}
+
+#[test]
+fn touching_matches_join_and_gaps_split() {
+    assert_eq!(runs(b"ab", b"abab"), vec![(0, 4, 2)]);
+    assert_eq!(runs(b"ab", b"ab.ab"), vec![(0, 2, 1), (3, 2, 1)]);
+    assert_eq!(runs(b"aa", b"aaa.aa"), vec![(0, 3, 2), (4, 2, 1)]);
+}
@@ -6,7 +6,10 @@ use simd_grep::engine::{GrepEngine, GrepOptions, MatchSink, VecSink};
 fn finds_matches_within_single_chunk() {
     let data = b"xxx-NEEDLE-yyy-NEEDLE-zzz".to_vec();
     let mut reader = Cursor::new(data);
-    let opts = GrepOptions { chunk_bytes: 8, ..Default::default() };
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        ..Default::default()
+    };
     let eng = GrepEngine::new_literal(b"NEEDLE", opts);
 
     let mut sink = VecSink::default();
@@ -20,14 +23,17 @@ fn finds_matches_within_single_chunk() {
 fn finds_boundary_crossing_match_due_to_overlap() {
     // Arrange data so that "NEEDLE" straddles the chunk boundary.
     // Chunk size is small to force multiple chunks: overlap = needle.len()-1 = 5.
-    let payload = b"AAAAANEEE".to_vec(); // prefix
-    let mid = b"DLEBBBBB".to_vec();      // carry across boundary
+    let payload = b"AAAAANEE".to_vec(); // prefix
+    let mid = b"DLEBBBBB".to_vec(); // carry across boundary
     let mut buf = Vec::new();
     buf.extend_from_slice(&payload);
     buf.extend_from_slice(&mid);
 
     let mut reader = Cursor::new(buf);
-    let opts = GrepOptions { chunk_bytes: 9, ..Default::default() }; // force split near "NEE|DLE"
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        ..Default::default()
+    }; // force split near "NEE|DLE"
     let eng = GrepEngine::new_literal(b"NEEDLE", opts);
 
     let mut sink = VecSink::default();
@@ -52,7 +58,10 @@ impl MatchSink for CountingSink {
 fn reports_all_overlapping_occurrences() {
     // "aaaaa" contains "aaa" at positions 0,1,2 -> 3 matches.
     let mut reader = Cursor::new(b"aaaaa".to_vec());
-    let opts = GrepOptions { chunk_bytes: 3, ..Default::default() };
+    let opts = GrepOptions {
+        chunk_bytes: 3,
+        ..Default::default()
+    };
     let eng = GrepEngine::new_literal(b"aaa", opts);
 
     let mut sink = CountingSink::default();
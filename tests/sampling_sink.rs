@@ -0,0 +1,136 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use simd_grep::engine::{GrepEngine, GrepOptions, Match, MatchRegion, MatchSink, VecSink};
+use simd_grep::io::output::{Clock, SamplingSink};
+
+/// A clock that only moves when told to.
+#[derive(Clone)]
+struct MockClock(Rc<Cell<Instant>>);
+
+impl MockClock {
+    fn new() -> Self {
+        Self(Rc::new(Cell::new(Instant::now())))
+    }
+
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+#[test]
+fn every_nth_forwards_indices_0_3_6_9() {
+    let mut sink = SamplingSink::every_nth(VecSink::default(), 3);
+    for off in 0..10 {
+        sink.on_match(off, 1, 0, 0);
+    }
+    assert_eq!(sink.total(), 10);
+    assert_eq!(sink.forwarded(), 4);
+    assert_eq!(sink.into_inner().offs, vec![0, 3, 6, 9]);
+}
+
+#[test]
+fn engine_totals_still_reach_the_inner_sink() {
+    struct Finish(Option<u64>);
+    impl MatchSink for Finish {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_finish(&mut self, _: u32, total: u64) {
+            self.0 = Some(total);
+        }
+    }
+
+    let data = b"x".repeat(10);
+    let eng = GrepEngine::new_literal(b"x", GrepOptions::default());
+    let mut sink = SamplingSink::every_nth(Finish(None), 4);
+    eng.search(&mut &data[..], &mut sink).unwrap();
+    assert_eq!(sink.total(), 10);
+    assert_eq!(sink.forwarded(), 3);
+    assert_eq!(sink.into_inner().0, Some(10));
+}
+
+#[test]
+fn regions_follow_their_match() {
+    #[derive(Default)]
+    struct Regions(Vec<(u64, MatchRegion)>);
+    impl MatchSink for Regions {
+        fn on_match(&mut self, _: u64, _: u32, _: u32, _: u32) {}
+        fn on_match_region(&mut self, m: &Match, region: MatchRegion) {
+            self.0.push((m.off, region));
+        }
+    }
+
+    let mut sink = SamplingSink::every_nth(Regions::default(), 2);
+    for (off, region) in [
+        (0, MatchRegion::Fresh),
+        (10, MatchRegion::Stitch),
+        (20, MatchRegion::Stitch),
+    ] {
+        let m = Match {
+            off,
+            match_off: off,
+            len: 1,
+            ..Default::default()
+        };
+        sink.on_match_region(&m, region);
+        sink.on_match_info(&m);
+    }
+    assert_eq!(
+        sink.into_inner().0,
+        vec![(0, MatchRegion::Fresh), (20, MatchRegion::Stitch)]
+    );
+}
+
+#[test]
+fn rate_limit_refills_with_time() {
+    let clock = MockClock::new();
+    let mut sink = SamplingSink::per_second(VecSink::default(), 2.0).with_clock(clock.clone());
+
+    // An initial burst of `rate` matches, then nothing until time passes.
+    for off in 0..5 {
+        sink.on_match(off, 1, 0, 0);
+    }
+    assert_eq!(sink.get_ref().offs, vec![0, 1]);
+
+    clock.advance(Duration::from_millis(500));
+    for off in 5..8 {
+        sink.on_match(off, 1, 0, 0);
+    }
+    assert_eq!(sink.get_ref().offs, vec![0, 1, 5]);
+
+    // A long pause refills the bucket only up to one second's worth.
+    clock.advance(Duration::from_secs(10));
+    for off in 8..12 {
+        sink.on_match(off, 1, 0, 0);
+    }
+    assert_eq!(sink.get_ref().offs, vec![0, 1, 5, 8, 9]);
+    assert_eq!(sink.total(), 12);
+    assert_eq!(sink.forwarded(), 5);
+}
+
+#[test]
+fn every_nth_and_rate_combine() {
+    let clock = MockClock::new();
+    let mut sink = SamplingSink::every_nth(VecSink::default(), 2)
+        .with_rate(1.0)
+        .with_clock(clock.clone());
+    for off in 0..6 {
+        sink.on_match(off, 1, 0, 0);
+        clock.advance(Duration::from_millis(400));
+    }
+    // Even offsets pass the first filter; the bucket admits 0 and, 0.8s later,
+    // not yet 2, then 4 at 1.6s.
+    assert_eq!(sink.into_inner().offs, vec![0, 4]);
+}
+
+#[test]
+#[should_panic(expected = "SamplingSink needs n of at least 1")]
+fn zero_n_panics() {
+    let _ = SamplingSink::every_nth(VecSink::default(), 0);
+}
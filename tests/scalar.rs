@@ -0,0 +1,94 @@
+//! The byte-by-byte search against the default one.
+//!
+//! With `cargo test --features scalar --test scalar` the engine itself runs on
+//! the scalar path, and the comparisons below check it against the reference.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::{find, find_scalar, simd_available};
+
+/// Inputs shaped like the other tests': text lines, a small alphabet that
+/// makes overlapping hits, and random binary.
+fn corpora() -> Vec<Vec<u8>> {
+    let mut rng = StdRng::seed_from_u64(0x5CA1);
+    let text = b"one needle\ntwo\nneedle three needle\n\nfour neeneedle\nneedle".repeat(40);
+    let small: Vec<u8> = (0..4000).map(|_| b"ab\n"[rng.gen_range(0..3)]).collect();
+    let binary: Vec<u8> = (0..8000).map(|_| rng.r#gen()).collect();
+    vec![text, small, binary, Vec::new()]
+}
+
+fn offsets(eng: &GrepEngine<'_>, data: &[u8]) -> (Vec<u64>, Vec<u32>) {
+    let mut sink = VecSink::default();
+    eng.search(&mut &data[..], &mut sink).unwrap();
+    (sink.offs, sink.line_nos)
+}
+
+/// Overlapping matches found one position at a time with `find_scalar`.
+fn reference(data: &[u8], needle: &[u8]) -> Vec<u64> {
+    let mut out = Vec::new();
+    let mut at = 0;
+    while let Some(i) = data.get(at..).and_then(|rest| find_scalar(rest, needle)) {
+        out.push((at + i) as u64);
+        at += i + 1;
+    }
+    out
+}
+
+#[test]
+fn find_matches_scalar_reference() {
+    let needles: [&[u8]; 7] = [b"", b"n", b"ab", b"aba", b"needle", b"\nne", b"zzzz"];
+    for data in corpora() {
+        for needle in needles {
+            assert_eq!(find(&data, needle), find_scalar(&data, needle));
+            for start in [1, 7, data.len() / 2] {
+                let rest = data.get(start..).unwrap_or_default();
+                assert_eq!(find(rest, needle), find_scalar(rest, needle));
+            }
+        }
+        let needle: &[u8] = &data[data.len() / 3..][..data.len().min(5)];
+        assert_eq!(find(&data, needle), find_scalar(&data, needle));
+    }
+}
+
+#[test]
+fn engine_matches_scalar_reference() {
+    let needles: [&[u8]; 5] = [b"n", b"ab", b"abab", b"needle", b"\nne"];
+    for data in corpora() {
+        for needle in needles {
+            let expected = reference(&data, needle);
+            for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+                for chunk_bytes in [7, 64, 1 << 20] {
+                    let eng = GrepEngine::new_literal(
+                        needle,
+                        GrepOptions {
+                            flags,
+                            chunk_bytes,
+                            ..Default::default()
+                        },
+                    );
+                    let (offs, line_nos) = offsets(&eng, &data);
+                    assert_eq!(offs, expected);
+                    if flags.contains(GrepFlags::LINE_NUMBER) {
+                        let lines: Vec<u32> = offs
+                            .iter()
+                            .map(|&off| {
+                                1 + simd_grep::lines::count_newlines(&data[..off as usize]) as u32
+                            })
+                            .collect();
+                        assert_eq!(line_nos, lines);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn capability_report() {
+    if cfg!(feature = "scalar") {
+        assert!(!simd_available());
+    } else if cfg!(target_arch = "x86_64") {
+        assert!(simd_available());
+    }
+}
@@ -0,0 +1,44 @@
+use std::io::{Cursor, Read};
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, OwnedGrepEngine, VecSink};
+
+const DATA: &[u8] = b"alpha needle\nbeta\nneedle gamma needle\n";
+
+fn boxed() -> Box<dyn Read> {
+    Box::new(Cursor::new(DATA.to_vec()))
+}
+
+#[test]
+fn boxed_reader_reports_what_search_does() {
+    let opts = GrepOptions {
+        chunk_bytes: 5,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    };
+    let engine = GrepEngine::new_literal(b"needle", opts);
+    let mut expected = VecSink::default();
+    engine.search(&mut &DATA[..], &mut expected).unwrap();
+
+    let mut reader = boxed();
+    let mut sink = VecSink::default();
+    engine.search_dyn(&mut *reader, &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![6, 18, 31]);
+    assert_eq!(sink.offs, expected.offs);
+    assert_eq!(sink.line_nos, expected.line_nos);
+}
+
+#[test]
+fn readers_picked_at_run_time() {
+    let engine = OwnedGrepEngine::new_literal(b"needle", GrepOptions::default());
+    for use_file in [false, true] {
+        // The concrete reader type differs between iterations.
+        let mut reader: Box<dyn Read> = if use_file {
+            boxed()
+        } else {
+            Box::new(&b"no match here"[..])
+        };
+        let mut sink = VecSink::default();
+        engine.search_dyn(reader.as_mut(), &mut sink).unwrap();
+        assert_eq!(sink.offs.len(), if use_file { 3 } else { 0 });
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::TempDir;
+use simd_grep::engine::{GrepOptions, VecSink};
+use simd_grep::error::GrepError;
+use simd_grep::io::search_paths;
+
+#[test]
+fn searches_existing_files_and_collects_errors() {
+    let dir = TempDir::new("paths");
+    let paths = vec![
+        dir.write("a.txt", b"needle"),
+        dir.path().join("missing.txt"),
+        dir.write("b.txt", b"xx needle needle"),
+        dir.path().join("gone/also_missing.txt"),
+        dir.write("c.txt", b"nothing"),
+    ];
+    let mut sink = VecSink::default();
+    let errors = search_paths(&paths, b"needle", &GrepOptions::default(), &mut sink);
+
+    assert_eq!(sink.offs, vec![0, 3, 10]);
+    assert_eq!(sink.file_ids, vec![0, 2, 2]);
+
+    let failed: Vec<_> = errors
+        .iter()
+        .map(|e| match e {
+            GrepError::Io(io, Some(path)) => {
+                assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+                path.clone()
+            }
+            other => panic!("unexpected error {other}"),
+        })
+        .collect();
+    assert_eq!(failed, vec![paths[1].clone(), paths[3].clone()]);
+}
+
+#[test]
+fn empty_list_searches_nothing() {
+    let mut sink = VecSink::default();
+    assert!(search_paths(&[], b"needle", &GrepOptions::default(), &mut sink).is_empty());
+    assert!(sink.offs.is_empty());
+}
@@ -0,0 +1,98 @@
+use std::io::Cursor;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{ByteRange, ColumnUnit, GrepEngine, GrepFlags, GrepOptions, VecSink};
+
+fn flag_combos() -> Vec<GrepFlags> {
+    let all = [
+        GrepFlags::COUNT_ONLY,
+        GrepFlags::LINE_NUMBER,
+        GrepFlags::REPORT_LINE_OFFSET,
+    ];
+    (0..1 << all.len())
+        .map(|bits| {
+            all.iter()
+                .enumerate()
+                .filter(|(i, _)| bits & (1 << i) != 0)
+                .fold(GrepFlags::empty(), |acc, (_, f)| acc | *f)
+        })
+        .collect()
+}
+
+fn same_results(data: &[u8], needle: &[u8], opts: GrepOptions) {
+    let eng = GrepEngine::new_literal(needle, opts.clone());
+    let mut chunked = VecSink::default();
+    eng.search_seekable(&mut Cursor::new(data), &mut chunked)
+        .unwrap();
+    let mut sliced = VecSink::default();
+    eng.search_slice(data, &mut sliced).unwrap();
+    assert_eq!(sliced.offs, chunked.offs, "{:?}", opts);
+    assert_eq!(sliced.line_nos, chunked.line_nos, "{:?}", opts);
+    assert_eq!(sliced.cols, chunked.cols, "{:?}", opts);
+}
+
+#[test]
+fn matches_chunked_search_for_all_flags() {
+    let mut rng = StdRng::seed_from_u64(0x511ce);
+    for _ in 0..30 {
+        let len = rng.gen_range(0..400);
+        let data: Vec<u8> = (0..len).map(|_| b"ab\n"[rng.gen_range(0..3)]).collect();
+        let needle: &[u8] = [&b"ab"[..], b"aba", b"a", b"b\na", b""][rng.gen_range(0..5)];
+        for flags in flag_combos() {
+            for column_unit in [ColumnUnit::Byte, ColumnUnit::Char] {
+                same_results(
+                    &data,
+                    needle,
+                    GrepOptions {
+                        chunk_bytes: rng.gen_range(4..32),
+                        flags,
+                        column_unit,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn matches_chunked_search_with_limits_and_ranges() {
+    let data = b"ab ab\nab\nxx ab ab ab\nab".repeat(5);
+    let variants = [
+        GrepOptions {
+            max_matches: Some(4),
+            ..Default::default()
+        },
+        GrepOptions {
+            max_matches: Some(0),
+            ..Default::default()
+        },
+        GrepOptions {
+            flags: GrepFlags::LINE_NUMBER,
+            max_matches_per_line: Some(1),
+            ..Default::default()
+        },
+        GrepOptions {
+            byte_range: Some(ByteRange::Head(30)),
+            ..Default::default()
+        },
+        GrepOptions {
+            flags: GrepFlags::LINE_NUMBER,
+            byte_range: Some(ByteRange::Tail(40)),
+            ..Default::default()
+        },
+        GrepOptions {
+            byte_range: Some(ByteRange::Span(7, 50)),
+            ..Default::default()
+        },
+        GrepOptions {
+            byte_range: Some(ByteRange::Span(500, 600)),
+            ..Default::default()
+        },
+    ];
+    for mut opts in variants {
+        opts.chunk_bytes = 7;
+        same_results(&data, b"ab", opts);
+    }
+}
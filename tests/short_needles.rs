@@ -0,0 +1,97 @@
+use memchr::memmem;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use simd_grep::engine::{GrepEngine, GrepOptions, VecSink};
+use simd_grep::io::chunker::Chunker;
+use simd_grep::io::testutil::ChunkedReader;
+use simd_grep::{contains, find};
+
+/// Overlapping occurrences, as the engine reports them.
+fn reference(haystack: &[u8], needle: &[u8]) -> Vec<u64> {
+    (0..haystack.len())
+        .filter(|&i| haystack[i..].starts_with(needle))
+        .map(|i| i as u64)
+        .collect()
+}
+
+#[test]
+fn short_needles_agree_with_memmem() {
+    let mut rng = StdRng::seed_from_u64(0x5407);
+    for _ in 0..200 {
+        let data: Vec<u8> = (0..rng.gen_range(0..300))
+            .map(|_| b"abc"[rng.gen_range(0..3)])
+            .collect();
+        for len in 1..=4 {
+            let needle: Vec<u8> = (0..len).map(|_| b"abc"[rng.gen_range(0..3)]).collect();
+            assert_eq!(find(&data, &needle), memmem::find(&data, &needle));
+
+            let opts = GrepOptions {
+                chunk_bytes: rng.gen_range(4..40),
+                ..Default::default()
+            };
+            let mut sink = VecSink::default();
+            GrepEngine::new_literal(&needle, opts)
+                .search(&mut &data[..], &mut sink)
+                .unwrap();
+            assert_eq!(sink.offs, reference(&data, &needle));
+        }
+    }
+}
+
+#[test]
+fn needle_at_haystack_edges() {
+    assert_eq!(find(b"ab", b"ab"), Some(0));
+    assert_eq!(find(b"a", b"ab"), None);
+    assert_eq!(find(b"xxab", b"ab"), Some(2));
+    assert_eq!(find(b"xxa", b"ab"), None);
+    assert_eq!(find(b"abc", b"abc"), Some(0));
+    assert_eq!(find(b"", b"a"), None);
+}
+
+#[test]
+fn single_byte_agrees_with_memchr() {
+    let base = vec![b'.'; 1000];
+    for at in [None, Some(0), Some(1), Some(500), Some(998), Some(999)] {
+        let mut data = base.clone();
+        if let Some(at) = at {
+            data[at] = b'x';
+        }
+        for hay in [&data[..], &data[..1], &[][..]] {
+            assert_eq!(find(hay, b"x"), memchr::memchr(b'x', hay));
+            assert_eq!(contains(hay, b"x"), memchr::memchr(b'x', hay).is_some());
+        }
+        assert_eq!(find(&data, b"x"), at);
+    }
+}
+
+#[test]
+fn zero_overlap_offsets_are_global() {
+    // A single-byte needle needs no overlap, so every chunk is fresh bytes only
+    // and must still start where the previous one ended.
+    let data = b"xa".repeat(50);
+    let expected: Vec<u64> = (0..50).map(|i| 2 * i + 1).collect();
+    for chunk_bytes in [1, 2, 3, 4, 7] {
+        let eng = GrepEngine::new_literal(
+            b"a",
+            GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            },
+        );
+        for step in [1, 3, 100] {
+            let mut sink = VecSink::default();
+            eng.search(&mut ChunkedReader::new(&data[..], step), &mut sink)
+                .unwrap();
+            assert_eq!(sink.offs, expected, "chunk_bytes={chunk_bytes} step={step}");
+        }
+    }
+
+    let mut reader = &data[..];
+    let mut chunker = Chunker::new(&mut reader, 4, 0);
+    let mut bases = Vec::new();
+    while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+        assert_eq!(chunk, &data[base as usize..][..chunk.len()]);
+        bases.push(base);
+    }
+    assert_eq!(bases, (0..25).map(|i| 4 * i).collect::<Vec<u64>>());
+}
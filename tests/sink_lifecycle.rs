@@ -0,0 +1,122 @@
+mod common;
+
+use common::TempDir;
+use simd_grep::engine::{
+    Control, ControlSink, GrepEngine, GrepOptions, Match, MatchSink, PreparedPattern,
+};
+use simd_grep::io::walk::{WalkOptions, search_dir};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Start(u32),
+    Match(u32, u64),
+    Finish(u32, u64),
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Event>);
+
+impl MatchSink for Recorder {
+    fn on_match(&mut self, off: u64, _len: u32, _line_no: u32, file_id: u32) {
+        self.0.push(Event::Match(file_id, off));
+    }
+
+    fn on_start(&mut self, file_id: u32) {
+        self.0.push(Event::Start(file_id));
+    }
+
+    fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+        self.0.push(Event::Finish(file_id, total_matches));
+    }
+}
+
+fn opts(file_id: u32) -> GrepOptions {
+    GrepOptions {
+        chunk_bytes: 4,
+        file_id,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hooks_wrap_each_search_once() {
+    let mut sink = Recorder::default();
+    let eng = GrepEngine::new_literal(b"ab", opts(7));
+    eng.search(&mut &b"ab ab xx ab"[..], &mut sink).unwrap();
+    eng.search(&mut &b"nothing"[..], &mut sink).unwrap();
+    eng.search_slice(b"xab", &mut sink).unwrap();
+    use Event::*;
+    assert_eq!(
+        sink.0,
+        vec![
+            Start(7),
+            Match(7, 0),
+            Match(7, 3),
+            Match(7, 9),
+            Finish(7, 3),
+            Start(7),
+            Finish(7, 0),
+            Start(7),
+            Match(7, 1),
+            Finish(7, 1),
+        ]
+    );
+}
+
+#[test]
+fn finish_counts_only_reported_matches() {
+    let mut sink = Recorder::default();
+    let limited = GrepOptions {
+        max_matches: Some(2),
+        ..opts(1)
+    };
+    GrepEngine::new_literal(b"ab", limited)
+        .search(&mut &b"ababab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.0.last(), Some(&Event::Finish(1, 2)));
+}
+
+#[test]
+fn stopping_sink_still_sees_finish() {
+    struct StopFirst(Vec<(u32, u64)>);
+    impl ControlSink for StopFirst {
+        fn on_match_control(&mut self, _: &Match, _: Option<&[u8]>) -> Control {
+            Control::Stop
+        }
+
+        fn on_finish(&mut self, file_id: u32, total_matches: u64) {
+            self.0.push((file_id, total_matches));
+        }
+    }
+    let mut sink = StopFirst(Vec::new());
+    GrepEngine::new_literal(b"ab", opts(3))
+        .search_control(&mut &b"ab ab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(sink.0, vec![(3, 1)]);
+}
+
+#[test]
+fn walker_reports_every_file() {
+    let dir = TempDir::new("lifecycle");
+    dir.write("a.txt", b"ab");
+    dir.write("b.txt", b"none");
+    dir.write("c.txt", b"ab ab");
+    let mut sink = Recorder::default();
+    search_dir(
+        dir.path(),
+        &PreparedPattern::new(b"ab"),
+        &GrepOptions::default(),
+        &WalkOptions::default(),
+        &mut sink,
+    )
+    .unwrap();
+    let finishes: Vec<_> = sink
+        .0
+        .iter()
+        .filter_map(|e| match e {
+            Event::Finish(id, n) => Some((*id, *n)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(finishes, vec![(0, 1), (1, 0), (2, 2)]);
+}
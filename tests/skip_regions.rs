@@ -0,0 +1,48 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::skip::QuotedStrings;
+
+const SRC: &[u8] = b"let needle = \"a needle\";\n\
+                      call(needle, \"esc \\\" needle\", needle);\n\
+                      \"multi\nline needle\" needle\n";
+
+fn skipping(opts: GrepOptions) -> VecSink {
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"needle", opts)
+        .search_skipping(&mut &SRC[..], &mut sink, &mut QuotedStrings::new())
+        .unwrap();
+    sink
+}
+
+#[test]
+fn matches_inside_double_quotes_are_skipped() {
+    let outside: Vec<u64> = vec![4, 30, 55, 84];
+    for &i in &outside {
+        assert_eq!(&SRC[i as usize..i as usize + 6], b"needle");
+    }
+    // Every chunk size, so the string state is carried across boundaries.
+    for chunk_bytes in 6..SRC.len() + 1 {
+        let opts = GrepOptions {
+            chunk_bytes,
+            ..Default::default()
+        };
+        assert_eq!(skipping(opts).offs, outside, "chunk_bytes {}", chunk_bytes);
+    }
+
+    let lined = skipping(GrepOptions {
+        chunk_bytes: 16,
+        flags: GrepFlags::LINE_NUMBER,
+        ..Default::default()
+    });
+    assert_eq!(lined.offs, outside);
+    assert_eq!(lined.line_nos, vec![1, 2, 2, 4]);
+}
+
+#[test]
+fn closures_can_veto_matches() {
+    let mut sink = VecSink::default();
+    let mut even_only = |_: &[u8], base: u64, pos: usize| (base + pos as u64).is_multiple_of(2);
+    GrepEngine::new_literal(b"a", GrepOptions::default())
+        .search_skipping(&mut &b"aaaaa"[..], &mut sink, &mut even_only)
+        .unwrap();
+    assert_eq!(sink.offs, vec![0, 2, 4]);
+}
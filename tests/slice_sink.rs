@@ -0,0 +1,38 @@
+use simd_grep::engine::{ByteRange, GrepEngine, GrepFlags, GrepOptions, SliceSink};
+
+#[test]
+fn literal_slices_equal_the_needle() {
+    let data = b"one needle\ntwo needle needle\n";
+    for flags in [GrepFlags::empty(), GrepFlags::line_offsets()] {
+        let engine = GrepEngine::new_literal(b"needle", GrepOptions::default().with_flags(flags));
+        let mut sink = SliceSink::new(data);
+        engine.search_slice(data, &mut sink).unwrap();
+        assert_eq!(sink.matches, vec![&b"needle"[..]; 3], "{flags:?}");
+    }
+}
+
+#[test]
+fn case_insensitive_slices_keep_the_input_case() {
+    let data = b"Needle NEEDLE needle";
+    let engine = GrepEngine::new_regex("(?i)needle", GrepOptions::default()).unwrap();
+    let mut sink = SliceSink::new(data);
+    engine.search_slice(data, &mut sink).unwrap();
+    assert_eq!(sink.matches, vec![&b"Needle"[..], b"NEEDLE", b"needle"]);
+    assert_ne!(sink.matches[0], b"needle");
+}
+
+#[test]
+fn byte_range_offsets_still_index_the_haystack() {
+    let data = b"ab xab ab";
+    let opts = GrepOptions {
+        byte_range: Some(ByteRange::Tail(5)),
+        ..Default::default()
+    };
+    let mut sink = SliceSink::new(data);
+    GrepEngine::new_literal(b"ab", opts)
+        .search_slice(data, &mut sink)
+        .unwrap();
+    assert_eq!(sink.matches, vec![&b"ab"[..], b"ab"]);
+    let start = sink.matches[0].as_ptr() as usize - data.as_ptr() as usize;
+    assert_eq!(start, 4);
+}
@@ -0,0 +1,77 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink, VecSink};
+use simd_grep::io::output::SortingSink;
+
+fn feed(sink: &mut impl MatchSink, matches: &[(u32, u64)]) {
+    for &(file_id, off) in matches {
+        sink.on_match(off, 1, 0, file_id);
+    }
+}
+
+fn pairs(sink: &VecSink) -> Vec<(u32, u64)> {
+    sink.file_ids
+        .iter()
+        .copied()
+        .zip(sink.offs.iter().copied())
+        .collect()
+}
+
+const SHUFFLED: [(u32, u64); 6] = [(2, 5), (0, 9), (1, 3), (0, 1), (2, 0), (1, 3)];
+
+#[test]
+fn out_of_order_matches_are_emitted_sorted() {
+    let mut sink = SortingSink::new(VecSink::default()).flush_on_finish(false);
+    feed(&mut sink, &SHUFFLED);
+    sink.on_finish(0, 6);
+    let inner = sink.finish();
+    assert_eq!(
+        pairs(&inner),
+        vec![(0, 1), (0, 9), (1, 3), (1, 3), (2, 0), (2, 5)]
+    );
+}
+
+#[test]
+fn on_finish_flushes_by_default() {
+    let mut sink = SortingSink::new(VecSink::default());
+    feed(&mut sink, &SHUFFLED[..3]);
+    sink.on_finish(0, 3);
+    feed(&mut sink, &SHUFFLED[3..]);
+    let inner = sink.finish();
+    assert_eq!(
+        pairs(&inner),
+        vec![(0, 9), (1, 3), (2, 5), (0, 1), (1, 3), (2, 0)]
+    );
+}
+
+#[test]
+fn cap_flushes_sorted_batches() {
+    let mut sink = SortingSink::new(VecSink::default()).with_cap(4);
+    feed(&mut sink, &SHUFFLED);
+    let inner = sink.finish();
+    assert_eq!(
+        pairs(&inner),
+        vec![(0, 1), (0, 9), (1, 3), (2, 5), (1, 3), (2, 0)]
+    );
+}
+
+#[test]
+fn lines_are_forwarded() {
+    #[derive(Default)]
+    struct Lines(Vec<(u64, Vec<u8>)>);
+    impl MatchSink for Lines {
+        fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+        fn on_match_line(&mut self, m: &Match, line: &[u8]) {
+            self.0.push((m.off, line.to_vec()));
+        }
+    }
+
+    let opts = GrepOptions::default().with_flags(GrepFlags::line_numbers());
+    let mut sink = SortingSink::new(Lines::default());
+    GrepEngine::new_literal(b"ab", opts)
+        .search(&mut &b"ab\nx ab"[..], &mut sink)
+        .unwrap();
+    assert_eq!(
+        sink.finish().0,
+        vec![(0, b"ab".to_vec()), (5, b"x ab".to_vec())]
+    );
+}
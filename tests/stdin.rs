@@ -0,0 +1,33 @@
+mod common;
+
+use std::io::Cursor;
+
+use common::Trickle;
+use simd_grep::engine::{GrepOptions, VecSink};
+use simd_grep::io::stdin::search_buffered;
+
+#[test]
+fn buffered_path_finds_matches_in_fake_stdin() {
+    let data = b"first needle\nsecond line\nneedle again\n";
+    for chunk_bytes in [1, 4, 16, 1 << 20] {
+        let opts = GrepOptions {
+            chunk_bytes,
+            ..Default::default()
+        };
+        let mut sink = VecSink::default();
+        search_buffered(Cursor::new(data), b"needle", opts, &mut sink).unwrap();
+        assert_eq!(sink.offs, vec![6, 25], "chunk_bytes={chunk_bytes}");
+    }
+}
+
+#[test]
+fn buffered_path_handles_fragmented_reads() {
+    let data = b"aXbXcXneedleXneedle";
+    let opts = GrepOptions {
+        chunk_bytes: 5,
+        ..Default::default()
+    };
+    let mut sink = VecSink::default();
+    search_buffered(Trickle::new(data, 1), b"needle", opts, &mut sink).unwrap();
+    assert_eq!(sink.offs, vec![6, 13]);
+}
@@ -0,0 +1,76 @@
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, VecSink};
+use simd_grep::io::chunker::Chunker;
+use simd_grep::io::testutil::ChunkedReader;
+
+// Every needle ends at the very last byte of the stream.
+const DATA: &[u8] = b"abc\nxyzNEEDLE\nqNEEDLE";
+const NEEDLE: &[u8] = b"NEEDLE";
+
+#[test]
+fn chunker_hands_out_the_final_match_once() {
+    let overlap = NEEDLE.len() - 1;
+    for chunk_size in overlap + 1..=DATA.len() + 1 {
+        for step in [1, 2, 5, DATA.len()] {
+            let mut reader = ChunkedReader::new(DATA, step);
+            let mut chunker = Chunker::new(&mut reader, chunk_size, overlap);
+            let mut ends = Vec::new();
+            let mut prev_end = 0;
+            while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+                for (i, _) in chunk.windows(NEEDLE.len()).enumerate() {
+                    let end = base + (i + NEEDLE.len()) as u64;
+                    if &chunk[i..i + NEEDLE.len()] == NEEDLE && end > prev_end {
+                        ends.push(end);
+                    }
+                }
+                prev_end = base + chunk.len() as u64;
+            }
+            assert_eq!(
+                ends,
+                vec![13, DATA.len() as u64],
+                "chunk_size {chunk_size}, step {step}"
+            );
+        }
+    }
+}
+
+#[test]
+fn engine_reports_the_match_at_stream_end() {
+    for chunk_bytes in NEEDLE.len()..=DATA.len() + 1 {
+        for flags in [GrepFlags::empty(), GrepFlags::LINE_NUMBER] {
+            for step in [1, 3, DATA.len()] {
+                let eng = GrepEngine::new_literal(
+                    NEEDLE,
+                    GrepOptions {
+                        flags,
+                        chunk_bytes,
+                        ..Default::default()
+                    },
+                );
+                let mut sink = VecSink::default();
+                eng.search(&mut ChunkedReader::new(DATA, step), &mut sink)
+                    .unwrap();
+                assert_eq!(
+                    sink.offs,
+                    vec![7, 15],
+                    "chunk_bytes {chunk_bytes}, step {step}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn single_byte_needle_at_stream_end() {
+    for chunk_bytes in 1..=4 {
+        let eng = GrepEngine::new_literal(
+            b"q",
+            GrepOptions {
+                chunk_bytes,
+                ..Default::default()
+            },
+        );
+        let mut sink = VecSink::default();
+        eng.search(&mut &b"aaq"[..], &mut sink).unwrap();
+        assert_eq!(sink.offs, vec![2]);
+    }
+}
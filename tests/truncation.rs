@@ -0,0 +1,82 @@
+mod common;
+
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+use common::TempDir;
+use simd_grep::engine::{GrepEngine, GrepOptions, Match, MatchSink, TruncationPolicy, VecSink};
+use simd_grep::io::fs::TruncationGuard;
+
+/// Truncates and rewrites the searched file when it sees the first match, as a
+/// log rotation might.
+struct Rotate<'a> {
+    path: &'a Path,
+    offs: Vec<u64>,
+}
+
+impl MatchSink for Rotate<'_> {
+    fn on_match(&mut self, _off: u64, _len: u32, _line_no: u32, _file_id: u32) {}
+
+    fn on_match_info(&mut self, m: &Match) {
+        if self.offs.is_empty() {
+            fs::write(self.path, b"needle\n").unwrap();
+        }
+        self.offs.push(m.off);
+    }
+}
+
+fn run(policy: TruncationPolicy) -> (io::Result<()>, Vec<u64>) {
+    let dir = TempDir::new("truncation");
+    let mut data = b"needle".to_vec();
+    data.resize(64, b'.');
+    let path = dir.write("log", &data);
+
+    let mut reader = TruncationGuard::new(File::open(&path).unwrap(), policy);
+    let opts = GrepOptions {
+        chunk_bytes: 8,
+        on_truncation: policy,
+        ..Default::default()
+    };
+    let mut sink = Rotate {
+        path: &path,
+        offs: Vec::new(),
+    };
+    let res = GrepEngine::new_literal(b"needle", opts).search(&mut reader, &mut sink);
+    (res, sink.offs)
+}
+
+#[test]
+fn stop_policy_ends_search_at_truncation() {
+    let (res, offs) = run(TruncationPolicy::Stop);
+    res.unwrap();
+    assert_eq!(offs, vec![0]);
+}
+
+#[test]
+fn error_policy_reports_truncation() {
+    let (res, offs) = run(TruncationPolicy::Error);
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert!(err.to_string().contains("truncated"));
+    assert_eq!(offs, vec![0]);
+}
+
+#[test]
+fn continue_policy_rereads_the_rewritten_file() {
+    let (res, offs) = run(TruncationPolicy::Continue);
+    res.unwrap();
+    // The rewritten "needle" is at file offset 0, after the 8 bytes read before.
+    assert_eq!(offs, vec![0, 8]);
+}
+
+#[test]
+fn untruncated_eof_is_not_reported() {
+    let data = b"needle..........needle..........";
+    let mut reader = TruncationGuard::new(io::Cursor::new(data), TruncationPolicy::Error);
+    let mut sink = VecSink::default();
+    GrepEngine::new_literal(b"needle", GrepOptions::default())
+        .search(&mut reader, &mut sink)
+        .unwrap();
+    assert_eq!(sink.offs, vec![0, 16]);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use std::fs::File;
+use std::path::PathBuf;
+
+use common::TempDir;
+
+use simd_grep::engine::{GrepEngine, GrepFlags, GrepOptions, Match, MatchSink};
+use simd_grep::io::output::{ColorChoice, TtySink};
+
+const DATA: &[u8] = b"one ab\nnone\nab xab\n";
+
+fn render(color: ColorChoice, is_tty: bool) -> String {
+    let opts = GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER);
+    let engine = GrepEngine::new_literal(b"ab", opts);
+    let mut sink = TtySink::new(Vec::new(), vec![PathBuf::from("f.txt")], color, is_tty);
+    engine.search(&mut &DATA[..], &mut sink).unwrap();
+    String::from_utf8(sink.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn auto_colors_only_terminals() {
+    let plain = render(ColorChoice::Auto, false);
+    assert_eq!(plain, "f.txt:1:one ab\nf.txt:3:ab xab\n");
+    assert!(!plain.contains('\x1b'));
+
+    let colored = render(ColorChoice::Auto, true);
+    assert_eq!(
+        colored,
+        "\x1b[35mf.txt\x1b[0m:\x1b[32m1\x1b[0m:one \x1b[1;31mab\x1b[0m\n\
+         \x1b[35mf.txt\x1b[0m:\x1b[32m3\x1b[0m:\x1b[1;31mab\x1b[0m x\x1b[1;31mab\x1b[0m\n"
+    );
+}
+
+#[test]
+fn explicit_choices_ignore_the_terminal() {
+    assert_eq!(
+        render(ColorChoice::Never, true),
+        render(ColorChoice::Auto, false)
+    );
+    assert_eq!(
+        render(ColorChoice::Always, false),
+        render(ColorChoice::Auto, true)
+    );
+}
+
+#[test]
+fn detect_asks_the_writer() {
+    // A file is never a terminal.
+    let dir = TempDir::new("tty");
+    let file = File::create(dir.path().join("out")).unwrap();
+    let sink = TtySink::detect(file, vec![], ColorChoice::Auto);
+    assert!(!sink.colored());
+    let file = File::create(dir.path().join("out")).unwrap();
+    assert!(TtySink::detect(file, vec![], ColorChoice::Always).colored());
+}
+
+#[test]
+fn overlapping_matches_are_highlighted_once() {
+    let engine = GrepEngine::new_literal(
+        b"aa",
+        GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER),
+    );
+    let mut sink = TtySink::new(Vec::new(), vec![], ColorChoice::Always, false);
+    engine.search_slice(b"xaaay", &mut sink).unwrap();
+    let out = String::from_utf8(sink.finish().unwrap()).unwrap();
+    assert_eq!(out, "\x1b[32m1\x1b[0m:x\x1b[1;31maaa\x1b[0my\n");
+}
+
+const BLUE: &str = "\x1b[1;34m";
+const GREEN: &str = "\x1b[1;32m";
+
+fn render_multi(needles: &[&str], palette: &[&str], data: &[u8]) -> String {
+    let engine = GrepEngine::new_multi_all(
+        needles,
+        GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER),
+    );
+    let palette = palette.iter().map(|c| c.as_bytes().to_vec()).collect();
+    let mut sink =
+        TtySink::new(Vec::new(), vec![], ColorChoice::Always, false).with_palette(palette);
+    engine.search_slice(data, &mut sink).unwrap();
+    String::from_utf8(sink.finish().unwrap()).unwrap()
+}
+
+#[test]
+fn patterns_get_their_palette_colors() {
+    let out = render_multi(&["ab", "cd"], &[BLUE, GREEN], b"ab cd ab");
+    assert_eq!(
+        out,
+        format!("\x1b[32m1\x1b[0m:{BLUE}ab\x1b[0m {GREEN}cd\x1b[0m {BLUE}ab\x1b[0m\n")
+    );
+}
+
+#[test]
+fn palette_cycles_over_extra_patterns() {
+    let out = render_multi(&["a", "b", "c"], &[BLUE, GREEN], b"abc");
+    assert_eq!(
+        out,
+        format!("\x1b[32m1\x1b[0m:{BLUE}a\x1b[0m{GREEN}b\x1b[0m{BLUE}c\x1b[0m\n")
+    );
+}
+
+#[test]
+fn overlapping_patterns_take_the_first_color() {
+    let first_starts = format!("\x1b[32m1\x1b[0m:x{BLUE}abcd\x1b[0m\n");
+    assert_eq!(
+        render_multi(&["abc", "bcd"], &[BLUE, GREEN], b"xabcd"),
+        first_starts
+    );
+    assert_eq!(
+        render_multi(&["bcd", "abc"], &[GREEN, BLUE], b"xabcd"),
+        first_starts
+    );
+    // Starting together, the lower pattern index wins.
+    let out = format!("\x1b[32m1\x1b[0m:x{GREEN}abc\x1b[0m\n");
+    assert_eq!(render_multi(&["abc", "ab"], &[GREEN, BLUE], b"xabc"), out);
+    assert_eq!(render_multi(&["ab", "abc"], &[GREEN, BLUE], b"xabc"), out);
+}
+
+#[test]
+fn default_palette_is_one_color() {
+    let engine = GrepEngine::new_multi(
+        &["ab", "cd"],
+        GrepOptions::default().with_flags(GrepFlags::LINE_NUMBER),
+    );
+    let mut sink = TtySink::new(Vec::new(), vec![], ColorChoice::Always, false);
+    engine.search_slice(b"ab cd", &mut sink).unwrap();
+    let out = String::from_utf8(sink.finish().unwrap()).unwrap();
+    assert_eq!(
+        out,
+        "\x1b[32m1\x1b[0m:\x1b[1;31mab\x1b[0m \x1b[1;31mcd\x1b[0m\n"
+    );
+}
+
+#[test]
+fn dropping_the_sink_prints_the_pending_line() {
+    let mut out = Vec::new();
+    {
+        // Matches without `on_finish`, as behind a wrapper that swallows it.
+        let mut sink = TtySink::new(&mut out, vec![], ColorChoice::Never, false);
+        let m = Match {
+            off: 4,
+            match_off: 4,
+            len: 2,
+            line_no: 1,
+            col: 5,
+            file_id: 0,
+            pattern: 0,
+            bytes_read: 6,
+        };
+        sink.on_match_line(&m, b"one ab");
+    }
+    assert_eq!(out, b"1:one ab\n");
+}
@@ -0,0 +1,45 @@
+use simd_grep::io::chunker::Utf8Chunker;
+
+fn chunks(data: &[u8], chunk_size: usize, overlap: usize) -> Vec<(u64, Vec<u8>)> {
+    let mut reader = data;
+    let mut chunker = Utf8Chunker::new(&mut reader, chunk_size, overlap);
+    let mut out = Vec::new();
+    while let Some((base, chunk)) = chunker.next_chunk().unwrap() {
+        out.push((base, chunk.to_vec()));
+    }
+    out
+}
+
+#[test]
+fn chunks_never_end_mid_codepoint() {
+    let text = "aé€😀b日本語🎉 ñ";
+    let data = text.as_bytes();
+    for chunk_size in 1..=9 {
+        for overlap in 0..chunk_size.min(4) {
+            let got = chunks(data, chunk_size, overlap);
+            let mut covered = 0u64;
+            for (base, chunk) in &got {
+                let end = *base as usize + chunk.len();
+                assert_eq!(&data[*base as usize..end], &chunk[..]);
+                assert!(
+                    text.is_char_boundary(end),
+                    "cs={} ov={} end={}",
+                    chunk_size,
+                    overlap,
+                    end
+                );
+                assert!(*base <= covered, "gap before {}", base);
+                covered = covered.max(end as u64);
+            }
+            assert_eq!(covered, data.len() as u64);
+        }
+    }
+}
+
+#[test]
+fn truncated_sequence_at_eof_is_kept() {
+    let data = b"ab\xe2\x82";
+    let got = chunks(data, 3, 0);
+    let last = got.last().unwrap();
+    assert_eq!(last.0 as usize + last.1.len(), data.len());
+}
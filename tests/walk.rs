@@ -0,0 +1,174 @@
+mod common;
+
+use common::TempDir;
+use simd_grep::engine::{GrepOptions, PreparedPattern, VecSink};
+use simd_grep::io::walk::{WalkOptions, collect_files, count_dir, files_with_matches, search_dir};
+
+fn tree() -> TempDir {
+    let dir = TempDir::new("walk");
+    dir.write("a.txt", b"needle needle");
+    dir.write("sub/b.txt", b"needle\nneedle\nneedle\n");
+    dir.write("sub/c.txt", b"nothing here");
+    dir.write("sub/deeper/d.txt", b"one needle");
+    dir
+}
+
+#[test]
+fn walks_in_sorted_depth_first_order() {
+    let dir = tree();
+    let files = collect_files(dir.path(), &WalkOptions::default()).unwrap();
+    let rel: Vec<_> = files
+        .iter()
+        .map(|p| p.strip_prefix(dir.path()).unwrap().to_owned())
+        .collect();
+    let expected: Vec<std::path::PathBuf> = ["a.txt", "sub/b.txt", "sub/c.txt", "sub/deeper/d.txt"]
+        .iter()
+        .map(Into::into)
+        .collect();
+    assert_eq!(rel, expected);
+}
+
+#[test]
+fn search_dir_assigns_file_ids_in_walk_order() {
+    let dir = tree();
+    let pattern = PreparedPattern::new(b"needle");
+    let mut sink = VecSink::default();
+    let files = search_dir(
+        dir.path(),
+        &pattern,
+        &GrepOptions::default(),
+        &WalkOptions::default(),
+        &mut sink,
+    )
+    .unwrap();
+    assert_eq!(files.len(), 4);
+    assert_eq!(sink.file_ids, vec![0, 0, 1, 1, 1, 3]);
+}
+
+#[test]
+fn count_summary_omits_zero_match_files_by_default() {
+    let dir = tree();
+    let pattern = PreparedPattern::new(b"needle");
+    let opts = GrepOptions::default();
+
+    let summary = count_dir(dir.path(), &pattern, &opts, &WalkOptions::default(), false).unwrap();
+    let got: Vec<_> = summary
+        .files
+        .iter()
+        .map(|(p, n)| {
+            (
+                p.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_owned(),
+                *n,
+            )
+        })
+        .collect();
+    assert_eq!(
+        got,
+        vec![
+            ("a.txt".to_owned(), 2),
+            ("sub/b.txt".to_owned(), 3),
+            ("sub/deeper/d.txt".to_owned(), 1)
+        ]
+    );
+    assert_eq!(summary.total, 6);
+
+    let all = count_dir(dir.path(), &pattern, &opts, &WalkOptions::default(), true).unwrap();
+    assert_eq!(all.files.len(), 4);
+    assert_eq!(all.files[2].1, 0);
+    assert_eq!(all.total, 6);
+}
+
+fn rel_files(dir: &TempDir, walk_opts: &WalkOptions) -> Vec<String> {
+    collect_files(dir.path(), walk_opts)
+        .unwrap()
+        .iter()
+        .map(|p| {
+            p.strip_prefix(dir.path())
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn max_depth_cuts_off_recursion() {
+    let dir = tree();
+    let depth = |d| WalkOptions {
+        max_depth: Some(d),
+        ..Default::default()
+    };
+    assert_eq!(rel_files(&dir, &depth(0)), vec!["a.txt"]);
+    assert_eq!(
+        rel_files(&dir, &depth(1)),
+        vec!["a.txt", "sub/b.txt", "sub/c.txt"]
+    );
+    assert_eq!(rel_files(&dir, &depth(2)).len(), 4);
+}
+
+#[test]
+fn oversized_files_are_skipped() {
+    let dir = tree();
+    dir.write("big.bin", &vec![b'n'; 4096]);
+    let walk_opts = WalkOptions {
+        max_filesize: Some(1024),
+        ..Default::default()
+    };
+    let files = rel_files(&dir, &walk_opts);
+    assert!(!files.contains(&"big.bin".to_owned()));
+    assert_eq!(files.len(), 4);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_are_followed_only_on_request() {
+    let dir = tree();
+    std::os::unix::fs::symlink(dir.path().join("sub"), dir.path().join("link")).unwrap();
+    // A cycle back to the root must not recurse forever.
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("sub/loop")).unwrap();
+
+    assert_eq!(rel_files(&dir, &WalkOptions::default()).len(), 4);
+
+    let follow = WalkOptions {
+        follow_symlinks: true,
+        ..Default::default()
+    };
+    // `link` sorts before `sub`, so the shared directory is reached through it
+    // first and not listed again.
+    let files = rel_files(&dir, &follow);
+    assert_eq!(
+        files,
+        vec!["a.txt", "link/b.txt", "link/c.txt", "link/deeper/d.txt"]
+    );
+}
+
+#[test]
+fn files_with_and_without_matches() {
+    let dir = tree();
+    let pattern = PreparedPattern::new(b"needle");
+    let opts = GrepOptions::default();
+    let walk_opts = WalkOptions::default();
+    let rel = |files: Vec<std::path::PathBuf>| -> Vec<String> {
+        files
+            .iter()
+            .map(|p| {
+                p.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect()
+    };
+
+    let with = files_with_matches(dir.path(), &pattern, &opts, &walk_opts, false).unwrap();
+    assert_eq!(rel(with), vec!["a.txt", "sub/b.txt", "sub/deeper/d.txt"]);
+
+    let without = files_with_matches(dir.path(), &pattern, &opts, &walk_opts, true).unwrap();
+    assert_eq!(rel(without), vec!["sub/c.txt"]);
+}